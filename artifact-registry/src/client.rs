@@ -1,5 +1,5 @@
 use crate::grpc::apiv1::artifact_registry_client::Client as ArtifactRegistryGrpcClient;
-use google_cloud_gax::conn::{ConnectionManager, ConnectionOptions, Environment, Error};
+use google_cloud_gax::conn::{CompressionEncoding, ConnectionManager, ConnectionOptions, Environment, Error};
 use google_cloud_token::{NopeTokenSourceProvider, TokenSourceProvider};
 use std::ops::{Deref, DerefMut};
 use std::time::Duration;
@@ -15,6 +15,11 @@ pub struct ClientConfig {
     pub token_source_provider: Box<dyn TokenSourceProvider>,
     pub timeout: Option<Duration>,
     pub connect_timeout: Option<Duration>,
+    /// The project billed for the quota of the requests. The `quota_project_id` of the credentials is used by default.
+    pub quota_project: Option<String>,
+    /// compression enables the compression of the requests and the responses, such as gzip.
+    /// Disabled by default.
+    pub compression: Option<CompressionEncoding>,
 }
 
 #[cfg(feature = "auth")]
@@ -40,6 +45,7 @@ impl ClientConfig {
     }
 
     async fn with_token_source(mut self, ts: google_cloud_auth::token::DefaultTokenSourceProvider) -> Self {
+        self.quota_project = self.quota_project.or(ts.quota_project_id());
         self.token_source_provider = Box::new(ts);
         self
     }
@@ -56,6 +62,8 @@ impl Default for ClientConfig {
             token_source_provider: Box::new(NopeTokenSourceProvider {}),
             timeout: Some(Duration::from_secs(30)),
             connect_timeout: Some(Duration::from_secs(30)),
+            quota_project: None,
+            compression: None,
         }
     }
 }
@@ -70,6 +78,9 @@ impl Client {
         let conn_options = ConnectionOptions {
             timeout: config.timeout,
             connect_timeout: config.connect_timeout,
+            quota_project: config.quota_project,
+            compression: config.compression,
+            ..Default::default()
         };
         let conn_pool = ConnectionManager::new(
             1,
@@ -79,11 +90,15 @@ impl Client {
             &conn_options,
         )
         .await?;
-        let conn = conn_pool.conn();
+        let client = ArtifactRegistryClient::new(conn_pool.conn());
+        let client = match conn_pool.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        };
         let lro_client = OperationsClient::new(conn_pool.conn()).await.unwrap();
 
         Ok(Self {
-            artifact_registry_client: ArtifactRegistryGrpcClient::new(ArtifactRegistryClient::new(conn), lro_client),
+            artifact_registry_client: ArtifactRegistryGrpcClient::new(client, lro_client),
         })
     }
 }
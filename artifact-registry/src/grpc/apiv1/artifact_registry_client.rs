@@ -27,6 +27,9 @@ fn default_setting() -> RetrySetting {
         factor: 1u64,
         take: 20,
         codes: vec![Code::Unavailable, Code::Unknown],
+        jitter: false,
+        max_elapsed: None,
+        timeout: None,
     }
 }
 
@@ -1,6 +1,6 @@
 use backon::{ExponentialBuilder, Retryable};
 use core::time::Duration;
-use google_cloud_gax::conn::{ConnectionOptions, Environment};
+use google_cloud_gax::conn::{CompressionEncoding, ConnectionOptions, Environment, KeepAliveConfig, RpcInterceptor};
 use google_cloud_gax::retry::RetrySetting;
 use google_cloud_googleapis::cloud::bigquery::storage::v1::{
     read_session, CreateReadSessionRequest, DataFormat, ReadSession,
@@ -12,6 +12,8 @@ use std::fmt::Debug;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use time::OffsetDateTime;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 use crate::grpc::apiv1::conn_pool::ConnectionManager;
 use crate::http::bigquery_client::BigqueryClient;
@@ -22,25 +24,33 @@ use crate::http::bigquery_routine_client::BigqueryRoutineClient;
 use crate::http::bigquery_row_access_policy_client::BigqueryRowAccessPolicyClient;
 use crate::http::bigquery_table_client::BigqueryTableClient;
 use crate::http::bigquery_tabledata_client::BigqueryTabledataClient;
+use crate::http::error::RETRYABLE_REASONS;
 use crate::http::job::get_query_results::GetQueryResultsRequest;
 use crate::http::job::query::QueryRequest;
-use crate::http::job::{is_script, is_select_query, JobConfiguration, JobReference, JobStatistics, JobType};
-use crate::http::table::TableReference;
-use crate::query::{QueryOption, QueryResult};
+use crate::http::job::{
+    is_script, is_select_query, Job, JobConfiguration, JobConfigurationExtract, JobConfigurationExtractSource,
+    JobConfigurationLoad, JobConfigurationQuery, JobReference, JobState, JobStatistics, JobStatisticsExtract, JobType,
+};
+use crate::http::query_parameter::QueryParameters;
+use crate::http::routine::{Routine, RoutineReference};
+use crate::http::table::{TableReference, TableSchema};
+use crate::http::tabledata::list::Tuple;
+use crate::job::{ExtractOptions, WaitConfig};
+use crate::query::{QueryCostEstimate, QueryJobConfig, QueryOption, QueryResult};
 use crate::storage;
-use crate::{http, query};
+use crate::{http, job, query};
 
 #[cfg(feature = "auth")]
 pub use google_cloud_auth;
 
-const JOB_RETRY_REASONS: [&str; 3] = ["backendError", "rateLimitExceeded", "internalError"];
-
 #[derive(Debug)]
 pub struct HttpClientConfig {
     client: Option<reqwest_middleware::ClientWithMiddleware>,
     bigquery_endpoint: Cow<'static, str>,
     token_source_provider: Box<dyn TokenSourceProvider>,
     debug: bool,
+    quota_project: Option<String>,
+    user_agent: Option<String>,
 }
 
 impl HttpClientConfig {
@@ -50,6 +60,8 @@ impl HttpClientConfig {
             bigquery_endpoint: http_addr.into(),
             token_source_provider: Box::new(EmptyTokenSourceProvider {}),
             debug: false,
+            quota_project: None,
+            user_agent: None,
         }
     }
 
@@ -59,6 +71,8 @@ impl HttpClientConfig {
             bigquery_endpoint: "https://bigquery.googleapis.com".into(),
             token_source_provider: http_token_source_provider,
             debug: false,
+            quota_project: None,
+            user_agent: None,
         }
     }
 
@@ -67,8 +81,10 @@ impl HttpClientConfig {
         self
     }
 
-    pub fn with_http_client(mut self, value: reqwest_middleware::ClientWithMiddleware) -> Self {
-        self.client = Some(value);
+    /// Uses the given HTTP client for all the REST requests instead of the default one.
+    /// A `reqwest::Client` configured with a proxy, timeouts or custom root certificates can be passed as is.
+    pub fn with_http_client(mut self, value: impl Into<reqwest_middleware::ClientWithMiddleware>) -> Self {
+        self.client = Some(value.into());
         self
     }
 
@@ -77,15 +93,31 @@ impl HttpClientConfig {
         self
     }
 
+    /// Sets the project billed for the quota of the requests, sent as the `x-goog-user-project` header.
+    pub fn with_quota_project(mut self, value: impl Into<String>) -> Self {
+        self.quota_project = Some(value.into());
+        self
+    }
+
+    /// Sets the token of the application like `my-service/1.0` sent in the `user-agent` and `x-goog-api-client` headers.
+    pub fn with_user_agent(mut self, value: impl Into<String>) -> Self {
+        self.user_agent = Some(value.into());
+        self
+    }
+
     pub fn create_client(self) -> Arc<BigqueryClient> {
         let ts = self.token_source_provider.token_source();
-        Arc::new(BigqueryClient::new(
-            ts,
-            self.bigquery_endpoint.as_ref(),
-            self.client
-                .unwrap_or_else(|| reqwest_middleware::ClientBuilder::new(reqwest::Client::default()).build()),
-            self.debug,
-        ))
+        Arc::new(
+            BigqueryClient::new(
+                ts,
+                self.bigquery_endpoint.as_ref(),
+                self.client
+                    .unwrap_or_else(|| reqwest_middleware::ClientBuilder::new(reqwest::Client::default()).build()),
+                self.debug,
+            )
+            .with_quota_project(self.quota_project)
+            .with_user_agent(self.user_agent),
+        )
     }
 }
 
@@ -123,6 +155,7 @@ pub struct ClientConfig {
     environment: Environment,
     streaming_read_config: ChannelConfig,
     streaming_write_config: StreamingWriteConfig,
+    maximum_bytes_billed: Option<i64>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -148,6 +181,9 @@ pub struct ChannelConfig {
     num_channels: usize,
     connect_timeout: Option<Duration>,
     timeout: Option<Duration>,
+    interceptors: Vec<Arc<dyn RpcInterceptor>>,
+    keep_alive: KeepAliveConfig,
+    compression: Option<CompressionEncoding>,
 }
 
 impl ChannelConfig {
@@ -163,10 +199,28 @@ impl ChannelConfig {
         self.timeout = Some(value);
         self
     }
+    /// Adds the interceptor called on every RPC, e.g. to add custom metadata or to collect metrics.
+    pub fn with_interceptor(mut self, value: Arc<dyn RpcInterceptor>) -> Self {
+        self.interceptors.push(value);
+        self
+    }
+    /// Configures the HTTP/2 keepalive pings. Pings every 30 seconds by default.
+    pub fn with_keep_alive(mut self, value: KeepAliveConfig) -> Self {
+        self.keep_alive = value;
+        self
+    }
+    /// Compresses the requests and the responses with the given encoding, such as gzip.
+    /// Disabled by default.
+    pub fn with_compression(mut self, value: CompressionEncoding) -> Self {
+        self.compression = Some(value);
+        self
+    }
 
     async fn into_connection_manager(
         self,
         environment: &Environment,
+        quota_project: Option<String>,
+        user_agent: Option<String>,
     ) -> Result<ConnectionManager, google_cloud_gax::conn::Error> {
         ConnectionManager::new(
             self.num_channels,
@@ -174,6 +228,11 @@ impl ChannelConfig {
             &ConnectionOptions {
                 timeout: self.timeout,
                 connect_timeout: self.connect_timeout,
+                compression: self.compression,
+                interceptors: self.interceptors,
+                quota_project,
+                keep_alive: self.keep_alive,
+                user_agent,
             },
         )
         .await
@@ -186,6 +245,9 @@ impl Default for ChannelConfig {
             num_channels: 4,
             connect_timeout: Some(Duration::from_secs(30)),
             timeout: None,
+            interceptors: vec![],
+            keep_alive: KeepAliveConfig::default(),
+            compression: None,
         }
     }
 }
@@ -216,6 +278,7 @@ impl ClientConfig {
             environment: Environment::Emulator(grpc_host.to_string()),
             streaming_read_config: ChannelConfig::default(),
             streaming_write_config: StreamingWriteConfig::default(),
+            maximum_bytes_billed: None,
         }
     }
 
@@ -228,6 +291,7 @@ impl ClientConfig {
             environment: Environment::GoogleCloud(grpc_token_source_provider),
             streaming_read_config: ChannelConfig::default(),
             streaming_write_config: StreamingWriteConfig::default(),
+            maximum_bytes_billed: None,
         }
     }
 
@@ -246,8 +310,10 @@ impl ClientConfig {
         self
     }
 
-    pub fn with_http_client(mut self, value: reqwest_middleware::ClientWithMiddleware) -> Self {
-        self.http.client = Some(value);
+    /// Uses the given HTTP client for all the REST requests instead of the default one.
+    /// A `reqwest::Client` configured with a proxy, timeouts or custom root certificates can be passed as is.
+    pub fn with_http_client(mut self, value: impl Into<reqwest_middleware::ClientWithMiddleware>) -> Self {
+        self.http.client = Some(value.into());
         self
     }
 
@@ -255,14 +321,37 @@ impl ClientConfig {
         self.http.bigquery_endpoint = value.into();
         self
     }
+
+    /// Sets the project billed for the quota of the requests, sent as the `x-goog-user-project` header.
+    /// The `quota_project_id` of the credentials is used by default.
+    pub fn with_quota_project(mut self, value: impl Into<String>) -> Self {
+        self.http.quota_project = Some(value.into());
+        self
+    }
+
+    /// Limits the bytes billed for the queries which don't set `maximum_bytes_billed` by themselves.
+    /// The queries which would bill more bytes fail without incurring a charge.
+    pub fn with_maximum_bytes_billed(mut self, value: i64) -> Self {
+        self.maximum_bytes_billed = Some(value);
+        self
+    }
+
+    /// Sets the token of the application like `my-service/1.0` sent in the `user-agent` and `x-goog-api-client` headers
+    /// of both the REST and the gRPC requests, so that the requests can be distinguished in the request logs.
+    pub fn with_user_agent(mut self, value: impl Into<String>) -> Self {
+        self.http.user_agent = Some(value.into());
+        self
+    }
 }
 
+use crate::http::dataset::list::{DatasetOverview, ListDatasetsRequest};
 use crate::http::job::get::GetJobRequest;
-use crate::http::job::list::ListJobsRequest;
+use crate::http::job::list::{JobOverview, ListJobsRequest};
+use crate::http::table::list::{ListTablesRequest, TableOverview};
+use google_cloud_gax::grpc::codegen::tokio_stream::Stream;
 
 use crate::grpc::apiv1::bigquery_client::StreamingReadClient;
 use crate::storage_write::stream::{buffered, committed, default, pending};
-use google_cloud_googleapis::cloud::bigquery::storage::v1::big_query_read_client::BigQueryReadClient;
 
 #[cfg(feature = "auth")]
 impl ClientConfig {
@@ -271,7 +360,9 @@ impl ClientConfig {
         let ts_grpc =
             google_cloud_auth::token::DefaultTokenSourceProvider::new(Self::bigquery_grpc_auth_config()).await?;
         let project_id = ts_grpc.project_id.clone();
-        let config = Self::new(Box::new(ts_http), Box::new(ts_grpc));
+        let quota_project = ts_grpc.quota_project_id();
+        let mut config = Self::new(Box::new(ts_http), Box::new(ts_grpc));
+        config.http.quota_project = quota_project;
         Ok((config, project_id))
     }
 
@@ -285,7 +376,9 @@ impl ClientConfig {
         )
         .await?;
         let project_id = ts_grpc.project_id.clone();
-        let config = Self::new(Box::new(ts_http), Box::new(ts_grpc));
+        let quota_project = ts_grpc.quota_project_id();
+        let mut config = Self::new(Box::new(ts_http), Box::new(ts_grpc));
+        config.http.quota_project = quota_project;
         Ok((config, project_id))
     }
 
@@ -310,6 +403,10 @@ pub enum QueryError {
     InvalidJobType(JobReference, String),
     #[error(transparent)]
     RunQuery(#[from] query::run::Error),
+    #[error("timed out waiting for query results: job={0:?}")]
+    Timeout(JobReference),
+    #[error(transparent)]
+    QueryParameter(#[from] http::query_parameter::Error),
 }
 
 #[derive(Clone)]
@@ -324,11 +421,14 @@ pub struct Client {
     streaming_read_conn_pool: Arc<ConnectionManager>,
     streaming_write_conn_pool: Arc<ConnectionManager>,
     streaming_write_max_insert_count: usize,
+    maximum_bytes_billed: Option<i64>,
 }
 
 impl Client {
     /// New client
     pub async fn new(config: ClientConfig) -> Result<Self, google_cloud_gax::conn::Error> {
+        let quota_project = config.http.quota_project.clone();
+        let user_agent = config.http.user_agent.clone();
         let client = config.http.create_client();
 
         Ok(Self {
@@ -342,17 +442,18 @@ impl Client {
             streaming_read_conn_pool: Arc::new(
                 config
                     .streaming_read_config
-                    .into_connection_manager(&config.environment)
+                    .into_connection_manager(&config.environment, quota_project.clone(), user_agent.clone())
                     .await?,
             ),
             streaming_write_conn_pool: Arc::new(
                 config
                     .streaming_write_config
                     .channel_config
-                    .into_connection_manager(&config.environment)
+                    .into_connection_manager(&config.environment, quota_project, user_agent)
                     .await?,
             ),
             streaming_write_max_insert_count: config.streaming_write_config.max_insert_count,
+            maximum_bytes_billed: config.maximum_bytes_billed,
         })
     }
 
@@ -398,6 +499,68 @@ impl Client {
         &self.model_client
     }
 
+    /// Lists the datasets one at a time while following the page tokens.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::dataset::list::ListDatasetsRequest;
+    /// use google_cloud_gax::grpc::codegen::tokio_stream::StreamExt;
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let request = ListDatasetsRequest {
+    ///         all: true,
+    ///         max_results: Some(100),
+    ///         ..Default::default()
+    ///     };
+    ///     let mut datasets = Box::pin(client.list_datasets_stream(project_id, Some(request)));
+    ///     while let Some(dataset) = datasets.next().await {
+    ///         println!("{}", dataset.unwrap().id);
+    ///     }
+    /// }
+    /// ```
+    pub fn list_datasets_stream<'a>(
+        &'a self,
+        project_id: &'a str,
+        req: Option<ListDatasetsRequest>,
+    ) -> impl Stream<Item = Result<DatasetOverview, http::error::Error>> + 'a {
+        self.dataset_client.list_stream(project_id, req)
+    }
+
+    /// Lists the tables of the dataset one at a time while following the page tokens.
+    pub fn list_tables_stream<'a>(
+        &'a self,
+        project_id: &'a str,
+        dataset_id: &'a str,
+        req: ListTablesRequest,
+    ) -> impl Stream<Item = Result<TableOverview, http::error::Error>> + 'a {
+        self.table_client.list_stream(project_id, dataset_id, req)
+    }
+
+    /// Lists the jobs one at a time while following the page tokens.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::job::list::ListJobsRequest;
+    /// use google_cloud_bigquery::http::job::JobState;
+    /// use google_cloud_gax::grpc::codegen::tokio_stream::StreamExt;
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let request = ListJobsRequest {
+    ///         state_filter: Some(vec![JobState::Running]),
+    ///         ..Default::default()
+    ///     };
+    ///     let mut jobs = Box::pin(client.list_jobs_stream(project_id, request));
+    ///     while let Some(job) = jobs.next().await {
+    ///         println!("{}", job.unwrap().id);
+    ///     }
+    /// }
+    /// ```
+    pub fn list_jobs_stream<'a>(
+        &'a self,
+        project_id: &'a str,
+        req: ListJobsRequest,
+    ) -> impl Stream<Item = Result<JobOverview, http::error::Error>> + 'a {
+        self.job_client.list_stream(project_id, req)
+    }
+
     /// Creates a new pending type storage writer for the specified table.
     /// https://cloud.google.com/bigquery/docs/write-api#pending_type
     /// ```
@@ -592,6 +755,7 @@ impl Client {
     where
         T: http::query::value::StructDecodable + storage::value::StructDecodable,
     {
+        let request = self.with_maximum_bytes_billed(request);
         let result = self.job_client.query(project_id, &request).await?;
         let (total_rows, page_token, rows, force_first_fetch) = if result.job_complete {
             (
@@ -610,16 +774,332 @@ impl Client {
             )
         };
 
+        self.new_query_iterator(
+            result.job_reference,
+            result.schema,
+            total_rows,
+            page_token,
+            rows,
+            force_first_fetch,
+            request,
+            option,
+        )
+        .await
+    }
+
+    /// Estimate the cost of the query by the dry run, which neither creates a job nor returns rows.
+    /// ```rust
+    /// use google_cloud_bigquery::http::job::query::QueryRequest;
+    /// use google_cloud_bigquery::client::Client;
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let request = QueryRequest {
+    ///         query: "SELECT * FROM dataset.table".to_string(),
+    ///         ..Default::default()
+    ///     };
+    ///     let estimate = client.estimate_query(project_id, request).await.unwrap();
+    ///     assert!(estimate.total_bytes_processed < 1_000_000_000);
+    /// }
+    /// ```
+    pub async fn estimate_query(
+        &self,
+        project_id: &str,
+        request: QueryRequest,
+    ) -> Result<QueryCostEstimate, http::error::Error> {
+        let mut job = Self::query_job(project_id, &request, QueryJobConfig::default());
+        job.configuration.dry_run = Some(true);
+        let result = self.job_client.create(&job).await?;
+        let statistics = result.statistics.and_then(|v| v.query).unwrap_or_default();
+        Ok(QueryCostEstimate {
+            total_bytes_processed: statistics.total_bytes_processed.unwrap_or_default(),
+            total_bytes_processed_accuracy: statistics.total_bytes_processed_accuracy,
+            schema: statistics.schema,
+        })
+    }
+
+    /// Estimate the bytes processed by the query by the dry run, which neither creates a job nor returns rows.
+    /// ```rust
+    /// use google_cloud_bigquery::http::job::query::QueryRequest;
+    /// use google_cloud_bigquery::client::Client;
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let request = QueryRequest {
+    ///         query: "SELECT * FROM dataset.table".to_string(),
+    ///         ..Default::default()
+    ///     };
+    ///     let bytes = client.estimate_query_bytes(project_id, request.clone()).await.unwrap();
+    ///     if bytes < 1_000_000_000 {
+    ///         let _ = client.query::<google_cloud_bigquery::query::row::Row>(project_id, request).await;
+    ///     }
+    /// }
+    /// ```
+    pub async fn estimate_query_bytes(
+        &self,
+        project_id: &str,
+        request: QueryRequest,
+    ) -> Result<i64, http::error::Error> {
+        self.estimate_query(project_id, request)
+            .await
+            .map(|v| v.total_bytes_processed)
+    }
+
+    /// Run query as a job created with the job level settings such as labels, priority and bytes billed cap.
+    /// The settings in `QueryJobConfig` take precedence over the same fields of the `QueryRequest`.
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use google_cloud_bigquery::http::job::Priority;
+    /// use google_cloud_bigquery::http::job::query::QueryRequest;
+    /// use google_cloud_bigquery::query::row::Row;
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::query::{QueryJobConfig, QueryOption};
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let request = QueryRequest {
+    ///         query: "SELECT * FROM dataset.table".to_string(),
+    ///         ..Default::default()
+    ///     };
+    ///     let labels = HashMap::from([("team".to_string(), "analytics".to_string())]);
+    ///     let config = QueryJobConfig::default()
+    ///         .with_labels(labels)
+    ///         .with_priority(Priority::Batch)
+    ///         .with_maximum_bytes_billed(1_000_000_000);
+    ///     let mut iter = client.query_with_job_config::<Row>(project_id, request, config, QueryOption::default()).await.unwrap();
+    ///     while let Some(row) = iter.next().await.unwrap() {
+    ///         let col1 = row.column::<String>(0);
+    ///     }
+    /// }
+    /// ```
+    pub async fn query_with_job_config<T>(
+        &self,
+        project_id: &str,
+        request: QueryRequest,
+        config: QueryJobConfig,
+        option: QueryOption,
+    ) -> Result<query::Iterator<T>, QueryError>
+    where
+        T: http::query::value::StructDecodable + storage::value::StructDecodable,
+    {
+        let request = self.with_maximum_bytes_billed(request);
+        let job = Self::query_job(project_id, &request, config);
+        let created = self.job_client.create(&job).await?;
+        let total_rows = self
+            .wait_for_query(&created.job_reference, option.retry, &request.timeout_ms)
+            .await?;
+        self.new_query_iterator(created.job_reference, None, total_rows, None, vec![], true, request, option)
+            .await
+    }
+
+    /// Read the results of the query job created elsewhere, without re-issuing the query.
+    /// `getQueryResults` is polled with exponential backoff until the job completes
+    /// or the timeout of the `WaitConfig` elapses, and the returned iterator follows the page tokens of the results.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use google_cloud_bigquery::http::job::JobReference;
+    /// use google_cloud_bigquery::job::WaitConfig;
+    /// use google_cloud_bigquery::query::row::Row;
+    /// use google_cloud_bigquery::client::Client;
+    ///
+    /// async fn run(client: &Client, job: &JobReference) {
+    ///     let config = WaitConfig::default().with_timeout(Duration::from_secs(600));
+    ///     let mut iter = client.wait_for_query_results::<Row>(job, config).await.unwrap();
+    ///     while let Some(row) = iter.next().await.unwrap() {
+    ///         let col1 = row.column::<String>(0);
+    ///     }
+    /// }
+    /// ```
+    pub async fn wait_for_query_results<T>(
+        &self,
+        job: &JobReference,
+        config: WaitConfig,
+    ) -> Result<query::Iterator<T>, QueryError>
+    where
+        T: http::query::value::StructDecodable + storage::value::StructDecodable,
+    {
+        let deadline = config.timeout.map(|v| tokio::time::Instant::now() + v);
+        let mut request = GetQueryResultsRequest {
+            location: job.location.clone(),
+            ..Default::default()
+        };
+        let mut interval = config.poll_interval;
+        let result = loop {
+            // The server holds the request until the job completes or timeout_ms elapses.
+            let started = tokio::time::Instant::now();
+            let remaining = deadline.map(|v| v.saturating_duration_since(started));
+            request.timeout_ms = Some(remaining.map_or(10_000, |v| v.as_millis().min(10_000) as i64));
+            tracing::debug!("waiting for query results {:?}", job);
+            match self
+                .job_client
+                .get_query_results(&job.project_id, &job.job_id, &request)
+                .await
+            {
+                Ok(result) if result.job_complete => break result,
+                Ok(_) | Err(http::error::Error::HttpClient(_)) => {}
+                Err(http::error::Error::Response(r)) if r.is_retryable(&RETRYABLE_REASONS) => {}
+                Err(e) => return Err(e.into()),
+            }
+            if remaining.is_some_and(|v| v.is_zero()) {
+                return Err(QueryError::Timeout(job.clone()));
+            }
+            // The requests answered without holding, such as the errors, are spaced by the backoff.
+            let mut wait = interval.saturating_sub(started.elapsed());
+            if let Some(deadline) = deadline {
+                wait = wait.min(deadline.saturating_duration_since(tokio::time::Instant::now()));
+            }
+            tokio::time::sleep(wait).await;
+            interval = config.next_interval(interval);
+        };
+        self.new_query_iterator(
+            result.job_reference,
+            result.schema,
+            result.total_rows,
+            result.page_token,
+            result.rows.unwrap_or_default(),
+            false,
+            QueryRequest::default(),
+            QueryOption::default(),
+        )
+        .await
+    }
+
+    /// Create a routine such as a SQL or JavaScript UDF and a stored procedure.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::routine::{Argument, Routine};
+    /// use google_cloud_bigquery::http::types::TypeKind;
+    ///
+    /// async fn run(client: &Client) {
+    ///     let routine = Routine::sql_function("project", "dataset", "AddFour", "x + 4")
+    ///         .with_argument(Argument::new("x", TypeKind::Int64))
+    ///         .with_return_type(TypeKind::Int64);
+    ///     client.create_routine(&routine).await.unwrap();
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn create_routine(&self, routine: &Routine) -> Result<Routine, http::error::Error> {
+        self.routine_client.create(routine).await
+    }
+
+    /// Call the stored procedure with `CALL` and get the rows of the last statement of the procedure.
+    /// The arguments are passed as the query parameters in the order they are added.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::query_parameter::QueryParameters;
+    /// use google_cloud_bigquery::http::routine::RoutineReference;
+    /// use google_cloud_bigquery::query::row::Row;
+    /// use google_cloud_bigquery::query::QueryOption;
+    ///
+    /// async fn run(client: &Client, routine: &RoutineReference) {
+    ///     let arguments = QueryParameters::new().add_positional(10_i64).add_positional("name");
+    ///     let mut iter = client.call_procedure::<Row>(routine, arguments, QueryOption::default()).await.unwrap();
+    ///     while let Some(row) = iter.next().await.unwrap() {
+    ///         let col1 = row.column::<i64>(0);
+    ///     }
+    /// }
+    /// ```
+    pub async fn call_procedure<T>(
+        &self,
+        routine: &RoutineReference,
+        arguments: QueryParameters,
+        option: QueryOption,
+    ) -> Result<query::Iterator<T>, QueryError>
+    where
+        T: http::query::value::StructDecodable + storage::value::StructDecodable,
+    {
+        let request = Self::call_request(routine, arguments)?;
+        self.query_with_option(&routine.project_id, request, option).await
+    }
+
+    fn call_request(
+        routine: &RoutineReference,
+        arguments: QueryParameters,
+    ) -> Result<QueryRequest, http::query_parameter::Error> {
+        let placeholders = arguments
+            .parameters()
+            .iter()
+            .map(|v| {
+                v.name
+                    .as_ref()
+                    .map_or_else(|| "?".to_string(), |name| format!("@{name}"))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut request = QueryRequest {
+            query: format!(
+                "CALL `{}.{}.{}`({placeholders})",
+                routine.project_id, routine.dataset_id, routine.routine_id
+            ),
+            ..Default::default()
+        };
+        arguments.apply(&mut request)?;
+        Ok(request)
+    }
+
+    /// Applies the `maximum_bytes_billed` of the `ClientConfig` unless the request sets it.
+    fn with_maximum_bytes_billed(&self, mut request: QueryRequest) -> QueryRequest {
+        request.maximum_bytes_billed = request.maximum_bytes_billed.or(self.maximum_bytes_billed);
+        request
+    }
+
+    fn query_job(project_id: &str, request: &QueryRequest, config: QueryJobConfig) -> Job {
+        let job_id = config
+            .job_id
+            .unwrap_or_else(|| format!("query_{}", OffsetDateTime::now_utc().unix_timestamp_nanos()));
+        Job {
+            job_reference: JobReference {
+                project_id: project_id.to_string(),
+                job_id,
+                location: (!request.location.is_empty()).then(|| request.location.clone()),
+            },
+            configuration: JobConfiguration {
+                job: JobType::Query(JobConfigurationQuery {
+                    query: request.query.clone(),
+                    default_dataset: config.default_dataset.or_else(|| request.default_dataset.clone()),
+                    priority: config.priority,
+                    use_query_cache: config.use_query_cache.or(request.use_query_cache),
+                    maximum_bytes_billed: config.maximum_bytes_billed.or(request.maximum_bytes_billed),
+                    use_legacy_sql: Some(request.use_legacy_sql),
+                    parameter_mode: request.parameter_mode.clone(),
+                    query_parameters: (!request.query_parameters.is_empty()).then(|| request.query_parameters.clone()),
+                    connection_properties: config.connection_properties.or_else(|| {
+                        (!request.connection_properties.is_empty()).then(|| request.connection_properties.clone())
+                    }),
+                    create_session: request.create_session,
+                    ..Default::default()
+                }),
+                dry_run: request.dry_run,
+                job_timeout_ms: config.job_timeout_ms,
+                labels: config.labels.or_else(|| request.labels.clone()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn new_query_iterator<T>(
+        &self,
+        job_reference: JobReference,
+        schema: Option<TableSchema>,
+        total_rows: i64,
+        page_token: Option<String>,
+        rows: Vec<Tuple>,
+        force_first_fetch: bool,
+        request: QueryRequest,
+        option: QueryOption,
+    ) -> Result<query::Iterator<T>, QueryError>
+    where
+        T: http::query::value::StructDecodable + storage::value::StructDecodable,
+    {
         //use storage api instead of rest API
         if option.enable_storage_read && (page_token.is_none() || page_token.as_ref().unwrap().is_empty()) {
-            tracing::trace!("use storage read api for query {:?}", result.job_reference);
+            tracing::trace!("use storage read api for query {:?}", job_reference);
             let job = self
                 .job_client
                 .get(
-                    &result.job_reference.project_id,
-                    &result.job_reference.job_id,
+                    &job_reference.project_id,
+                    &job_reference.job_id,
                     &GetJobRequest {
-                        location: result.job_reference.location.clone(),
+                        location: job_reference.location.clone(),
                     },
                 )
                 .await?;
@@ -634,19 +1114,20 @@ impl Client {
 
         let http_query_iterator = http::query::Iterator {
             client: self.job_client.clone(),
-            project_id: result.job_reference.project_id,
-            job_id: result.job_reference.job_id,
+            project_id: job_reference.project_id,
+            job_id: job_reference.job_id,
             request: GetQueryResultsRequest {
                 start_index: 0,
                 page_token,
                 max_results: request.max_results,
                 timeout_ms: request.timeout_ms,
-                location: result.job_reference.location,
+                location: job_reference.location,
                 format_options: request.format_options,
             },
             chunk: VecDeque::from(rows),
             total_size: total_rows,
             force_first_fetch,
+            schema: schema.map(Arc::new),
             _marker: PhantomData,
         };
         Ok(query::Iterator {
@@ -736,12 +1217,170 @@ impl Client {
             .when(|e: &query::run::Error| match e {
                 query::run::Error::JobIncomplete => true,
                 query::run::Error::Http(http::error::Error::HttpClient(_)) => true,
-                query::run::Error::Http(http::error::Error::Response(r)) => r.is_retryable(&JOB_RETRY_REASONS),
+                query::run::Error::Http(http::error::Error::Response(r)) => r.is_retryable(&RETRYABLE_REASONS),
                 _ => false,
             })
             .await
     }
 
+    /// Load the data read from the reader into the table without staging it in Cloud Storage.
+    /// The data is streamed to the multipart upload of the load job, and the created job is returned.
+    /// Use `wait_for_job` to wait for the completion of the load.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::job::{Job, JobConfigurationLoad};
+    /// use google_cloud_bigquery::http::table::{SourceFormat, TableReference};
+    /// use google_cloud_bigquery::job::WaitConfig;
+    ///
+    /// async fn run(client: &Client) -> Job {
+    ///     let data = "1,alice\n2,bob\n".as_bytes();
+    ///     let config = JobConfigurationLoad {
+    ///         destination_table: TableReference {
+    ///             project_id: "project".to_string(),
+    ///             dataset_id: "dataset".to_string(),
+    ///             table_id: "table".to_string(),
+    ///         },
+    ///         source_format: Some(SourceFormat::Csv),
+    ///         ..Default::default()
+    ///     };
+    ///     let job = client.load_from_reader("project", config, data).await.unwrap();
+    ///     client.wait_for_job(&job.job_reference, WaitConfig::default()).await.unwrap()
+    /// }
+    /// ```
+    pub async fn load_from_reader<R>(
+        &self,
+        project_id: &str,
+        config: JobConfigurationLoad,
+        mut reader: R,
+    ) -> Result<Job, http::error::Error>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let job = Job {
+            job_reference: JobReference {
+                project_id: project_id.to_string(),
+                job_id: format!("load_{}", OffsetDateTime::now_utc().unix_timestamp_nanos()),
+                location: None,
+            },
+            configuration: JobConfiguration {
+                job: JobType::Load(config),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let stream = async_stream::stream! {
+            let mut buf = vec![0; 1024 * 1024];
+            loop {
+                match reader.read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(n) => yield Ok(buf[..n].to_vec()),
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                }
+            }
+        };
+        self.job_client.upload(&job, reqwest::Body::wrap_stream(stream)).await
+    }
+
+    /// Wait until the job is done by polling its status with exponential backoff.
+    /// Returns the finished job or the `error_result` of the job if it failed.
+    /// ```rust
+    /// use std::time::Duration;
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::job::{Job, JobReference};
+    /// use google_cloud_bigquery::job::WaitConfig;
+    ///
+    /// async fn run(client: &Client, job: &JobReference) -> Job {
+    ///     let config = WaitConfig::default()
+    ///         .with_poll_interval(Duration::from_secs(1))
+    ///         .with_timeout(Duration::from_secs(600));
+    ///     client.wait_for_job(job, config).await.unwrap()
+    /// }
+    /// ```
+    pub async fn wait_for_job(&self, job: &JobReference, config: WaitConfig) -> Result<Job, job::Error> {
+        let deadline = config.timeout.map(|v| tokio::time::Instant::now() + v);
+        let request = GetJobRequest {
+            location: job.location.clone(),
+        };
+        let mut interval = config.poll_interval;
+        loop {
+            tracing::debug!("waiting for job completion {:?}", job);
+            match self.job_client.get(&job.project_id, &job.job_id, &request).await {
+                Ok(result) if result.status.state == JobState::Done => {
+                    return match result.status.error_result {
+                        Some(e) => Err(job::Error::JobFailed(e)),
+                        None => Ok(result),
+                    }
+                }
+                Ok(_) | Err(http::error::Error::HttpClient(_)) => {}
+                Err(http::error::Error::Response(r)) if r.is_retryable(&RETRYABLE_REASONS) => {}
+                Err(e) => return Err(e.into()),
+            }
+            if let Some(deadline) = deadline {
+                if tokio::time::Instant::now() + interval > deadline {
+                    return Err(job::Error::Timeout(job.clone()));
+                }
+            }
+            tokio::time::sleep(interval).await;
+            interval = config.next_interval(interval);
+        }
+    }
+
+    /// Export the table to Cloud Storage and wait for the completion of the extract job.
+    /// Returns the statistics of the job such as the number of the exported files per URI.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::table::{DestinationFormat, TableReference};
+    /// use google_cloud_bigquery::job::ExtractOptions;
+    ///
+    /// async fn run(client: &Client, table: &TableReference) {
+    ///     let options = ExtractOptions::default()
+    ///         .with_destination_format(DestinationFormat::Csv)
+    ///         .with_compression("GZIP")
+    ///         .with_field_delimiter("\t");
+    ///     let uris = vec!["gs://bucket/table-*.csv.gz".to_string()];
+    ///     let statistics = client.extract_table(table, uris, options).await.unwrap();
+    ///     let file_counts = statistics.destination_uri_file_counts;
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn extract_table(
+        &self,
+        table: &TableReference,
+        destination_uris: Vec<String>,
+        options: ExtractOptions,
+    ) -> Result<JobStatisticsExtract, job::Error> {
+        let job = Self::extract_job(table, destination_uris, &options);
+        let created = self.job_client.create(&job).await?;
+        let done = self.wait_for_job(&created.job_reference, options.wait).await?;
+        Ok(done.statistics.and_then(|v| v.extract).unwrap_or_default())
+    }
+
+    fn extract_job(table: &TableReference, destination_uris: Vec<String>, options: &ExtractOptions) -> Job {
+        Job {
+            job_reference: JobReference {
+                project_id: table.project_id.to_string(),
+                job_id: format!("extract_{}", OffsetDateTime::now_utc().unix_timestamp_nanos()),
+                location: options.location.clone(),
+            },
+            configuration: JobConfiguration {
+                job: JobType::Extract(JobConfigurationExtract {
+                    destination_uris,
+                    print_header: options.print_header,
+                    field_delimiter: options.field_delimiter.clone(),
+                    destination_format: options.destination_format.clone(),
+                    compression: options.compression.clone(),
+                    source: JobConfigurationExtractSource::SourceTable(table.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
     /// Read table data by BigQuery Storage Read API.
     /// ```rust
     /// use google_cloud_bigquery::storage::row::Row;
@@ -769,9 +1408,122 @@ impl Client {
     where
         T: storage::value::StructDecodable,
     {
+        let (client, read_session, retry) = self.create_read_session(table, option).await?;
+        storage::Iterator::new(client, read_session, retry).await
+    }
+
+    /// Read table data decoded into `T` as a stream by BigQuery Storage Read API.
+    /// Use `read_table` and `storage::Iterator::collect` to read all the rows into a `Vec<T>` at once.
+    /// ```rust
+    /// use futures_util::StreamExt;
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::table::TableReference;
+    /// use google_cloud_bigquery::storage::row::Row;
+    ///
+    /// async fn run(client: &Client, table: &TableReference) {
+    ///     let mut rows = Box::pin(client.read_table_as::<Row>(table, None).await.unwrap());
+    ///     while let Some(row) = rows.next().await {
+    ///         let col1 = row.unwrap().column::<String>(0);
+    ///     }
+    ///     let all: Vec<Row> = client.read_table::<Row>(table, None).await.unwrap().collect().await.unwrap();
+    /// }
+    /// ```
+    pub async fn read_table_as<T>(
+        &self,
+        table: &TableReference,
+        option: Option<ReadTableOption>,
+    ) -> Result<impl Stream<Item = Result<T, storage::Error>>, storage::Error>
+    where
+        T: storage::value::StructDecodable,
+    {
+        Ok(self.read_table::<T>(table, option).await?.into_stream())
+    }
+
+    /// Read table data by BigQuery Storage Read API with the multiple streams.
+    /// Each iterator reads its own stream with its own offset, so that the iterators can be consumed concurrently.
+    /// Use ReadTableOption::with_max_stream_count to request the number of streams.
+    /// The server may return fewer streams than requested.
+    /// ```rust
+    /// use google_cloud_bigquery::storage::row::Row;
+    /// use google_cloud_bigquery::client::{Client, ReadTableOption};
+    /// use google_cloud_bigquery::http::table::TableReference;
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let table = TableReference {
+    ///         project_id: project_id.to_string(),
+    ///         dataset_id: "dataset".to_string(),
+    ///         table_id: "table".to_string(),
+    ///     };
+    ///     let option = ReadTableOption::default().with_max_stream_count(4);
+    ///     let iterators = client.read_table_streams::<Row>(&table, Some(option)).await.unwrap();
+    ///     let mut tasks = Vec::with_capacity(iterators.len());
+    ///     for mut iter in iterators {
+    ///         tasks.push(tokio::spawn(async move {
+    ///             while let Some(row) = iter.next().await.unwrap() {
+    ///                 let col1 = row.column::<String>(0);
+    ///             }
+    ///         }));
+    ///     }
+    ///     for task in tasks {
+    ///         task.await.unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub async fn read_table_streams<T>(
+        &self,
+        table: &TableReference,
+        option: Option<ReadTableOption>,
+    ) -> Result<Vec<storage::Iterator<T>>, storage::Error>
+    where
+        T: storage::value::StructDecodable,
+    {
+        let (_, read_session, retry) = self.create_read_session(table, option).await?;
+        let mut iterators = Vec::with_capacity(read_session.streams.len());
+        for stream in read_session.streams {
+            let client = self.streaming_read_conn_pool.reader();
+            iterators.push(storage::Iterator::new_with_streams(client, vec![stream], retry.clone()).await?);
+        }
+        Ok(iterators)
+    }
+
+    /// Read table data as Arrow record batches by BigQuery Storage Read API.
+    /// This avoids the per-row decoding, the batches can be passed to the Arrow based libraries as is.
+    /// It requires the `arrow` feature since the record batches expose the version of the `arrow` crate.
+    /// ```rust
+    /// use google_cloud_bigquery::client::Client;
+    /// use google_cloud_bigquery::http::table::TableReference;
+    ///
+    /// async fn run(client: &Client, project_id: &str) {
+    ///     let table = TableReference {
+    ///         project_id: project_id.to_string(),
+    ///         dataset_id: "dataset".to_string(),
+    ///         table_id: "table".to_string(),
+    ///     };
+    ///     let mut iter = client.read_table_arrow(&table, None).await.unwrap();
+    ///     while let Some(batch) = iter.next().await.unwrap() {
+    ///         let rows = batch.num_rows();
+    ///         let schema = batch.schema();
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub async fn read_table_arrow(
+        &self,
+        table: &TableReference,
+        option: Option<ReadTableOption>,
+    ) -> Result<storage::RecordBatchIterator, storage::Error> {
+        let (client, read_session, retry) = self.create_read_session(table, option).await?;
+        storage::RecordBatchIterator::new(client, read_session, retry).await
+    }
+
+    async fn create_read_session(
+        &self,
+        table: &TableReference,
+        option: Option<ReadTableOption>,
+    ) -> Result<(StreamingReadClient, ReadSession, Option<RetrySetting>), storage::Error> {
         let option = option.unwrap_or_default();
 
-        let mut client = StreamingReadClient::new(BigQueryReadClient::new(self.streaming_read_conn_pool.conn()));
+        let mut client = self.streaming_read_conn_pool.reader();
         let read_session = client
             .create_read_session(
                 CreateReadSessionRequest {
@@ -797,7 +1549,7 @@ impl Client {
             )
             .await?
             .into_inner();
-        storage::Iterator::new(client, read_session, option.read_rows_retry_setting).await
+        Ok((client, read_session, option.read_rows_retry_setting))
     }
 }
 
@@ -847,6 +1599,7 @@ impl ReadTableOption {
 mod tests {
     use bigdecimal::BigDecimal;
 
+    use futures_util::StreamExt;
     use serial_test::serial;
     use std::collections::HashMap;
     use std::ops::AddAssign;
@@ -857,12 +1610,25 @@ mod tests {
 
     use crate::client::{Client, ClientConfig, ReadTableOption};
     use crate::http::bigquery_client::test::{create_table_schema, dataset_name, TestData};
+    use crate::http::dataset::list::ListDatasetsRequest;
+    use crate::http::job::list::ListJobsRequest;
     use crate::http::job::query::QueryRequest;
-    use crate::http::table::{Table, TableReference};
+    use crate::http::job::{
+        JobConfigurationExtractSource, JobConfigurationLoad, JobState, JobType, Priority, WriteDisposition,
+    };
+    use crate::http::query_parameter::QueryParameters;
+    use crate::http::routine::{Argument, Routine};
+    use crate::http::table::list::ListTablesRequest;
+    use crate::http::table::{
+        DestinationFormat, SourceFormat, Table, TableFieldSchema, TableFieldType, TableReference, TableSchema,
+    };
     use crate::http::tabledata::insert_all::{InsertAllRequest, Row};
-    use crate::http::types::{QueryParameter, QueryParameterStructType, QueryParameterType, QueryParameterValue};
+    use crate::http::types::{
+        Interval, QueryParameter, QueryParameterStructType, QueryParameterType, QueryParameterValue, TypeKind,
+    };
+    use crate::job::{ExtractOptions, WaitConfig};
     use crate::query;
-    use crate::query::QueryOption;
+    use crate::query::{QueryJobConfig, QueryOption};
 
     #[ctor::ctor]
     fn init() {
@@ -890,6 +1656,170 @@ mod tests {
         test_query(option).await
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_query_numeric_from_storage_and_rest() {
+        let (client, project_id) = create_client().await;
+        let request = QueryRequest {
+            query: "SELECT
+                cast('1.5' as NUMERIC),
+                cast('0.000000001' as NUMERIC),
+                cast('-12345678901234567890.123456789' as NUMERIC),
+                cast('100' as BIGNUMERIC),
+                cast('0.12345678901234567890123456789012345678' as BIGNUMERIC)
+            "
+            .to_string(),
+            ..Default::default()
+        };
+        let mut values = vec![];
+        for option in [
+            QueryOption::default(),
+            QueryOption::default().with_enable_storage_read(true),
+        ] {
+            let mut iter = client
+                .query_with_option::<query::row::Row>(&project_id, request.clone(), option)
+                .await
+                .unwrap();
+            let row = iter.next().await.unwrap().unwrap();
+            let mut decimals = vec![];
+            let mut strings = vec![];
+            for i in 0..5 {
+                decimals.push(row.column::<BigDecimal>(i).unwrap());
+                strings.push(row.column::<String>(i).unwrap());
+            }
+            values.push((decimals, strings));
+        }
+        // The decimals read via the Storage Read API keep the full precision of the REST API.
+        assert_eq!(values[0].0, values[1].0);
+        assert_eq!(
+            values[1].1,
+            vec![
+                "1.5",
+                "0.000000001",
+                "-12345678901234567890.123456789",
+                "100",
+                "0.12345678901234567890123456789012345678"
+            ]
+        );
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct DeserializedRow {
+        name: String,
+        count: i64,
+        ratio: Option<f64>,
+        #[serde(with = "time::serde::rfc3339")]
+        created_at: OffsetDateTime,
+        tags: Vec<String>,
+        nested: DeserializedNested,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct DeserializedNested {
+        flag: bool,
+        day: String,
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_query_deserialize_from_rest() {
+        test_query_deserialize(QueryOption::default()).await
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_query_deserialize_from_storage() {
+        test_query_deserialize(QueryOption::default().with_enable_storage_read(true)).await
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_query_json_interval_geography_from_rest() {
+        test_query_json_interval_geography(QueryOption::default()).await
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_query_json_interval_geography_from_storage() {
+        test_query_json_interval_geography(QueryOption::default().with_enable_storage_read(true)).await
+    }
+
+    async fn test_query_json_interval_geography(option: QueryOption) {
+        let (client, project_id) = create_client().await;
+        let mut iterator = client
+            .query_with_option::<query::row::Row>(
+                &project_id,
+                QueryRequest {
+                    query: "SELECT
+                        JSON '{\"a\": 1, \"b\": [\"x\"]}',
+                        MAKE_INTERVAL(1, 2, 3, 4, 5, 6),
+                        ST_GEOGPOINT(139.7, 35.6),
+                        CAST(NULL AS JSON)
+                    "
+                    .to_string(),
+                    ..Default::default()
+                },
+                option,
+            )
+            .await
+            .unwrap();
+        let row = iterator.next().await.unwrap().unwrap();
+        let v: serde_json::Value = row.column(0).unwrap();
+        assert_eq!(v, serde_json::json!({"a": 1, "b": ["x"]}));
+        let v: Interval = row.column(1).unwrap();
+        assert_eq!(
+            v,
+            Interval {
+                months: 14,
+                days: 3,
+                nanos: ((4 * 60 + 5) * 60 + 6) * 1_000_000_000,
+            }
+        );
+        let v: String = row.column(2).unwrap();
+        assert_eq!(v, "POINT(139.7 35.6)");
+        let v: Option<serde_json::Value> = row.column(3).unwrap();
+        assert!(v.is_none());
+    }
+
+    async fn test_query_deserialize(option: QueryOption) {
+        let (client, project_id) = create_client().await;
+        let mut iterator = client
+            .query_with_option::<query::row::Row>(
+                &project_id,
+                QueryRequest {
+                    query: "SELECT
+                        100 AS count,
+                        'A' AS name,
+                        NULL AS ratio,
+                        TIMESTAMP_MICROS(1230219000000019) AS created_at,
+                        ['x','y'] AS tags,
+                        STRUCT(true AS flag, DATE(2023,9,1) AS day) AS nested
+                    "
+                    .to_string(),
+                    ..Default::default()
+                },
+                option,
+            )
+            .await
+            .unwrap();
+        let row = iterator.next().await.unwrap().unwrap();
+        let row: DeserializedRow = row.deserialize().unwrap();
+        assert_eq!(
+            row,
+            DeserializedRow {
+                name: "A".to_string(),
+                count: 100,
+                ratio: None,
+                created_at: OffsetDateTime::from_unix_timestamp_nanos(1230219000000019000).unwrap(),
+                tags: vec!["x".to_string(), "y".to_string()],
+                nested: DeserializedNested {
+                    flag: true,
+                    day: "2023-09-01".to_string(),
+                },
+            }
+        );
+    }
+
     async fn test_query(option: QueryOption) {
         let (client, project_id) = create_client().await;
         let mut iterator = client
@@ -1182,6 +2112,62 @@ mod tests {
 
         assert_data(&now, data_as_struct);
         assert_data(&now, data_as_row);
+
+        let collected = client
+            .read_table::<TestData>(&table, None)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_data(&now, collected);
+
+        let streamed: Vec<TestData> = client
+            .read_table_as::<TestData>(&table, None)
+            .await
+            .unwrap()
+            .map(|v| v.unwrap())
+            .collect()
+            .await;
+        assert_data(&now, streamed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_read_table_streams() {
+        let dataset = dataset_name("table");
+        let (client, project_id) = create_client().await;
+        let now = OffsetDateTime::from_unix_timestamp(OffsetDateTime::now_utc().unix_timestamp()).unwrap();
+        let table = format!("test_read_table_streams_{}", now.unix_timestamp());
+        insert(&client, &project_id, &dataset, &table, 3, &now).await;
+
+        let table = TableReference {
+            project_id,
+            dataset_id: dataset.to_string(),
+            table_id: table.to_string(),
+        };
+        let option = ReadTableOption::default().with_max_stream_count(2);
+        let iterators = client
+            .read_table_streams::<TestData>(&table, Some(option))
+            .await
+            .unwrap();
+        assert!(!iterators.is_empty() && iterators.len() <= 2);
+
+        let mut tasks = Vec::with_capacity(iterators.len());
+        for mut iter in iterators {
+            tasks.push(tokio::spawn(async move {
+                let mut data = vec![];
+                while let Some(row) = iter.next().await.unwrap() {
+                    data.push(row);
+                }
+                data
+            }));
+        }
+        let mut data_as_struct: Vec<TestData> = vec![];
+        for task in tasks {
+            data_as_struct.extend(task.await.unwrap());
+        }
+        assert_eq!(data_as_struct.len(), 3);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -1333,6 +2319,266 @@ mod tests {
         assert_eq!(col[1], "val2-2".to_string());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_query_with_job_config() {
+        let (client, project_id) = create_client().await;
+        let labels = HashMap::from([("test".to_string(), "query_with_job_config".to_string())]);
+        let config = QueryJobConfig::default()
+            .with_labels(labels.clone())
+            .with_priority(Priority::Batch)
+            .with_use_query_cache(false)
+            .with_maximum_bytes_billed(10_000_000);
+        let request = QueryRequest {
+            query: "SELECT 'a' AS col1 UNION ALL SELECT 'b' AS col1 ORDER BY col1".to_string(),
+            ..Default::default()
+        };
+        let mut iter = client
+            .query_with_job_config::<query::row::Row>(&project_id, request, config, QueryOption::default())
+            .await
+            .unwrap();
+        let mut values = vec![];
+        while let Some(row) = iter.next().await.unwrap() {
+            values.push(row.column::<String>(0).unwrap());
+        }
+        assert_eq!(values, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_wait_for_query_results() {
+        let (client, project_id) = create_client().await;
+        let request = QueryRequest {
+            query: "SELECT 'a' AS col1 UNION ALL SELECT 'b' AS col1 ORDER BY col1".to_string(),
+            ..Default::default()
+        };
+        let job = Client::query_job(&project_id, &request, QueryJobConfig::default());
+        let created = client.job().create(&job).await.unwrap();
+        let mut iter = client
+            .wait_for_query_results::<query::row::Row>(
+                &created.job_reference,
+                WaitConfig::default().with_timeout(Duration::from_secs(60)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(iter.total_size, 2);
+        let mut values = vec![];
+        while let Some(row) = iter.next().await.unwrap() {
+            values.push(row.column::<String>(0).unwrap());
+        }
+        assert_eq!(values, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_estimate_query() {
+        let (client, project_id) = create_client().await;
+        let request = QueryRequest {
+            query: "SELECT name FROM `bigquery-public-data.usa_names.usa_1910_current`".to_string(),
+            ..Default::default()
+        };
+        let estimate = client.estimate_query(&project_id, request).await.unwrap();
+        assert!(estimate.total_bytes_processed > 0);
+        assert!(estimate.total_bytes_processed_accuracy.is_some());
+        let schema = estimate.schema.unwrap();
+        assert_eq!(schema.fields.len(), 1);
+        assert_eq!(schema.fields[0].name, "name");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_column_by_name() {
+        let (client, project_id) = create_client().await;
+        for option in [
+            QueryOption::default(),
+            QueryOption::default().with_enable_storage_read(true),
+        ] {
+            let request = QueryRequest {
+                query: "SELECT 'a' AS UserName, 1 AS Age".to_string(),
+                ..Default::default()
+            };
+            let mut iter = client
+                .query_with_option::<query::row::Row>(&project_id, request, option)
+                .await
+                .unwrap();
+            let row = iter.next().await.unwrap().unwrap();
+            assert_eq!(row.column_by_name::<String>("UserName").unwrap(), "a");
+            assert!(row.column_by_name::<String>("username").is_err());
+            assert_eq!(row.column_by_name_ci::<String>("username").unwrap(), "a");
+            assert_eq!(row.column_by_name_ci::<i64>("AGE").unwrap(), 1);
+            assert!(row.column_by_name_ci::<i64>("unknown").is_err());
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_list_stream() {
+        let (client, project_id) = create_client().await;
+        let request = ListDatasetsRequest {
+            max_results: Some(1),
+            ..Default::default()
+        };
+        let datasets = client.dataset().list(&project_id, None).await.unwrap();
+        let streamed: Vec<_> = client
+            .list_datasets_stream(&project_id, Some(request))
+            .map(|v| v.unwrap().id)
+            .collect()
+            .await;
+        assert_eq!(streamed, datasets.into_iter().map(|v| v.id).collect::<Vec<_>>());
+
+        let dataset_id = dataset_name("table");
+        let request = ListTablesRequest { max_results: Some(1) };
+        let tables = client.table().list(&project_id, &dataset_id, &request).await.unwrap();
+        let streamed: Vec<_> = client
+            .list_tables_stream(&project_id, &dataset_id, request)
+            .map(|v| v.unwrap().id)
+            .collect()
+            .await;
+        assert_eq!(streamed, tables.into_iter().map(|v| v.id).collect::<Vec<_>>());
+
+        let request = ListJobsRequest {
+            max_results: Some(2),
+            state_filter: Some(vec![JobState::Done]),
+            ..Default::default()
+        };
+        let jobs: Vec<_> = client
+            .list_jobs_stream(&project_id, request)
+            .take(5)
+            .map(|v| v.unwrap())
+            .collect()
+            .await;
+        assert_eq!(jobs.len(), 5);
+        assert!(jobs.iter().all(|v| v.state == JobState::Done));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_estimate_query_bytes() {
+        let (client, project_id) = create_client().await;
+        let request = QueryRequest {
+            query: "SELECT name FROM `bigquery-public-data.usa_names.usa_1910_current`".to_string(),
+            ..Default::default()
+        };
+        let bytes = client.estimate_query_bytes(&project_id, request.clone()).await.unwrap();
+        let estimate = client.estimate_query(&project_id, request).await.unwrap();
+        assert_eq!(bytes, estimate.total_bytes_processed);
+        assert!(bytes > 0);
+
+        // the query reading no table processes no bytes.
+        let request = QueryRequest {
+            query: "SELECT 1".to_string(),
+            ..Default::default()
+        };
+        let bytes = client.estimate_query_bytes(&project_id, request).await.unwrap();
+        assert_eq!(bytes, 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_query_exceeds_maximum_bytes_billed() {
+        let (client_config, project_id) = ClientConfig::new_with_auth().await.unwrap();
+        let client = Client::new(client_config.with_maximum_bytes_billed(1)).await.unwrap();
+        let project_id = project_id.unwrap();
+        let request = QueryRequest {
+            query: "SELECT name FROM `bigquery-public-data.usa_names.usa_1910_current`".to_string(),
+            use_query_cache: Some(false),
+            ..Default::default()
+        };
+        let result = client.query::<query::row::Row>(&project_id, request).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_call_procedure() {
+        let (client, project_id) = create_client().await;
+        let routine = Routine::procedure(
+            &project_id,
+            &dataset_name("job"),
+            &format!("add_{}", OffsetDateTime::now_utc().unix_timestamp()),
+            "SELECT x + y AS total;",
+        )
+        .with_argument(Argument::new("x", TypeKind::Int64))
+        .with_argument(Argument::new("y", TypeKind::Int64));
+        let created = client.create_routine(&routine).await.unwrap();
+        let arguments = QueryParameters::new().add_positional(1_i64).add_positional(2_i64);
+        let mut iter = client
+            .call_procedure::<query::row::Row>(&created.routine_reference, arguments, QueryOption::default())
+            .await
+            .unwrap();
+        let row = iter.next().await.unwrap().unwrap();
+        assert_eq!(row.column::<i64>(0).unwrap(), 3);
+        assert!(iter.next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_load_from_reader() {
+        let (client, project_id) = create_client().await;
+        let config = JobConfigurationLoad {
+            destination_table: TableReference {
+                project_id: project_id.to_string(),
+                dataset_id: dataset_name("table"),
+                table_id: format!("load_from_reader_{}", OffsetDateTime::now_utc().unix_timestamp()),
+            },
+            schema: Some(TableSchema {
+                fields: vec![
+                    TableFieldSchema {
+                        name: "id".to_string(),
+                        data_type: TableFieldType::Integer,
+                        ..Default::default()
+                    },
+                    TableFieldSchema {
+                        name: "name".to_string(),
+                        data_type: TableFieldType::String,
+                        ..Default::default()
+                    },
+                ],
+            }),
+            source_format: Some(SourceFormat::Csv),
+            write_disposition: Some(WriteDisposition::WriteTruncate),
+            ..Default::default()
+        };
+        let job = client
+            .load_from_reader(&project_id, config, "1,alice\n2,bob\n".as_bytes())
+            .await
+            .unwrap();
+        let job = client
+            .wait_for_job(&job.job_reference, WaitConfig::default())
+            .await
+            .unwrap();
+        let output_rows = job.statistics.unwrap().load.unwrap().output_rows;
+        assert_eq!(output_rows, Some(2));
+    }
+
+    #[test]
+    fn test_extract_job() {
+        let table = TableReference {
+            project_id: "project".to_string(),
+            dataset_id: "dataset".to_string(),
+            table_id: "table".to_string(),
+        };
+        let options = ExtractOptions::default()
+            .with_destination_format(DestinationFormat::Csv)
+            .with_compression("GZIP")
+            .with_field_delimiter("|")
+            .with_location("US");
+        let job = Client::extract_job(&table, vec!["gs://bucket/table-*.csv.gz".to_string()], &options);
+        assert_eq!(job.job_reference.project_id, "project");
+        assert_eq!(job.job_reference.location.as_deref(), Some("US"));
+        match job.configuration.job {
+            JobType::Extract(config) => {
+                assert_eq!(config.destination_uris, vec!["gs://bucket/table-*.csv.gz".to_string()]);
+                assert_eq!(config.destination_format, Some(DestinationFormat::Csv));
+                assert_eq!(config.compression.as_deref(), Some("GZIP"));
+                assert_eq!(config.field_delimiter.as_deref(), Some("|"));
+                assert_eq!(config.print_header, None);
+                assert_eq!(config.source, JobConfigurationExtractSource::SourceTable(table));
+            }
+            other => panic!("unexpected job type {other:?}"),
+        }
+    }
+
     fn assert_data(now: &OffsetDateTime, data: Vec<TestData>) {
         for (i, d) in data.iter().enumerate() {
             assert_eq!(&TestData::default(i, *now + Duration::from_secs(i as u64)), d);
@@ -1,7 +1,8 @@
-use crate::grpc::apiv1::bigquery_client::StreamingWriteClient;
+use crate::grpc::apiv1::bigquery_client::{StreamingReadClient, StreamingWriteClient};
 use google_cloud_gax::conn::{
     Channel, ConnectionManager as GRPCConnectionManager, ConnectionOptions, Environment, Error,
 };
+use google_cloud_googleapis::cloud::bigquery::storage::v1::big_query_read_client::BigQueryReadClient;
 use google_cloud_googleapis::cloud::bigquery::storage::v1::big_query_write_client::BigQueryWriteClient;
 
 pub const AUDIENCE: &str = "https://bigquerystorage.googleapis.com/";
@@ -36,7 +37,21 @@ impl ConnectionManager {
         self.inner.conn()
     }
 
+    pub fn reader(&self) -> StreamingReadClient {
+        let client = BigQueryReadClient::new(self.conn());
+        let client = match self.inner.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        };
+        StreamingReadClient::new(client)
+    }
+
     pub fn writer(&self) -> StreamingWriteClient {
-        StreamingWriteClient::new(BigQueryWriteClient::new(self.conn()))
+        let client = BigQueryWriteClient::new(self.conn());
+        let client = match self.inner.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        };
+        StreamingWriteClient::new(client)
     }
 }
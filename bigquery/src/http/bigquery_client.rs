@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
-use reqwest::Response;
+use reqwest::{Request, Response};
 use reqwest_middleware::{ClientWithMiddleware as Client, RequestBuilder};
+use tracing::Instrument;
 
+use google_cloud_gax::conn::QUOTA_PROJECT_HEADER;
 use google_cloud_token::TokenSource;
 
 use crate::http::error::{Error, ErrorWrapper};
@@ -17,12 +19,19 @@ pub const SCOPES: [&str; 7] = [
     "https://www.googleapis.com/auth/devstorage.read_write",
 ];
 
+const API_CLIENT: &str = "rust";
+const USER_AGENT: &str = "google-cloud-bigquery";
+
 #[derive(Debug, Clone)]
 pub struct BigqueryClient {
     ts: Arc<dyn TokenSource>,
     endpoint: String,
+    upload_endpoint: String,
     http: Client,
     debug: bool,
+    quota_project: Option<String>,
+    api_client: String,
+    user_agent: String,
 }
 
 impl BigqueryClient {
@@ -30,24 +39,51 @@ impl BigqueryClient {
         Self {
             ts,
             endpoint: format!("{endpoint}/bigquery/v2"),
+            upload_endpoint: format!("{endpoint}/upload/bigquery/v2"),
             http,
             debug,
+            quota_project: None,
+            api_client: API_CLIENT.to_string(),
+            user_agent: USER_AGENT.to_string(),
+        }
+    }
+
+    /// Sets the project billed for the quota of the requests.
+    pub(crate) fn with_quota_project(mut self, value: Option<String>) -> Self {
+        self.quota_project = value;
+        self
+    }
+
+    /// Appends the token of the application to the `user-agent` and `x-goog-api-client` headers.
+    pub(crate) fn with_user_agent(mut self, value: Option<String>) -> Self {
+        if let Some(value) = value {
+            self.api_client = format!("{API_CLIENT} {value}");
+            self.user_agent = format!("{USER_AGENT} {value}");
         }
+        self
     }
 
     pub(crate) fn endpoint(&self) -> &str {
         self.endpoint.as_str()
     }
 
+    pub(crate) fn upload_endpoint(&self) -> &str {
+        self.upload_endpoint.as_str()
+    }
+
     pub(crate) fn http(&self) -> &Client {
         &self.http
     }
 
     async fn with_headers(&self, builder: RequestBuilder) -> Result<RequestBuilder, Error> {
         let token = self.ts.token().await.map_err(Error::TokenSource)?;
+        let builder = match &self.quota_project {
+            Some(project) => builder.header(QUOTA_PROJECT_HEADER, project),
+            None => builder,
+        };
         Ok(builder
-            .header("X-Goog-Api-Client", "rust")
-            .header(reqwest::header::USER_AGENT, "google-cloud-bigquery")
+            .header("X-Goog-Api-Client", &self.api_client)
+            .header(reqwest::header::USER_AGENT, &self.user_agent)
             .header(reqwest::header::AUTHORIZATION, token))
     }
 
@@ -55,9 +91,8 @@ impl BigqueryClient {
     where
         T: serde::de::DeserializeOwned,
     {
-        let request = self.with_headers(builder).await?;
-        let response = request.send().await?;
-        let response = Self::check_response_status(response).await?;
+        let request = self.with_headers(builder).await?.build()?;
+        let response = self.execute(request).await?;
         if self.debug {
             let text = response.text().await?;
             tracing::info!("{}", text);
@@ -68,12 +103,23 @@ impl BigqueryClient {
     }
 
     pub async fn send_get_empty(&self, builder: RequestBuilder) -> Result<(), Error> {
-        let builder = self.with_headers(builder).await?;
-        let response = builder.send().await?;
-        Self::check_response_status(response).await?;
+        let request = self.with_headers(builder).await?.build()?;
+        self.execute(request).await?;
         Ok(())
     }
 
+    /// Executes the request within a span recording the status code of the response.
+    async fn execute(&self, request: Request) -> Result<Response, Error> {
+        let span = request_span(&request);
+        async {
+            let response = self.http.execute(request).await?;
+            tracing::Span::current().record("http.response.status_code", response.status().as_u16());
+            Self::check_response_status(response).await
+        }
+        .instrument(span)
+        .await
+    }
+
     /// Checks whether an HTTP response is successful and returns it, or returns an error.
     async fn check_response_status(response: Response) -> Result<Response, Error> {
         // Check the status code, returning the response if it is not an error.
@@ -91,6 +137,16 @@ impl BigqueryClient {
     }
 }
 
+#[cfg(feature = "trace")]
+fn request_span(request: &Request) -> tracing::Span {
+    google_cloud_gax::http_request_span(request.method().as_str(), request.url().as_str())
+}
+
+#[cfg(not(feature = "trace"))]
+fn request_span(_request: &Request) -> tracing::Span {
+    tracing::Span::none()
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use std::str::FromStr;
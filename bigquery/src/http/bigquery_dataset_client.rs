@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use google_cloud_gax::grpc::codegen::tokio_stream::Stream;
+
 use crate::http::bigquery_client::BigqueryClient;
 use crate::http::dataset;
 use crate::http::dataset::list::{DatasetOverview, ListDatasetsRequest, ListDatasetsResponse};
@@ -64,6 +66,31 @@ impl BigqueryDatasetClient {
         }
         Ok(datasets)
     }
+
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/datasets/list
+    /// Yields the datasets one at a time while following the page tokens.
+    /// `max_results` of the request is the size of each page.
+    pub fn list_stream<'a>(
+        &'a self,
+        project_id: &'a str,
+        req: Option<ListDatasetsRequest>,
+    ) -> impl Stream<Item = Result<DatasetOverview, Error>> + 'a {
+        async_stream::try_stream! {
+            let mut page_token: Option<String> = None;
+            loop {
+                let builder =
+                    dataset::list::build(self.inner.endpoint(), self.inner.http(), project_id, req.as_ref(), page_token);
+                let response: ListDatasetsResponse = self.inner.send(builder).await?;
+                for dataset in response.datasets {
+                    yield dataset;
+                }
+                if response.next_page_token.is_none() {
+                    break;
+                }
+                page_token = response.next_page_token;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
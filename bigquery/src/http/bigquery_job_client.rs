@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use google_cloud_gax::grpc::codegen::tokio_stream::Stream;
+
 use crate::http::bigquery_client::BigqueryClient;
 use crate::http::error::Error;
 use crate::http::job;
@@ -67,6 +69,14 @@ impl BigqueryJobClient {
         self.inner.send(builder).await
     }
 
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/jobs/insert
+    /// Creates the load job with the media uploaded by the multipart request instead of `source_uris`.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn upload<T: Into<reqwest::Body>>(&self, metadata: &Job, media: T) -> Result<Job, Error> {
+        let builder = job::upload::build(self.inner.upload_endpoint(), self.inner.http(), metadata, media)?;
+        self.inner.send(builder).await
+    }
+
     /// https://cloud.google.com/bigquery/docs/reference/rest/v2/jobs/delete
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn delete(&self, project_id: &str, job_id: &str) -> Result<(), Error> {
@@ -141,6 +151,30 @@ impl BigqueryJobClient {
         }
         Ok(jobs)
     }
+
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/jobs/list
+    /// Yields the jobs one at a time while following the page tokens.
+    /// `max_results` of the request is the size of each page.
+    pub fn list_stream<'a>(
+        &'a self,
+        project_id: &'a str,
+        req: ListJobsRequest,
+    ) -> impl Stream<Item = Result<JobOverview, Error>> + 'a {
+        async_stream::try_stream! {
+            let mut page_token: Option<String> = None;
+            loop {
+                let builder = job::list::build(self.inner.endpoint(), self.inner.http(), project_id, &req, page_token);
+                let response: ListJobsResponse = self.inner.send(builder).await?;
+                for job in response.jobs {
+                    yield job;
+                }
+                if response.next_page_token.is_none() {
+                    break;
+                }
+                page_token = response.next_page_token;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -45,6 +45,16 @@ impl BigqueryRoutineClient {
         self.inner.send(builder).await
     }
 
+    /// exists returns false instead of the error if the routine is not found.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn exists(&self, project_id: &str, dataset_id: &str, routine_id: &str) -> Result<bool, Error> {
+        match self.get(project_id, dataset_id, routine_id).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     /// https://cloud.google.com/bigquery/docs/reference/rest/v2/routines/list
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn list(
@@ -203,6 +213,11 @@ mod test {
                 .delete(f.project_id.as_str(), f.dataset_id.as_str(), f.routine_id.as_str())
                 .await
                 .unwrap();
+            let exists = client
+                .exists(f.project_id.as_str(), f.dataset_id.as_str(), f.routine_id.as_str())
+                .await
+                .unwrap();
+            assert!(!exists);
         }
     }
 }
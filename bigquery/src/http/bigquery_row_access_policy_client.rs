@@ -6,6 +6,7 @@ use crate::http::row_access_policy;
 use crate::http::row_access_policy::list::{
     ListRowAccessPoliciesRequest, ListRowAccessPoliciesResponse, RowAccessPolicyOverview,
 };
+use crate::http::row_access_policy::RowAccessPolicy;
 use crate::http::table::get_iam_policy::GetIamPolicyRequest;
 use crate::http::table::test_iam_permissions::{TestIamPermissionsRequest, TestIamPermissionsResponse};
 use crate::http::types::Policy;
@@ -20,6 +21,62 @@ impl BigqueryRowAccessPolicyClient {
         Self { inner }
     }
 
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/rowAccessPolicies/get
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn get(
+        &self,
+        project_id: &str,
+        dataset_id: &str,
+        table_id: &str,
+        policy_id: &str,
+    ) -> Result<RowAccessPolicy, Error> {
+        let builder = row_access_policy::get::build(
+            self.inner.endpoint(),
+            self.inner.http(),
+            project_id,
+            dataset_id,
+            table_id,
+            policy_id,
+        );
+        self.inner.send(builder).await
+    }
+
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/rowAccessPolicies/delete
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn delete(
+        &self,
+        project_id: &str,
+        dataset_id: &str,
+        table_id: &str,
+        policy_id: &str,
+    ) -> Result<(), Error> {
+        let builder = row_access_policy::delete::build(
+            self.inner.endpoint(),
+            self.inner.http(),
+            project_id,
+            dataset_id,
+            table_id,
+            policy_id,
+        );
+        self.inner.send_get_empty(builder).await
+    }
+
+    /// exists returns false instead of the error if the row access policy is not found.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn exists(
+        &self,
+        project_id: &str,
+        dataset_id: &str,
+        table_id: &str,
+        policy_id: &str,
+    ) -> Result<bool, Error> {
+        match self.get(project_id, dataset_id, table_id, policy_id).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     /// https://cloud.google.com/bigquery/docs/reference/rest/v2/rowAccessPolicies/getIamPolicy
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn get_iam_policy(
@@ -142,6 +199,15 @@ mod test {
         assert_eq!(policies[1].filter_predicate, "string_field_1 = 'value2'");
         for p in policies {
             let r = p.row_access_policy_reference;
+            let policy = client
+                .get(&r.project_id, &r.dataset_id, &r.table_id, &r.policy_id)
+                .await
+                .unwrap();
+            assert_eq!(policy.filter_predicate, p.filter_predicate);
+            assert!(client
+                .exists(&r.project_id, &r.dataset_id, &r.table_id, &r.policy_id)
+                .await
+                .unwrap());
             let p = client
                 .get_iam_policy(
                     &r.project_id,
@@ -154,5 +220,9 @@ mod test {
                 .unwrap();
             assert_eq!(p.bindings[0].role, "roles/bigquery.filteredDataViewer");
         }
+        assert!(!client
+            .exists(&table1.project_id, &table1.dataset_id, &table1.table_id, "not_found_policy")
+            .await
+            .unwrap());
     }
 }
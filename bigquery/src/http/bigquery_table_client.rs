@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use google_cloud_gax::grpc::codegen::tokio_stream::Stream;
+
 use crate::http::bigquery_client::BigqueryClient;
 use crate::http::error::Error;
 use crate::http::table;
@@ -163,6 +165,38 @@ impl BigqueryTableClient {
         }
         Ok(tables)
     }
+
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/tables/list
+    /// Yields the tables one at a time while following the page tokens.
+    /// `max_results` of the request is the size of each page.
+    pub fn list_stream<'a>(
+        &'a self,
+        project_id: &'a str,
+        dataset_id: &'a str,
+        req: ListTablesRequest,
+    ) -> impl Stream<Item = Result<TableOverview, Error>> + 'a {
+        async_stream::try_stream! {
+            let mut page_token: Option<String> = None;
+            loop {
+                let builder = table::list::build(
+                    self.inner.endpoint(),
+                    self.inner.http(),
+                    project_id,
+                    dataset_id,
+                    &req,
+                    page_token,
+                );
+                let response: ListTablesResponse = self.inner.send(builder).await?;
+                for table in response.tables {
+                    yield table;
+                }
+                if response.next_page_token.is_none() {
+                    break;
+                }
+                page_token = response.next_page_token;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,16 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
+use backon::{BackoffBuilder, ExponentialBuilder};
 use serde::Serialize;
 
 use crate::http::bigquery_client::BigqueryClient;
-use crate::http::error::Error;
+use crate::http::error::{Error, RETRYABLE_REASONS};
+use crate::http::query::value::StructDecodable;
+use crate::http::query::Error as ListError;
 use crate::http::tabledata;
-use crate::http::tabledata::insert_all::{InsertAllRequest, InsertAllResponse};
+use crate::http::tabledata::insert_all::{InsertAllRequest, InsertAllResponse, Row};
 use crate::http::tabledata::list::{FetchDataRequest, FetchDataResponse};
 
 #[derive(Debug, Clone)]
@@ -60,6 +65,102 @@ impl BigqueryTabledataClient {
         self.inner.send(builder).await
     }
 
+    /// Inserts the rows, retrying transient failures with exponential backoff.
+    /// Requests failed by 5xx or `rateLimitExceeded` are sent again as a whole.
+    /// When the response contains only transient row errors, only the failed rows are sent again.
+    /// If any row failed permanently (e.g. schema mismatch), no retry is made and the row errors are returned.
+    /// The `index` of the returned row errors always refers to the rows of `req`.
+    /// Set `insert_id` of each row so that BigQuery can deduplicate the retried rows.
+    /// ```rust
+    /// use google_cloud_bigquery::http::tabledata::insert_all::{InsertAllRequest, Row};
+    /// use google_cloud_bigquery::http::bigquery_tabledata_client::BigqueryTabledataClient;
+    /// use google_cloud_bigquery::query::ExponentialBuilder;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// pub struct TestData {
+    ///     pub col1: String,
+    /// }
+    ///
+    /// async fn run(client: &BigqueryTabledataClient, project_id: &str, data: TestData) {
+    ///     let request = InsertAllRequest {
+    ///         rows: vec![Row {
+    ///             insert_id: Some("unique-id".to_string()),
+    ///             json: data,
+    ///         }],
+    ///         ..Default::default()
+    ///     };
+    ///     let retry = ExponentialBuilder::default().with_max_times(5);
+    ///     let result = client.insert_with_retry(project_id, "dataset", "table", &request, retry).await.unwrap();
+    ///     let error = result.insert_errors;
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn insert_with_retry<T: Serialize>(
+        &self,
+        project_id: &str,
+        dataset_id: &str,
+        table_id: &str,
+        req: &InsertAllRequest<T>,
+        retry: ExponentialBuilder,
+    ) -> Result<InsertAllResponse, Error> {
+        let mut backoff = retry.build();
+        // indices of the rows in `req` to send
+        let mut pending: Vec<usize> = (0..req.rows.len()).collect();
+        loop {
+            let request = InsertAllRequest {
+                skip_invalid_rows: req.skip_invalid_rows,
+                ignore_unknown_values: req.ignore_unknown_values,
+                template_suffix: req.template_suffix.clone(),
+                rows: pending
+                    .iter()
+                    .map(|i| Row {
+                        insert_id: req.rows[*i].insert_id.clone(),
+                        json: &req.rows[*i].json,
+                    })
+                    .collect(),
+                trace_id: req.trace_id.clone(),
+            };
+            let mut response = match self.insert(project_id, dataset_id, table_id, &request).await {
+                Ok(response) => response,
+                Err(err) => {
+                    let retryable = match &err {
+                        Error::HttpClient(_) => true,
+                        Error::Response(r) => r.is_retryable(&RETRYABLE_REASONS),
+                        _ => false,
+                    };
+                    match backoff.next() {
+                        Some(duration) if retryable => {
+                            tracing::debug!("retry insert after {:?}: {:?}", duration, err);
+                            tokio::time::sleep(duration).await;
+                            continue;
+                        }
+                        _ => return Err(err),
+                    }
+                }
+            };
+            // map the row index of the sent request to the index of `req`
+            if let Some(errors) = response.insert_errors.as_mut() {
+                for e in errors.iter_mut() {
+                    if let Some(i) = pending.get(e.index as usize) {
+                        e.index = *i as i32;
+                    }
+                }
+            }
+            let errors = match &response.insert_errors {
+                Some(errors) if !errors.is_empty() && errors.iter().all(|e| e.is_transient()) => errors,
+                _ => return Ok(response),
+            };
+            match backoff.next() {
+                Some(duration) => {
+                    tracing::debug!("retry {} failed rows after {:?}", errors.len(), duration);
+                    pending = errors.iter().map(|e| e.index as usize).collect();
+                    tokio::time::sleep(duration).await;
+                }
+                None => return Ok(response),
+            }
+        }
+    }
+
     /// https://cloud.google.com/bigquery/docs/reference/rest/v2/tabledata/list
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn read(
@@ -73,6 +174,51 @@ impl BigqueryTabledataClient {
             tabledata::list::build(self.inner.endpoint(), self.inner.http(), project_id, dataset_id, table_id, req);
         self.inner.send(builder).await
     }
+
+    /// Reads the rows of the table without running a query job.
+    /// The first page is fetched by this call and the rest of the pages are fetched by the iterator.
+    /// https://cloud.google.com/bigquery/docs/reference/rest/v2/tabledata/list
+    /// ```rust
+    /// use google_cloud_bigquery::http::bigquery_tabledata_client::BigqueryTabledataClient;
+    /// use google_cloud_bigquery::http::query::row::Row;
+    /// use google_cloud_bigquery::http::tabledata::list::FetchDataRequest;
+    ///
+    /// async fn run(client: &BigqueryTabledataClient, project_id: &str) {
+    ///     let request = FetchDataRequest {
+    ///         start_index: Some(10),
+    ///         selected_fields: Some("col1,col2".to_string()),
+    ///         ..Default::default()
+    ///     };
+    ///     let mut iter = client.list::<Row>(project_id, "dataset", "table", request).await.unwrap();
+    ///     while let Some(row) = iter.next().await.unwrap() {
+    ///         let col1 = row.column::<String>(0);
+    ///         let col2 = row.column::<Option<i64>>(1);
+    ///     }
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn list<T: StructDecodable>(
+        &self,
+        project_id: &str,
+        dataset_id: &str,
+        table_id: &str,
+        mut req: FetchDataRequest,
+    ) -> Result<tabledata::list::Iterator<T>, ListError> {
+        let response = self.read(project_id, dataset_id, table_id, &req).await?;
+        req.page_token = response.page_token;
+        // start_index is only applicable to the first page
+        req.start_index = None;
+        Ok(tabledata::list::Iterator {
+            client: self.clone(),
+            project_id: project_id.to_string(),
+            dataset_id: dataset_id.to_string(),
+            table_id: table_id.to_string(),
+            request: req,
+            chunk: VecDeque::from(response.rows.unwrap_or_default()),
+            total_rows: response.total_rows,
+            _marker: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -86,6 +232,7 @@ mod test {
     use crate::http::bigquery_client::test::{create_client, create_table_schema, dataset_name, TestData};
     use crate::http::bigquery_table_client::BigqueryTableClient;
     use crate::http::bigquery_tabledata_client::BigqueryTabledataClient;
+    use crate::http::query::row::Row as QueryRow;
     use crate::http::table::Table;
     use crate::http::tabledata::insert_all::{InsertAllRequest, Row};
     use crate::http::tabledata::list;
@@ -176,4 +323,28 @@ mod test {
         }
         assert_eq!(data.len(), 1000, "{:?}", data.pop());
     }
+
+    #[tokio::test]
+    #[serial]
+    pub async fn list() {
+        let dataset = dataset_name("job");
+        let (client, project) = create_client().await;
+        let client = BigqueryTabledataClient::new(Arc::new(client));
+
+        let request = FetchDataRequest {
+            max_results: Some(300),
+            start_index: Some(100),
+            ..Default::default()
+        };
+        let mut iter = client
+            .list::<QueryRow>(project.as_str(), dataset.as_str(), "reading_data", request)
+            .await
+            .unwrap();
+        assert_eq!(iter.total_rows, 1000);
+        let mut count = 0;
+        while let Some(_row) = iter.next().await.unwrap() {
+            count += 1;
+        }
+        assert_eq!(count, 900);
+    }
 }
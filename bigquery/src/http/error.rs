@@ -19,6 +19,17 @@ pub enum Error {
     TokenSource(Box<dyn std::error::Error + Send + Sync>),
 }
 
+impl Error {
+    /// Returns true if the requested resource does not exist.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::Response(e) => e.code == 404,
+            Error::HttpClient(e) => e.status() == Some(reqwest::StatusCode::NOT_FOUND),
+            _ => false,
+        }
+    }
+}
+
 impl From<reqwest_middleware::Error> for Error {
     fn from(error: reqwest_middleware::Error) -> Self {
         match error {
@@ -45,6 +56,9 @@ pub struct ErrorResponse {
 
 const RETRYABLE_CODES: [u16; 4] = [500, 502, 503, 504];
 
+/// The reasons of the transient errors retried by the job and insert requests.
+pub(crate) const RETRYABLE_REASONS: [&str; 3] = ["backendError", "rateLimitExceeded", "internalError"];
+
 impl ErrorResponse {
     pub fn is_retryable(&self, retryable_reasons: &[&str]) -> bool {
         if RETRYABLE_CODES.contains(&self.code) {
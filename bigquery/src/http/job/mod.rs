@@ -20,6 +20,7 @@ pub mod get_query_results;
 pub mod insert;
 pub mod list;
 pub mod query;
+pub mod upload;
 
 #[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize, Debug, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
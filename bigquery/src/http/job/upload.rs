@@ -0,0 +1,22 @@
+use reqwest::multipart::{Form, Part};
+use reqwest_middleware::{ClientWithMiddleware as Client, RequestBuilder};
+
+use crate::http::error::Error;
+use crate::http::job::Job;
+
+pub fn build<T: Into<reqwest::Body>>(
+    base_url: &str,
+    client: &Client,
+    data: &Job,
+    media: T,
+) -> Result<RequestBuilder, Error> {
+    let url = format!(
+        "{}/projects/{}/jobs?uploadType=multipart",
+        base_url, data.job_reference.project_id
+    );
+    let metadata_part = Part::text(serde_json::to_string(data).expect("job serialize failed"))
+        .mime_str("application/json; charset=UTF-8")?;
+    let media_part = Part::stream(media).mime_str("application/octet-stream")?;
+    let form = Form::new().part("metadata", metadata_part).part("media", media_part);
+    Ok(client.post(url).multipart(form))
+}
@@ -17,6 +17,7 @@ pub mod error;
 pub mod job;
 pub mod model;
 pub mod query;
+pub mod query_parameter;
 pub mod routine;
 pub mod row_access_policy;
 pub mod table;
@@ -1,10 +1,12 @@
 use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use crate::http::bigquery_job_client::BigqueryJobClient;
 use crate::http::error::Error as HttpError;
 use crate::http::job::get_query_results::GetQueryResultsRequest;
 use crate::http::query::value::StructDecodable;
+use crate::http::table::TableSchema;
 use crate::http::tabledata::list::Tuple;
 
 #[derive(thiserror::Error, Debug)]
@@ -22,6 +24,7 @@ pub struct Iterator<T: StructDecodable> {
     pub(crate) request: GetQueryResultsRequest,
     pub(crate) chunk: VecDeque<Tuple>,
     pub(crate) force_first_fetch: bool,
+    pub(crate) schema: Option<Arc<TableSchema>>,
     pub total_size: i64,
     pub(crate) _marker: PhantomData<T>,
 }
@@ -30,7 +33,11 @@ impl<T: StructDecodable> Iterator<T> {
     pub async fn next(&mut self) -> Result<Option<T>, Error> {
         loop {
             if let Some(v) = self.chunk.pop_front() {
-                return Ok(T::decode(v).map(Some)?);
+                return Ok(match &self.schema {
+                    Some(schema) => T::decode_with_schema(v, schema),
+                    None => T::decode(v),
+                }
+                .map(Some)?);
             }
             if self.force_first_fetch {
                 self.force_first_fetch = false
@@ -41,6 +48,9 @@ impl<T: StructDecodable> Iterator<T> {
                 .client
                 .get_query_results(self.project_id.as_str(), self.job_id.as_str(), &self.request)
                 .await?;
+            if let Some(schema) = response.schema {
+                self.schema = Some(Arc::new(schema));
+            }
             if response.rows.is_none() {
                 return Ok(None);
             }
@@ -52,19 +62,34 @@ impl<T: StructDecodable> Iterator<T> {
 }
 
 pub mod row {
-    use crate::http::query::value::StructDecodable;
-    use crate::http::tabledata::list::{Cell, Tuple};
+    use std::sync::Arc;
+
+    use serde::de::DeserializeOwned;
+    use serde_json::{Map, Number};
+    use time::format_description::well_known::Rfc3339;
+    use time::OffsetDateTime;
+
+    use crate::http::query::value::{Decodable, StructDecodable};
+    use crate::http::table::{TableFieldMode, TableFieldSchema, TableFieldType, TableSchema};
+    use crate::http::tabledata::list::{Cell, Tuple, Value};
 
     #[derive(thiserror::Error, Debug)]
     pub enum Error {
         #[error("no data found: {0}")]
         UnexpectedColumnIndex(usize),
+        #[error("no column found: {0}")]
+        UnexpectedColumnName(String),
         #[error(transparent)]
         Value(#[from] super::value::Error),
+        #[error("no schema found for the row")]
+        NoSchemaFound,
+        #[error(transparent)]
+        Deserialize(#[from] serde_json::Error),
     }
 
     pub struct Row {
         inner: Vec<Cell>,
+        schema: Option<Arc<TableSchema>>,
     }
 
     impl Row {
@@ -72,19 +97,102 @@ pub mod row {
             let cell: &Cell = self.inner.get(index).ok_or(Error::UnexpectedColumnIndex(index))?;
             Ok(T::decode(&cell.v)?)
         }
+
+        /// Returns the value of the column with the name. The schema is required.
+        pub fn column_by_name<T: super::value::Decodable>(&self, name: &str) -> Result<T, Error> {
+            self.column(self.index_of(name, |v| v == name)?)
+        }
+
+        /// Returns the value of the column whose name matches ignoring the ASCII case. The schema is required.
+        pub fn column_by_name_ci<T: super::value::Decodable>(&self, name: &str) -> Result<T, Error> {
+            self.column(self.index_of(name, |v| v.eq_ignore_ascii_case(name))?)
+        }
+
+        fn index_of(&self, name: &str, matches: impl Fn(&str) -> bool) -> Result<usize, Error> {
+            let schema = self.schema.as_ref().ok_or(Error::NoSchemaFound)?;
+            schema
+                .fields
+                .iter()
+                .position(|field| matches(&field.name))
+                .ok_or_else(|| Error::UnexpectedColumnName(name.to_string()))
+        }
+
+        /// Deserializes the row into `T` by matching the column names to the fields of `T`.
+        /// TIMESTAMP columns are provided as RFC3339 strings and BYTES columns as base64 encoded strings.
+        pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+            let schema = self.schema.as_ref().ok_or(Error::NoSchemaFound)?;
+            let value = tuple_to_json(&self.inner, &schema.fields)?;
+            Ok(serde_json::from_value(value)?)
+        }
     }
 
     impl StructDecodable for Row {
         fn decode(value: Tuple) -> Result<Self, crate::http::query::value::Error> {
-            Ok(Self { inner: value.f })
+            Ok(Self {
+                inner: value.f,
+                schema: None,
+            })
+        }
+
+        fn decode_with_schema(
+            value: Tuple,
+            schema: &Arc<TableSchema>,
+        ) -> Result<Self, crate::http::query::value::Error> {
+            Ok(Self {
+                inner: value.f,
+                schema: Some(schema.clone()),
+            })
         }
     }
+
+    fn tuple_to_json(cells: &[Cell], fields: &[TableFieldSchema]) -> Result<serde_json::Value, Error> {
+        let mut object = Map::with_capacity(fields.len());
+        for (cell, field) in cells.iter().zip(fields) {
+            object.insert(field.name.clone(), field_to_json(&cell.v, field)?);
+        }
+        Ok(serde_json::Value::Object(object))
+    }
+
+    fn field_to_json(value: &Value, field: &TableFieldSchema) -> Result<serde_json::Value, Error> {
+        match value {
+            Value::Null => Ok(serde_json::Value::Null),
+            Value::Array(cells) if field.mode == Some(TableFieldMode::Repeated) => cells
+                .iter()
+                .map(|cell| scalar_to_json(&cell.v, field))
+                .collect::<Result<Vec<_>, _>>()
+                .map(serde_json::Value::Array),
+            _ => scalar_to_json(value, field),
+        }
+    }
+
+    fn scalar_to_json(value: &Value, field: &TableFieldSchema) -> Result<serde_json::Value, Error> {
+        if let Value::Null = value {
+            return Ok(serde_json::Value::Null);
+        }
+        Ok(match field.data_type {
+            TableFieldType::Record | TableFieldType::Struct => match value {
+                Value::Struct(tuple) => tuple_to_json(&tuple.f, field.fields.as_deref().unwrap_or_default())?,
+                _ => return Err(super::value::Error::InvalidType.into()),
+            },
+            TableFieldType::Integer | TableFieldType::Int64 => serde_json::Value::from(i64::decode(value)?),
+            TableFieldType::Float | TableFieldType::Float64 => {
+                Number::from_f64(f64::decode(value)?).map_or(serde_json::Value::Null, serde_json::Value::Number)
+            }
+            TableFieldType::Boolean | TableFieldType::Bool => serde_json::Value::Bool(bool::decode(value)?),
+            TableFieldType::Timestamp => {
+                let v = OffsetDateTime::decode(value)?;
+                serde_json::Value::String(v.format(&Rfc3339).map_err(|_| super::value::Error::InvalidType)?)
+            }
+            _ => serde_json::Value::String(String::decode(value)?),
+        })
+    }
 }
 
 pub mod value {
     use std::num::ParseIntError;
     use std::ops::AddAssign;
     use std::str::FromStr;
+    use std::sync::Arc;
     use std::time::Duration;
 
     use base64::prelude::BASE64_STANDARD;
@@ -92,9 +200,11 @@ pub mod value {
     use bigdecimal::BigDecimal;
     use time::error::ComponentRange;
     use time::macros::format_description;
-    use time::{Date, OffsetDateTime, Time};
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
 
+    use crate::http::table::TableSchema;
     use crate::http::tabledata::list::{Tuple, Value};
+    use crate::http::types::{Interval, ParseIntervalError};
 
     #[derive(thiserror::Error, Debug)]
     pub enum Error {
@@ -114,6 +224,10 @@ pub mod value {
         ParseBigDecimal(#[from] bigdecimal::ParseBigDecimalError),
         #[error(transparent)]
         ParseTime(#[from] ParseIntError),
+        #[error(transparent)]
+        ParseInterval(#[from] ParseIntervalError),
+        #[error(transparent)]
+        ParseJson(#[from] serde_json::Error),
     }
 
     pub trait Decodable: Sized {
@@ -122,6 +236,12 @@ pub mod value {
 
     pub trait StructDecodable: Sized {
         fn decode(value: Tuple) -> Result<Self, Error>;
+
+        /// Decodes the row with the schema of the query result.
+        /// The schema is ignored by default.
+        fn decode_with_schema(value: Tuple, _schema: &Arc<TableSchema>) -> Result<Self, Error> {
+            Self::decode(value)
+        }
     }
 
     impl<T: StructDecodable> Decodable for T {
@@ -207,6 +327,26 @@ pub mod value {
         }
     }
 
+    impl Decodable for Interval {
+        fn decode(value: &Value) -> Result<Self, Error> {
+            match value {
+                Value::String(v) => Ok(v.parse()?),
+                Value::Null => Err(Error::UnexpectedNullValue),
+                _ => Err(Error::InvalidType),
+            }
+        }
+    }
+
+    impl Decodable for serde_json::Value {
+        fn decode(value: &Value) -> Result<Self, Error> {
+            match value {
+                Value::String(v) => Ok(serde_json::from_str(v)?),
+                Value::Null => Err(Error::UnexpectedNullValue),
+                _ => Err(Error::InvalidType),
+            }
+        }
+    }
+
     impl Decodable for Date {
         fn decode(value: &Value) -> Result<Self, Error> {
             match value {
@@ -235,6 +375,28 @@ pub mod value {
         }
     }
 
+    impl Decodable for PrimitiveDateTime {
+        fn decode(value: &Value) -> Result<Self, Error> {
+            match value {
+                Value::String(v) => {
+                    // ex) 2024-03-04T05:06:07.123456
+                    let (datetime, fraction) = v.split_once('.').unwrap_or((v, ""));
+                    let datetime = PrimitiveDateTime::parse(
+                        datetime,
+                        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+                    )?;
+                    if fraction.is_empty() {
+                        return Ok(datetime);
+                    }
+                    let micro: u32 = format!("{fraction:0<6}")[..6].parse()?;
+                    Ok(datetime.replace_microsecond(micro)?)
+                }
+                Value::Null => Err(Error::UnexpectedNullValue),
+                _ => Err(Error::InvalidType),
+            }
+        }
+    }
+
     impl<T> Decodable for Vec<T>
     where
         T: Decodable,
@@ -265,4 +427,34 @@ pub mod value {
             }
         }
     }
+    #[cfg(test)]
+    mod tests {
+        use time::macros::datetime;
+        use time::PrimitiveDateTime;
+
+        use crate::http::query::value::{Decodable, Error};
+        use crate::http::tabledata::list::Value;
+
+        #[test]
+        fn test_decode_datetime() {
+            let decode = |v: &str| PrimitiveDateTime::decode(&Value::String(v.to_string()));
+            assert_eq!(decode("2024-03-04T05:06:07").unwrap(), datetime!(2024-03-04 05:06:07));
+            assert_eq!(decode("2024-03-04T05:06:07.12").unwrap(), datetime!(2024-03-04 05:06:07.12));
+            assert_eq!(
+                decode("2024-03-04T05:06:07.123456").unwrap(),
+                datetime!(2024-03-04 05:06:07.123456)
+            );
+            // BigQuery stores microseconds, so that the rest digits are truncated.
+            assert_eq!(
+                decode("2024-03-04T05:06:07.123456789").unwrap(),
+                datetime!(2024-03-04 05:06:07.123456)
+            );
+            assert!(decode("2024-03-04 05:06:07").is_err());
+            assert!(matches!(
+                PrimitiveDateTime::decode(&Value::Null),
+                Err(Error::UnexpectedNullValue)
+            ));
+            assert_eq!(Option::<PrimitiveDateTime>::decode(&Value::Null).unwrap(), None);
+        }
+    }
 }
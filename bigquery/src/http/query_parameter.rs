@@ -0,0 +1,518 @@
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use serde_json::{Map, Value};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use crate::http::job::query::QueryRequest;
+use crate::http::types::{QueryParameter, QueryParameterStructType, QueryParameterType, QueryParameterValue};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Serialize(#[from] serde_json::Error),
+    #[error("unsupported parameter value: {0}")]
+    UnsupportedValue(String),
+    #[error("named and positional parameters cannot be mixed")]
+    MixedParameterMode,
+}
+
+/// A Rust type that can be bound as a GoogleSQL query parameter.
+/// The BigQuery type is derived from the Rust type.
+pub trait IntoQueryParam {
+    fn parameter_type() -> QueryParameterType;
+    fn parameter_value(self) -> QueryParameterValue;
+}
+
+fn scalar_type(name: &str) -> QueryParameterType {
+    QueryParameterType {
+        parameter_type: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn scalar_value(value: Option<String>) -> QueryParameterValue {
+    QueryParameterValue {
+        value,
+        ..Default::default()
+    }
+}
+
+macro_rules! impl_scalar {
+    ($type_name:literal, $($t:ty),+) => {
+        $(
+            impl IntoQueryParam for $t {
+                fn parameter_type() -> QueryParameterType {
+                    scalar_type($type_name)
+                }
+                fn parameter_value(self) -> QueryParameterValue {
+                    scalar_value(Some(self.to_string()))
+                }
+            }
+        )+
+    };
+}
+
+impl_scalar!("INT64", i64, i32, i16, i8, u32, u16, u8);
+impl_scalar!("FLOAT64", f64, f32);
+impl_scalar!("BOOL", bool);
+impl_scalar!("STRING", String, &str);
+impl_scalar!("NUMERIC", BigDecimal);
+
+/// Binds as `TIMESTAMP`, formatted in UTC as RFC3339 with microsecond precision.
+/// Nanoseconds are truncated since BigQuery stores microseconds.
+impl IntoQueryParam for OffsetDateTime {
+    fn parameter_type() -> QueryParameterType {
+        scalar_type("TIMESTAMP")
+    }
+    fn parameter_value(self) -> QueryParameterValue {
+        let v = self.to_offset(UtcOffset::UTC);
+        scalar_value(Some(format!(
+            "{}T{:02}:{:02}:{:02}.{:06}Z",
+            format_date(v.date()),
+            v.hour(),
+            v.minute(),
+            v.second(),
+            v.microsecond()
+        )))
+    }
+}
+
+/// Binds as `DATE`, formatted as `YYYY-MM-DD`.
+impl IntoQueryParam for Date {
+    fn parameter_type() -> QueryParameterType {
+        scalar_type("DATE")
+    }
+    fn parameter_value(self) -> QueryParameterValue {
+        scalar_value(Some(format_date(self)))
+    }
+}
+
+/// Binds as `DATETIME`, formatted as `YYYY-MM-DD HH:MM:SS.FFFFFF`.
+/// Nanoseconds are truncated since BigQuery stores microseconds.
+impl IntoQueryParam for PrimitiveDateTime {
+    fn parameter_type() -> QueryParameterType {
+        scalar_type("DATETIME")
+    }
+    fn parameter_value(self) -> QueryParameterValue {
+        scalar_value(Some(format!(
+            "{} {:02}:{:02}:{:02}.{:06}",
+            format_date(self.date()),
+            self.hour(),
+            self.minute(),
+            self.second(),
+            self.microsecond()
+        )))
+    }
+}
+
+fn format_date(v: Date) -> String {
+    format!("{:04}-{:02}-{:02}", v.year(), v.month() as u8, v.day())
+}
+
+impl QueryParameter {
+    /// Creates a named `TIMESTAMP` parameter.
+    pub fn timestamp(name: &str, value: OffsetDateTime) -> Self {
+        Self::named(name, value)
+    }
+
+    /// Creates a named `DATE` parameter.
+    pub fn date(name: &str, value: Date) -> Self {
+        Self::named(name, value)
+    }
+
+    /// Creates a named `DATETIME` parameter.
+    pub fn datetime(name: &str, value: PrimitiveDateTime) -> Self {
+        Self::named(name, value)
+    }
+
+    fn named<T: IntoQueryParam>(name: &str, value: T) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            parameter_type: T::parameter_type(),
+            parameter_value: value.parameter_value(),
+        }
+    }
+}
+
+impl<T: IntoQueryParam> IntoQueryParam for Option<T> {
+    fn parameter_type() -> QueryParameterType {
+        T::parameter_type()
+    }
+    fn parameter_value(self) -> QueryParameterValue {
+        match self {
+            Some(v) => v.parameter_value(),
+            None => scalar_value(None),
+        }
+    }
+}
+
+impl<T: IntoQueryParam> IntoQueryParam for Vec<T> {
+    fn parameter_type() -> QueryParameterType {
+        QueryParameterType {
+            parameter_type: "ARRAY".to_string(),
+            array_type: Some(Box::new(T::parameter_type())),
+            struct_types: None,
+        }
+    }
+    fn parameter_value(self) -> QueryParameterValue {
+        QueryParameterValue {
+            array_values: Some(self.into_iter().map(IntoQueryParam::parameter_value).collect()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Builder for the query parameters of a `QueryRequest`.
+///
+/// ```rust
+/// use google_cloud_bigquery::http::job::query::QueryRequest;
+/// use google_cloud_bigquery::http::query_parameter::QueryParameters;
+///
+/// #[derive(serde::Serialize)]
+/// struct Item {
+///     name: String,
+///     count: i64,
+/// }
+///
+/// fn run() -> Result<QueryRequest, google_cloud_bigquery::http::query_parameter::Error> {
+///     let items = vec![Item { name: "a".to_string(), count: 1 }];
+///     let mut request = QueryRequest {
+///         query: "SELECT * FROM UNNEST(@items) WHERE count > @min".to_string(),
+///         ..Default::default()
+///     };
+///     QueryParameters::new()
+///         .add_named("min", 0_i64)
+///         .add_named_serialize("items", &items)?
+///         .apply(&mut request)?;
+///     Ok(request)
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct QueryParameters {
+    mode: Option<&'static str>,
+    mixed: bool,
+    parameters: Vec<QueryParameter>,
+}
+
+impl QueryParameters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a named parameter referenced as `@name` in the query.
+    pub fn add_named<T: IntoQueryParam>(self, name: &str, value: T) -> Self {
+        self.push(Some(name), T::parameter_type(), value.parameter_value())
+    }
+
+    /// Adds a positional parameter referenced as `?` in the query.
+    pub fn add_positional<T: IntoQueryParam>(self, value: T) -> Self {
+        self.push(None, T::parameter_type(), value.parameter_value())
+    }
+
+    /// Adds a named parameter from a `serde::Serialize` value.
+    /// Structs become `STRUCT` and sequences become `ARRAY` parameters.
+    /// Struct fields keep their declaration order and the type of an array is taken from its first non-null element.
+    /// Integers beyond the range of `INT64` become `NUMERIC`.
+    /// `None` becomes a `NULL` of type `STRING` since its type is unknown; use `add_named` with an `Option` for a typed `NULL`.
+    pub fn add_named_serialize<T: serde::Serialize + ?Sized>(self, name: &str, value: &T) -> Result<Self, Error> {
+        let (parameter_type, parameter_value) = from_json(&serde_json::to_value(value)?)?;
+        Ok(self.push(Some(name), parameter_type, parameter_value))
+    }
+
+    /// Adds a positional parameter from a `serde::Serialize` value.
+    pub fn add_positional_serialize<T: serde::Serialize + ?Sized>(self, value: &T) -> Result<Self, Error> {
+        let (parameter_type, parameter_value) = from_json(&serde_json::to_value(value)?)?;
+        Ok(self.push(None, parameter_type, parameter_value))
+    }
+
+    /// Returns `NAMED` or `POSITIONAL` depending on the parameters added.
+    pub fn parameter_mode(&self) -> Option<&'static str> {
+        self.mode
+    }
+
+    pub fn parameters(&self) -> &[QueryParameter] {
+        &self.parameters
+    }
+
+    /// Sets `parameter_mode` and `query_parameters` of the request.
+    /// Returns an error if both named and positional parameters are added since BigQuery accepts only one mode.
+    pub fn apply(self, request: &mut QueryRequest) -> Result<(), Error> {
+        if self.mixed {
+            return Err(Error::MixedParameterMode);
+        }
+        request.parameter_mode = self.mode.map(|v| v.to_string());
+        request.query_parameters = self.parameters;
+        Ok(())
+    }
+
+    fn push(
+        mut self,
+        name: Option<&str>,
+        parameter_type: QueryParameterType,
+        parameter_value: QueryParameterValue,
+    ) -> Self {
+        let mode = if name.is_some() { "NAMED" } else { "POSITIONAL" };
+        self.mixed |= self.mode.is_some_and(|v| v != mode);
+        self.mode = Some(mode);
+        self.parameters.push(QueryParameter {
+            name: name.map(|v| v.to_string()),
+            parameter_type,
+            parameter_value,
+        });
+        self
+    }
+}
+
+fn from_json(value: &Value) -> Result<(QueryParameterType, QueryParameterValue), Error> {
+    Ok((json_type(value)?, json_value(value)))
+}
+
+fn json_type(value: &Value) -> Result<QueryParameterType, Error> {
+    Ok(match value {
+        Value::Null => scalar_type("STRING"),
+        Value::Bool(_) => scalar_type("BOOL"),
+        Value::Number(v) if v.is_f64() => scalar_type("FLOAT64"),
+        Value::Number(v) if v.is_i64() => scalar_type("INT64"),
+        Value::Number(_) => scalar_type("NUMERIC"),
+        Value::String(_) => scalar_type("STRING"),
+        Value::Array(values) => {
+            let element = values.iter().find(|v| !v.is_null()).ok_or_else(|| {
+                Error::UnsupportedValue("cannot infer the element type of an empty array".to_string())
+            })?;
+            let mut element_type = json_type(element)?;
+            // an array of u64 is NUMERIC when any of the elements exceeds INT64.
+            if element_type.parameter_type == "INT64" && values.iter().any(|v| v.is_u64() && !v.is_i64()) {
+                element_type = scalar_type("NUMERIC");
+            }
+            QueryParameterType {
+                parameter_type: "ARRAY".to_string(),
+                array_type: Some(Box::new(element_type)),
+                struct_types: None,
+            }
+        }
+        Value::Object(fields) => QueryParameterType {
+            parameter_type: "STRUCT".to_string(),
+            array_type: None,
+            struct_types: Some(json_struct_types(fields)?),
+        },
+    })
+}
+
+fn json_struct_types(fields: &Map<String, Value>) -> Result<Vec<QueryParameterStructType>, Error> {
+    fields
+        .iter()
+        .map(|(name, value)| {
+            Ok(QueryParameterStructType {
+                name: Some(name.clone()),
+                field_type: json_type(value)?,
+                description: None,
+            })
+        })
+        .collect()
+}
+
+fn json_value(value: &Value) -> QueryParameterValue {
+    match value {
+        Value::Null => scalar_value(None),
+        Value::Bool(v) => scalar_value(Some(v.to_string())),
+        Value::Number(v) => scalar_value(Some(v.to_string())),
+        Value::String(v) => scalar_value(Some(v.clone())),
+        Value::Array(values) => QueryParameterValue {
+            array_values: Some(values.iter().map(json_value).collect()),
+            ..Default::default()
+        },
+        Value::Object(fields) => QueryParameterValue {
+            struct_values: Some(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), json_value(value)))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::http::job::query::QueryRequest;
+    use crate::http::query_parameter::{Error, QueryParameters};
+    use crate::http::types::QueryParameter;
+
+    #[derive(serde::Serialize)]
+    struct Val {
+        val1: String,
+        val2: i64,
+    }
+
+    #[test]
+    fn test_scalar_and_array() {
+        let params = QueryParameters::new()
+            .add_named("p1", 1_i64)
+            .add_named("p2", "a")
+            .add_named("p3", vec![1.5_f64])
+            .add_named("p4", None::<bool>);
+        assert_eq!(params.parameter_mode(), Some("NAMED"));
+        let p = params.parameters();
+        assert_eq!(p[0].name.as_deref(), Some("p1"));
+        assert_eq!(p[0].parameter_type.parameter_type, "INT64");
+        assert_eq!(p[0].parameter_value.value.as_deref(), Some("1"));
+        assert_eq!(p[1].parameter_type.parameter_type, "STRING");
+        assert_eq!(p[2].parameter_type.parameter_type, "ARRAY");
+        assert_eq!(p[2].parameter_type.array_type.as_ref().unwrap().parameter_type, "FLOAT64");
+        assert_eq!(
+            p[2].parameter_value.array_values.as_ref().unwrap()[0].value.as_deref(),
+            Some("1.5")
+        );
+        assert_eq!(p[3].parameter_type.parameter_type, "BOOL");
+        assert!(p[3].parameter_value.value.is_none());
+    }
+
+    #[test]
+    fn test_positional() {
+        let mut request = QueryRequest::default();
+        QueryParameters::new()
+            .add_positional(10_i64)
+            .add_positional(true)
+            .apply(&mut request)
+            .unwrap();
+        assert_eq!(request.parameter_mode.as_deref(), Some("POSITIONAL"));
+        assert_eq!(request.query_parameters.len(), 2);
+        assert!(request.query_parameters[0].name.is_none());
+        assert_eq!(request.query_parameters[1].parameter_type.parameter_type, "BOOL");
+    }
+
+    #[test]
+    fn test_array_of_struct() {
+        let values = vec![
+            Val {
+                val1: "a".to_string(),
+                val2: 1,
+            },
+            Val {
+                val1: "b".to_string(),
+                val2: 2,
+            },
+        ];
+        let params = QueryParameters::new().add_named_serialize("p1", &values).unwrap();
+        let p = &params.parameters()[0];
+        assert_eq!(p.parameter_type.parameter_type, "ARRAY");
+        let element = p.parameter_type.array_type.as_ref().unwrap();
+        assert_eq!(element.parameter_type, "STRUCT");
+        let fields = element.struct_types.as_ref().unwrap();
+        assert_eq!(fields[0].name.as_deref(), Some("val1"));
+        assert_eq!(fields[0].field_type.parameter_type, "STRING");
+        assert_eq!(fields[1].name.as_deref(), Some("val2"));
+        assert_eq!(fields[1].field_type.parameter_type, "INT64");
+        let second = &p.parameter_value.array_values.as_ref().unwrap()[1];
+        let struct_values = second.struct_values.as_ref().unwrap();
+        assert_eq!(struct_values["val1"].value.as_deref(), Some("b"));
+        assert_eq!(struct_values["val2"].value.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_struct_field_order() {
+        #[derive(serde::Serialize)]
+        struct Row {
+            zeta: i64,
+            alpha: String,
+        }
+        let row = Row {
+            zeta: 1,
+            alpha: "a".to_string(),
+        };
+        let params = QueryParameters::new().add_named_serialize("p1", &row).unwrap();
+        let fields = params.parameters()[0].parameter_type.struct_types.as_ref().unwrap();
+        assert_eq!(fields[0].name.as_deref(), Some("zeta"));
+        assert_eq!(fields[1].name.as_deref(), Some("alpha"));
+    }
+
+    #[test]
+    fn test_timestamp_and_date() {
+        let p = QueryParameter::timestamp("ts", datetime!(2024-03-04 05:06:07.123456789 +09:00));
+        assert_eq!(p.name.as_deref(), Some("ts"));
+        assert_eq!(p.parameter_type.parameter_type, "TIMESTAMP");
+        assert_eq!(p.parameter_value.value.as_deref(), Some("2024-03-03T20:06:07.123456Z"));
+
+        let p = QueryParameter::timestamp("ts", datetime!(1969-12-31 23:59:59.5 UTC));
+        assert_eq!(p.parameter_value.value.as_deref(), Some("1969-12-31T23:59:59.500000Z"));
+
+        let p = QueryParameter::date("d", date!(0900 - 01 - 02));
+        assert_eq!(p.parameter_type.parameter_type, "DATE");
+        assert_eq!(p.parameter_value.value.as_deref(), Some("0900-01-02"));
+
+        let params = QueryParameters::new().add_named("dates", vec![date!(1960 - 02 - 29)]);
+        let p = &params.parameters()[0];
+        assert_eq!(p.parameter_type.array_type.as_ref().unwrap().parameter_type, "DATE");
+        assert_eq!(
+            p.parameter_value.array_values.as_ref().unwrap()[0].value.as_deref(),
+            Some("1960-02-29")
+        );
+    }
+
+    #[test]
+    fn test_datetime() {
+        let p = QueryParameter::datetime("dt", datetime!(2024-03-04 05:06:07.123456789));
+        assert_eq!(p.name.as_deref(), Some("dt"));
+        assert_eq!(p.parameter_type.parameter_type, "DATETIME");
+        assert_eq!(p.parameter_value.value.as_deref(), Some("2024-03-04 05:06:07.123456"));
+
+        let params = QueryParameters::new().add_named("dts", vec![Some(datetime!(0900-01-02 0:00))]);
+        let p = &params.parameters()[0];
+        assert_eq!(p.parameter_type.array_type.as_ref().unwrap().parameter_type, "DATETIME");
+        assert_eq!(
+            p.parameter_value.array_values.as_ref().unwrap()[0].value.as_deref(),
+            Some("0900-01-02 00:00:00.000000")
+        );
+    }
+
+    #[test]
+    fn test_mixed_mode_is_error() {
+        let mut request = QueryRequest::default();
+        let result = QueryParameters::new()
+            .add_named("p1", 1_i64)
+            .add_positional(true)
+            .apply(&mut request);
+        assert!(matches!(result, Err(Error::MixedParameterMode)));
+        assert!(request.parameter_mode.is_none());
+        assert!(request.query_parameters.is_empty());
+    }
+
+    #[test]
+    fn test_null() {
+        let params = QueryParameters::new()
+            .add_named_serialize("p1", &None::<Val>)
+            .unwrap()
+            .add_named_serialize("p2", &serde_json::Value::Null)
+            .unwrap();
+        for p in params.parameters() {
+            assert_eq!(p.parameter_type.parameter_type, "STRING");
+            assert!(p.parameter_value.value.is_none());
+        }
+    }
+
+    #[test]
+    fn test_u64_beyond_int64() {
+        let params = QueryParameters::new()
+            .add_named_serialize("p1", &u64::MAX)
+            .unwrap()
+            .add_named_serialize("p2", &vec![1_u64, u64::MAX])
+            .unwrap()
+            .add_named_serialize("p3", &1_u64)
+            .unwrap();
+        let p = params.parameters();
+        assert_eq!(p[0].parameter_type.parameter_type, "NUMERIC");
+        assert_eq!(p[0].parameter_value.value.as_deref(), Some("18446744073709551615"));
+        assert_eq!(p[1].parameter_type.array_type.as_ref().unwrap().parameter_type, "NUMERIC");
+        assert_eq!(p[2].parameter_type.parameter_type, "INT64");
+    }
+
+    #[test]
+    fn test_empty_array_is_error() {
+        let values: Vec<Val> = vec![];
+        assert!(QueryParameters::new().add_named_serialize("p1", &values).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::http::types::{StandardSqlDataType, StandardSqlField};
+use crate::http::types::{StandardSqlDataType, StandardSqlField, TypeKind};
 
 pub mod delete;
 pub mod get;
@@ -211,3 +211,155 @@ pub struct Routine {
     /// Optional. Spark specific options.
     pub spark_options: Option<SparkOptions>,
 }
+
+impl Argument {
+    /// Creates a named argument of the given type.
+    pub fn new(name: &str, type_kind: TypeKind) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            data_type: StandardSqlDataType { type_kind },
+            ..Default::default()
+        }
+    }
+
+    pub fn with_mode(mut self, value: Mode) -> Self {
+        self.mode = Some(value);
+        self
+    }
+}
+
+impl Routine {
+    /// Creates a SQL scalar function.
+    /// `definition_body` is the expression inside the parentheses of the AS clause.
+    /// ```rust
+    /// use google_cloud_bigquery::http::routine::{Argument, Routine};
+    /// use google_cloud_bigquery::http::types::TypeKind;
+    ///
+    /// let routine = Routine::sql_function("project", "dataset", "AddFour", "x + 4")
+    ///     .with_argument(Argument::new("x", TypeKind::Int64))
+    ///     .with_return_type(TypeKind::Int64);
+    /// ```
+    pub fn sql_function(project_id: &str, dataset_id: &str, routine_id: &str, definition_body: &str) -> Self {
+        Self::new(
+            project_id,
+            dataset_id,
+            routine_id,
+            RoutineType::ScalarFunction,
+            Language::Sql,
+            definition_body,
+        )
+    }
+
+    /// Creates a JavaScript scalar function. The return type is required for JavaScript.
+    /// ```rust
+    /// use google_cloud_bigquery::http::routine::{Argument, DeterminismLevel, Routine};
+    /// use google_cloud_bigquery::http::types::TypeKind;
+    ///
+    /// let routine = Routine::javascript_function("project", "dataset", "Greet", "return 'hello ' + name;", TypeKind::String)
+    ///     .with_argument(Argument::new("name", TypeKind::String))
+    ///     .with_determinism_level(DeterminismLevel::Deterministic);
+    /// ```
+    pub fn javascript_function(
+        project_id: &str,
+        dataset_id: &str,
+        routine_id: &str,
+        definition_body: &str,
+        return_type: TypeKind,
+    ) -> Self {
+        Self::new(
+            project_id,
+            dataset_id,
+            routine_id,
+            RoutineType::ScalarFunction,
+            Language::Javascript,
+            definition_body,
+        )
+        .with_return_type(return_type)
+    }
+
+    /// Creates a SQL stored procedure.
+    /// `definition_body` is the statements between BEGIN and END.
+    pub fn procedure(project_id: &str, dataset_id: &str, routine_id: &str, definition_body: &str) -> Self {
+        Self::new(
+            project_id,
+            dataset_id,
+            routine_id,
+            RoutineType::Procedure,
+            Language::Sql,
+            definition_body,
+        )
+    }
+
+    fn new(
+        project_id: &str,
+        dataset_id: &str,
+        routine_id: &str,
+        routine_type: RoutineType,
+        language: Language,
+        definition_body: &str,
+    ) -> Self {
+        Self {
+            routine_reference: RoutineReference {
+                project_id: project_id.to_string(),
+                dataset_id: dataset_id.to_string(),
+                routine_id: routine_id.to_string(),
+            },
+            routine_type,
+            language: Some(language),
+            definition_body: definition_body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_argument(mut self, value: Argument) -> Self {
+        self.arguments.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
+    pub fn with_return_type(mut self, value: TypeKind) -> Self {
+        self.return_type = Some(StandardSqlDataType { type_kind: value });
+        self
+    }
+
+    pub fn with_description(mut self, value: &str) -> Self {
+        self.description = Some(value.to_string());
+        self
+    }
+
+    pub fn with_determinism_level(mut self, value: DeterminismLevel) -> Self {
+        self.determinism_level = Some(value);
+        self
+    }
+
+    pub fn with_imported_libraries(mut self, value: Vec<String>) -> Self {
+        self.imported_libraries = Some(value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::routine::{Argument, Language, Mode, Routine, RoutineType};
+    use crate::http::types::TypeKind;
+
+    #[test]
+    fn test_javascript_function() {
+        let routine = Routine::javascript_function("p", "d", "f", "return x * 2;", TypeKind::Float64)
+            .with_argument(Argument::new("x", TypeKind::Float64));
+        assert_eq!(routine.routine_type, RoutineType::ScalarFunction);
+        assert_eq!(routine.language, Some(Language::Javascript));
+        assert_eq!(routine.return_type.unwrap().type_kind, TypeKind::Float64);
+        let value = serde_json::to_value(routine.arguments.unwrap()).unwrap();
+        assert_eq!(value[0]["name"], "x");
+        assert_eq!(value[0]["dataType"]["typeKind"], "FLOAT64");
+    }
+
+    #[test]
+    fn test_procedure() {
+        let routine = Routine::procedure("p", "d", "proc", "SELECT 1;")
+            .with_argument(Argument::new("x", TypeKind::Int64).with_mode(Mode::Inout));
+        assert_eq!(routine.routine_type, RoutineType::Procedure);
+        assert_eq!(routine.language, Some(Language::Sql));
+        assert_eq!(routine.arguments.unwrap()[0].mode, Some(Mode::Inout));
+    }
+}
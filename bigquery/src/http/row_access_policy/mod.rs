@@ -1,3 +1,7 @@
+use time::OffsetDateTime;
+
+pub mod delete;
+pub mod get;
 pub mod get_iam_policy;
 pub mod list;
 pub mod test_iam_permissions;
@@ -19,13 +23,18 @@ pub struct RowAccessPolicyReference {
 #[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RowAccessPolicy {
-    /// Required. The ID of the project containing this table.
-    pub project_id: String,
-    /// Required. The ID of the dataset containing this table.
-    pub dataset_id: String,
-    /// Required. The ID of the table containing this row access policy.
-    pub table_id: String,
-    /// Required. The ID of the row access policy.
-    /// The ID must contain only letters (a-z, A-Z), numbers (0-9), or underscores (_). The maximum length is 256 characters.
-    pub policy_id: String,
+    /// Output only. A hash of this resource.
+    pub etag: String,
+    /// Required. Reference describing the ID of this row access policy.
+    pub row_access_policy_reference: RowAccessPolicyReference,
+    /// Required.
+    /// A SQL boolean expression that represents the rows defined by this row access policy,
+    /// similar to the boolean expression in a WHERE clause of a SELECT query on a table.
+    pub filter_predicate: String,
+    /// Output only. The time when this row access policy was created.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub creation_time: Option<OffsetDateTime>,
+    /// Output only. The time when this row access policy was last modified.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_modified_time: Option<OffsetDateTime>,
 }
@@ -64,6 +64,27 @@ pub struct Error {
     pub errors: Vec<ErrorMessage>,
 }
 
+/// Reasons of the row errors that may succeed when the row is sent again.
+/// `stopped` is reported for the valid rows of a request that was rejected because of other rows.
+pub(crate) const TRANSIENT_ROW_ERROR_REASONS: [&str; 5] = [
+    "backendError",
+    "rateLimitExceeded",
+    "internalError",
+    "timeout",
+    "stopped",
+];
+
+impl Error {
+    /// Returns true if all the errors of the row are transient.
+    pub fn is_transient(&self) -> bool {
+        !self.errors.is_empty()
+            && self
+                .errors
+                .iter()
+                .all(|e| TRANSIENT_ROW_ERROR_REASONS.contains(&e.reason.as_str()))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InsertAllResponse {
@@ -72,6 +93,26 @@ pub struct InsertAllResponse {
     pub insert_errors: Option<Vec<Error>>,
 }
 
+impl InsertAllResponse {
+    /// Returns true if any row of the request failed to be inserted.
+    pub fn has_errors(&self) -> bool {
+        self.insert_errors.as_ref().is_some_and(|errors| !errors.is_empty())
+    }
+
+    /// Returns the index of each failed row in the request's `rows` together with its errors, ordered by index.
+    /// Use this to resend only the failed subset of the rows.
+    pub fn failed_rows(&self) -> Vec<(usize, &Vec<ErrorMessage>)> {
+        let mut rows: Vec<(usize, &Vec<ErrorMessage>)> = self
+            .insert_errors
+            .iter()
+            .flatten()
+            .map(|e| (e.index as usize, &e.errors))
+            .collect();
+        rows.sort_by_key(|(index, _)| *index);
+        rows
+    }
+}
+
 pub fn build<T: Serialize>(
     base_url: &str,
     client: &Client,
@@ -86,3 +127,53 @@ pub fn build<T: Serialize>(
     );
     client.post(url).json(data)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::http::tabledata::insert_all::{Error, ErrorMessage, InsertAllResponse};
+
+    fn row_error(reasons: &[&str]) -> Error {
+        Error {
+            index: 0,
+            errors: reasons
+                .iter()
+                .map(|r| ErrorMessage {
+                    reason: r.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(row_error(&["backendError"]).is_transient());
+        assert!(row_error(&["rateLimitExceeded", "stopped"]).is_transient());
+        assert!(!row_error(&["invalid"]).is_transient());
+        assert!(!row_error(&["invalid", "backendError"]).is_transient());
+        assert!(!row_error(&[]).is_transient());
+    }
+
+    #[test]
+    fn test_failed_rows() {
+        let mut second = row_error(&["invalid"]);
+        second.index = 5;
+        let mut first = row_error(&["stopped"]);
+        first.index = 2;
+        let response = InsertAllResponse {
+            kind: "bigquery#tableDataInsertAllResponse".to_string(),
+            insert_errors: Some(vec![second, first]),
+        };
+        assert!(response.has_errors());
+        let failed = response.failed_rows();
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].0, 2);
+        assert_eq!(failed[0].1[0].reason, "stopped");
+        assert_eq!(failed[1].0, 5);
+        assert_eq!(failed[1].1[0].reason, "invalid");
+
+        let response = InsertAllResponse::default();
+        assert!(!response.has_errors());
+        assert!(response.failed_rows().is_empty());
+    }
+}
@@ -1,7 +1,13 @@
+use std::collections::VecDeque;
 use std::fmt::Debug;
+use std::marker::PhantomData;
 
 use reqwest_middleware::{ClientWithMiddleware as Client, RequestBuilder};
 
+use crate::http::bigquery_tabledata_client::BigqueryTabledataClient;
+use crate::http::query::value::StructDecodable;
+use crate::http::query::Error;
+
 #[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Debug)]
 #[serde(untagged)]
 pub enum Value {
@@ -72,3 +78,39 @@ pub fn build(
     );
     client.get(url).query(&data)
 }
+
+/// Iterator reads the rows of the table page by page with tabledata.list.
+/// Since tabledata.list returns no schema, the rows are decoded by the column index.
+pub struct Iterator<T: StructDecodable> {
+    pub(crate) client: BigqueryTabledataClient,
+    pub(crate) project_id: String,
+    pub(crate) dataset_id: String,
+    pub(crate) table_id: String,
+    pub(crate) request: FetchDataRequest,
+    pub(crate) chunk: VecDeque<Tuple>,
+    pub total_rows: u64,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<T: StructDecodable> Iterator<T> {
+    pub async fn next(&mut self) -> Result<Option<T>, Error> {
+        loop {
+            if let Some(v) = self.chunk.pop_front() {
+                return Ok(T::decode(v).map(Some)?);
+            }
+            if self.request.page_token.is_none() {
+                return Ok(None);
+            }
+            let response = self
+                .client
+                .read(&self.project_id, &self.dataset_id, &self.table_id, &self.request)
+                .await?;
+            self.total_rows = response.total_rows;
+            self.request.page_token = response.page_token;
+            match response.rows {
+                Some(rows) => self.chunk = VecDeque::from(rows),
+                None => return Ok(None),
+            }
+        }
+    }
+}
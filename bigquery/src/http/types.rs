@@ -294,3 +294,94 @@ pub struct ErrorProto {
     /// A human-readable description of the error.
     pub message: Option<String>,
 }
+
+/// Value of the INTERVAL type.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub nanos: i64,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid interval: {0}")]
+pub struct ParseIntervalError(String);
+
+impl std::str::FromStr for Interval {
+    type Err = ParseIntervalError;
+
+    /// Parses the canonical format `[sign]Y-M [sign]D [sign]H:M:S[.F]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseIntervalError(s.to_string());
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        if parts.len() != 3 {
+            return Err(err());
+        }
+        let (negative_ym, ym) = split_sign(parts[0]);
+        let (years, months) = ym.split_once('-').ok_or_else(err)?;
+        let months = years.parse::<i32>().map_err(|_| err())? * 12 + months.parse::<i32>().map_err(|_| err())?;
+
+        let days = parts[1].parse::<i32>().map_err(|_| err())?;
+
+        let (negative_hms, hms) = split_sign(parts[2]);
+        let (hms, fraction) = match hms.split_once('.') {
+            Some((hms, fraction)) => (hms, fraction),
+            None => (hms, ""),
+        };
+        let hms: Vec<&str> = hms.split(':').collect();
+        if hms.len() != 3 || fraction.len() > 9 {
+            return Err(err());
+        }
+        let mut nanos = 0_i64;
+        for v in hms {
+            nanos = nanos * 60 + v.parse::<i64>().map_err(|_| err())?;
+        }
+        nanos *= 1_000_000_000;
+        if !fraction.is_empty() {
+            nanos += format!("{fraction:0<9}").parse::<i64>().map_err(|_| err())?;
+        }
+        Ok(Self {
+            months: if negative_ym { -months } else { months },
+            days,
+            nanos: if negative_hms { -nanos } else { nanos },
+        })
+    }
+}
+
+fn split_sign(v: &str) -> (bool, &str) {
+    match v.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, v.strip_prefix('+').unwrap_or(v)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::types::Interval;
+
+    #[test]
+    fn test_parse_interval() {
+        let v: Interval = "1-2 3 4:5:6.789".parse().unwrap();
+        assert_eq!(
+            v,
+            Interval {
+                months: 14,
+                days: 3,
+                nanos: ((4 * 60 + 5) * 60 + 6) * 1_000_000_000 + 789_000_000,
+            }
+        );
+        let v: Interval = "-1-2 -3 -0:0:1".parse().unwrap();
+        assert_eq!(
+            v,
+            Interval {
+                months: -14,
+                days: -3,
+                nanos: -1_000_000_000,
+            }
+        );
+        let v: Interval = "0-0 0 0:0:0".parse().unwrap();
+        assert_eq!(v, Interval::default());
+        assert!("1-2 3".parse::<Interval>().is_err());
+        assert!("a-b 3 0:0:0".parse::<Interval>().is_err());
+    }
+}
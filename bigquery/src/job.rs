@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use crate::http;
+use crate::http::job::JobReference;
+use crate::http::table::DestinationFormat;
+use crate::http::types::ErrorProto;
+
+#[derive(Debug, Clone)]
+pub struct WaitConfig {
+    /// Interval of the first poll.
+    pub(crate) poll_interval: Duration,
+    /// Upper bound of the poll interval.
+    pub(crate) max_poll_interval: Duration,
+    /// Factor by which the poll interval grows after each poll.
+    pub(crate) factor: f64,
+    /// Total time to wait for the job. `None` waits forever.
+    /// The default is 6 hours, the maximum execution time of the query and load jobs.
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl Default for WaitConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(500),
+            max_poll_interval: Duration::from_secs(10),
+            factor: 2.0,
+            timeout: Some(Duration::from_secs(6 * 60 * 60)),
+        }
+    }
+}
+
+impl WaitConfig {
+    pub fn with_poll_interval(mut self, value: Duration) -> Self {
+        self.poll_interval = value;
+        self
+    }
+    pub fn with_max_poll_interval(mut self, value: Duration) -> Self {
+        self.max_poll_interval = value;
+        self
+    }
+    pub fn with_factor(mut self, value: f64) -> Self {
+        self.factor = value;
+        self
+    }
+    pub fn with_timeout(mut self, value: Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+    /// Waits for the job forever.
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    pub(crate) fn next_interval(&self, current: Duration) -> Duration {
+        current.mul_f64(self.factor.max(1.0)).min(self.max_poll_interval)
+    }
+}
+
+/// Options of the extract job created by `Client::extract_table`.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    pub(crate) destination_format: Option<DestinationFormat>,
+    /// DEFLATE, GZIP, NONE, SNAPPY or ZSTD.
+    pub(crate) compression: Option<String>,
+    pub(crate) field_delimiter: Option<String>,
+    pub(crate) print_header: Option<bool>,
+    pub(crate) location: Option<String>,
+    pub(crate) wait: WaitConfig,
+}
+
+impl ExtractOptions {
+    pub fn with_destination_format(mut self, value: DestinationFormat) -> Self {
+        self.destination_format = Some(value);
+        self
+    }
+    pub fn with_compression(mut self, value: impl Into<String>) -> Self {
+        self.compression = Some(value.into());
+        self
+    }
+    pub fn with_field_delimiter(mut self, value: impl Into<String>) -> Self {
+        self.field_delimiter = Some(value.into());
+        self
+    }
+    pub fn with_print_header(mut self, value: bool) -> Self {
+        self.print_header = Some(value);
+        self
+    }
+    pub fn with_location(mut self, value: impl Into<String>) -> Self {
+        self.location = Some(value.into());
+        self
+    }
+    pub fn with_wait_config(mut self, value: WaitConfig) -> Self {
+        self.wait = value;
+        self
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Http(#[from] http::error::Error),
+    #[error("job failed: reason={:?}, message={:?}", .0.reason, .0.message)]
+    JobFailed(ErrorProto),
+    #[error("timed out waiting for job completion: {0:?}")]
+    Timeout(JobReference),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::job::WaitConfig;
+
+    #[test]
+    fn test_next_interval() {
+        let config = WaitConfig::default()
+            .with_poll_interval(Duration::from_secs(1))
+            .with_max_poll_interval(Duration::from_secs(5))
+            .with_factor(2.0);
+        let mut interval = config.poll_interval;
+        let mut intervals = vec![];
+        for _ in 0..4 {
+            interval = config.next_interval(interval);
+            intervals.push(interval.as_secs());
+        }
+        assert_eq!(intervals, vec![2, 4, 5, 5]);
+    }
+
+    #[test]
+    fn test_timeout() {
+        assert_eq!(WaitConfig::default().timeout, Some(Duration::from_secs(21600)));
+        assert!(WaitConfig::default().without_timeout().timeout.is_none());
+    }
+}
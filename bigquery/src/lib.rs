@@ -86,11 +86,17 @@
 //! * time::OffsetDateTime (for TIMESTAMP)
 //! * time::Date (for DATE)
 //! * time::Time (for TIME)
+//! * time::PrimitiveDateTime (for DATETIME)
+//! * String (for GEOGRAPHY as WKT)
+//! * serde_json::Value (for JSON)
+//! * google_cloud_bigquery::http::types::Interval (for INTERVAL)
 //! * T: StructDecodable (for STRUCT)
 //!   - [Example](https://github.com/yoshidan/google-cloud-rust/blob/082f4553e65ffe54d80a81f316a3eee6ddb10093/bigquery/src/http/bigquery_client.rs#L156)
 //! * Option (for all NULLABLE)
 //! * Vec (for ARRAY)
 //!
+//! DATETIME read by the Storage Read API is decoded without the offset since it has no time zone.
+//!
 //! ### Insert Data
 //!
 //! #### Table data API
@@ -188,6 +194,7 @@
 pub mod client;
 pub mod grpc;
 pub mod http;
+pub mod job;
 pub mod query;
 pub mod storage;
 pub mod storage_write;
@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+
 pub use backon::*;
 
+use crate::http::dataset::DatasetReference;
+use crate::http::job::Priority;
+use crate::http::table::TableSchema;
+use crate::http::types::ConnectionProperty;
 use crate::{http, storage};
 
 #[derive(Debug, Clone)]
@@ -30,6 +36,67 @@ impl QueryOption {
     }
 }
 
+/// Job level settings for the query job created by `Client::query_with_job_config`.
+/// Values set here take precedence over the same fields of the `QueryRequest`.
+#[derive(Debug, Clone, Default)]
+pub struct QueryJobConfig {
+    /// Job id of the query job. A unique id is generated if unset.
+    pub(crate) job_id: Option<String>,
+    pub(crate) labels: Option<HashMap<String, String>>,
+    pub(crate) priority: Option<Priority>,
+    pub(crate) use_query_cache: Option<bool>,
+    pub(crate) maximum_bytes_billed: Option<i64>,
+    pub(crate) connection_properties: Option<Vec<ConnectionProperty>>,
+    pub(crate) default_dataset: Option<DatasetReference>,
+    pub(crate) job_timeout_ms: Option<i64>,
+}
+
+impl QueryJobConfig {
+    pub fn with_job_id(mut self, value: impl Into<String>) -> Self {
+        self.job_id = Some(value.into());
+        self
+    }
+    pub fn with_labels(mut self, value: HashMap<String, String>) -> Self {
+        self.labels = Some(value);
+        self
+    }
+    pub fn with_priority(mut self, value: Priority) -> Self {
+        self.priority = Some(value);
+        self
+    }
+    pub fn with_use_query_cache(mut self, value: bool) -> Self {
+        self.use_query_cache = Some(value);
+        self
+    }
+    pub fn with_maximum_bytes_billed(mut self, value: i64) -> Self {
+        self.maximum_bytes_billed = Some(value);
+        self
+    }
+    pub fn with_connection_properties(mut self, value: Vec<ConnectionProperty>) -> Self {
+        self.connection_properties = Some(value);
+        self
+    }
+    pub fn with_default_dataset(mut self, value: DatasetReference) -> Self {
+        self.default_dataset = Some(value);
+        self
+    }
+    pub fn with_job_timeout_ms(mut self, value: i64) -> Self {
+        self.job_timeout_ms = Some(value);
+        self
+    }
+}
+
+/// Estimated cost of a query returned by `Client::estimate_query`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryCostEstimate {
+    /// Bytes the query will process.
+    pub total_bytes_processed: i64,
+    /// Accuracy of `total_bytes_processed`, such as `PRECISE`, `LOWER_BOUND` or `UPPER_BOUND`.
+    pub total_bytes_processed_accuracy: Option<String>,
+    /// Schema of the query result.
+    pub schema: Option<TableSchema>,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -58,9 +125,14 @@ impl<T: http::query::value::StructDecodable + storage::value::StructDecodable> I
 }
 
 pub mod row {
+    use std::sync::Arc;
+
+    use crate::http::table::TableSchema;
     use crate::http::tabledata::list::Tuple;
     use crate::{http, storage};
     use arrow::array::ArrayRef;
+    use arrow::datatypes::Fields;
+    use serde::de::DeserializeOwned;
 
     #[derive(thiserror::Error, Debug)]
     pub enum Error {
@@ -89,6 +161,77 @@ pub mod row {
                 RowType::Storage(row) => row.column(index)?,
             })
         }
+
+        /// Returns the value of the column with the name.
+        pub fn column_by_name<T: http::query::value::Decodable + storage::value::Decodable>(
+            &self,
+            name: &str,
+        ) -> Result<T, Error> {
+            Ok(match &self.inner {
+                RowType::Http(row) => row.column_by_name(name)?,
+                RowType::Storage(row) => row.column_by_name(name)?,
+            })
+        }
+
+        /// Returns the value of the column whose name matches ignoring the ASCII case,
+        /// for the queries whose column names are cased inconsistently.
+        /// ```rust
+        /// use google_cloud_bigquery::http::job::query::QueryRequest;
+        /// use google_cloud_bigquery::query::row::Row;
+        /// use google_cloud_bigquery::client::Client;
+        ///
+        /// async fn run(client: &Client, project_id: &str) {
+        ///     let request = QueryRequest {
+        ///         query: "SELECT 'a' AS UserName".to_string(),
+        ///         ..Default::default()
+        ///     };
+        ///     let mut iter = client.query::<Row>(project_id, request).await.unwrap();
+        ///     while let Some(row) = iter.next().await.unwrap() {
+        ///         let name = row.column_by_name_ci::<String>("username").unwrap();
+        ///     }
+        /// }
+        /// ```
+        pub fn column_by_name_ci<T: http::query::value::Decodable + storage::value::Decodable>(
+            &self,
+            name: &str,
+        ) -> Result<T, Error> {
+            Ok(match &self.inner {
+                RowType::Http(row) => row.column_by_name_ci(name)?,
+                RowType::Storage(row) => row.column_by_name_ci(name)?,
+            })
+        }
+
+        /// Deserializes the row into `T` by matching the column names to the fields of `T`.
+        /// ```rust
+        /// use google_cloud_bigquery::http::job::query::QueryRequest;
+        /// use google_cloud_bigquery::query::row::Row;
+        /// use google_cloud_bigquery::client::Client;
+        ///
+        /// #[derive(serde::Deserialize)]
+        /// struct MyRow {
+        ///     name: String,
+        ///     count: Option<i64>,
+        ///     #[serde(with = "time::serde::rfc3339")]
+        ///     created_at: time::OffsetDateTime,
+        /// }
+        ///
+        /// async fn run(client: &Client, project_id: &str) {
+        ///     let request = QueryRequest {
+        ///         query: "SELECT name, count, created_at FROM dataset.table".to_string(),
+        ///         ..Default::default()
+        ///     };
+        ///     let mut iter = client.query::<Row>(project_id, request).await.unwrap();
+        ///     while let Some(row) = iter.next().await.unwrap() {
+        ///         let row: MyRow = row.deserialize().unwrap();
+        ///     }
+        /// }
+        /// ```
+        pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+            Ok(match &self.inner {
+                RowType::Http(row) => row.deserialize()?,
+                RowType::Storage(row) => row.deserialize()?,
+            })
+        }
     }
 
     impl http::query::value::StructDecodable for Row {
@@ -97,6 +240,12 @@ pub mod row {
                 inner: RowType::Http(http::query::row::Row::decode(value)?),
             })
         }
+
+        fn decode_with_schema(value: Tuple, schema: &Arc<TableSchema>) -> Result<Self, http::query::value::Error> {
+            Ok(Self {
+                inner: RowType::Http(http::query::row::Row::decode_with_schema(value, schema)?),
+            })
+        }
     }
 
     impl storage::value::StructDecodable for Row {
@@ -105,6 +254,16 @@ pub mod row {
                 inner: RowType::Storage(storage::row::Row::decode_arrow(fields, row_no)?),
             })
         }
+
+        fn decode_arrow_with_fields(
+            fields: &[ArrayRef],
+            names: &Fields,
+            row_no: usize,
+        ) -> Result<Self, storage::value::Error> {
+            Ok(Self {
+                inner: RowType::Storage(storage::row::Row::decode_arrow_with_fields(fields, names, row_no)?),
+            })
+        }
     }
 }
 
@@ -3,13 +3,15 @@ use std::io::{BufReader, Cursor};
 
 use arrow::error::ArrowError;
 use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
 pub use arrow::*;
+use futures_util::Stream;
 
-use google_cloud_gax::grpc::{Status, Streaming};
+use google_cloud_gax::grpc::{Code, Status, Streaming};
 use google_cloud_gax::retry::RetrySetting;
 use google_cloud_googleapis::cloud::bigquery::storage::v1::read_rows_response::{Rows, Schema};
 use google_cloud_googleapis::cloud::bigquery::storage::v1::{
-    ArrowSchema, ReadRowsRequest, ReadRowsResponse, ReadSession,
+    ArrowSchema, ReadRowsRequest, ReadRowsResponse, ReadSession, ReadStream,
 };
 
 use crate::grpc::apiv1::bigquery_client::StreamingReadClient;
@@ -31,105 +33,213 @@ pub enum Error {
     NoSchemaFound,
 }
 
-pub struct Iterator<T>
-where
-    T: StructDecodable,
-{
+/// The number of times to resume reading the stream consecutively from the last offset.
+const MAX_RESUME_COUNT: usize = 5;
+
+/// RecordBatchIterator reads the Arrow record batches from the streams of the read session in order.
+pub struct RecordBatchIterator {
     client: StreamingReadClient,
-    session: ReadSession,
+    streams: Vec<ReadStream>,
     retry: Option<RetrySetting>,
     // mutable
     stream_index: usize,
-    current_stream: Streaming<ReadRowsResponse>,
-    chunk: VecDeque<T>,
+    offset: i64,
+    resume_count: usize,
+    current_stream: Option<Streaming<ReadRowsResponse>>,
+    chunk: VecDeque<RecordBatch>,
     schema: Option<ArrowSchema>,
 }
 
-impl<T> Iterator<T>
-where
-    T: StructDecodable,
-{
+impl RecordBatchIterator {
     pub async fn new(
-        mut client: StreamingReadClient,
+        client: StreamingReadClient,
         session: ReadSession,
         retry: Option<RetrySetting>,
     ) -> Result<Self, Error> {
-        let current_stream = client
-            .read_rows(
-                ReadRowsRequest {
-                    read_stream: session.streams[0].name.to_string(),
-                    offset: 0,
-                },
-                retry.clone(),
-            )
-            .await?
-            .into_inner();
+        Self::new_with_streams(client, session.streams, retry).await
+    }
+
+    /// new_with_streams reads only the specified streams of the read session.
+    pub async fn new_with_streams(
+        mut client: StreamingReadClient,
+        streams: Vec<ReadStream>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Self, Error> {
+        let current_stream = match streams.first() {
+            Some(stream) => Some(Self::read_rows(&mut client, &stream.name, 0, retry.clone()).await?),
+            None => None,
+        };
         Ok(Self {
             client,
-            session,
+            streams,
             retry,
             current_stream,
             stream_index: 0,
+            offset: 0,
+            resume_count: 0,
             chunk: VecDeque::new(),
             schema: None,
         })
     }
 
-    pub async fn next(&mut self) -> Result<Option<T>, Error> {
+    /// offset returns the number of rows already received from the current stream.
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub async fn next(&mut self) -> Result<Option<RecordBatch>, Error> {
         loop {
-            if let Some(row) = self.chunk.pop_front() {
-                return Ok(Some(row));
+            if let Some(batch) = self.chunk.pop_front() {
+                return Ok(Some(batch));
             }
-            if let Some(rows) = self.current_stream.message().await? {
-                if self.schema.is_none() {
-                    match rows.schema.ok_or(Error::NoSchemaFound)? {
-                        Schema::ArrowSchema(schema) => self.schema = Some(schema),
-                        _ => return Err(Error::InvalidSchemaFormat),
+            let current_stream = match self.current_stream.as_mut() {
+                Some(stream) => stream,
+                None => return Ok(None),
+            };
+            match current_stream.message().await {
+                Ok(Some(res)) => {
+                    self.resume_count = 0;
+                    if self.schema.is_none() {
+                        match res.schema.ok_or(Error::NoSchemaFound)? {
+                            Schema::ArrowSchema(schema) => self.schema = Some(schema),
+                            _ => return Err(Error::InvalidSchemaFormat),
+                        }
+                    };
+                    self.offset += res.row_count;
+                    if let Some(rows) = res.rows {
+                        self.chunk = rows_to_record_batches(self.schema.as_ref().unwrap(), rows)?;
+                    }
+                    continue;
+                }
+                Ok(None) => {}
+                Err(status) => {
+                    // resume reading from the last offset when the stream is disconnected.
+                    if status.code() != Code::Unavailable || self.resume_count >= MAX_RESUME_COUNT {
+                        return Err(status.into());
                     }
-                };
-                if let Some(rows) = rows.rows {
-                    self.chunk = rows_to_chunk(self.schema.clone().unwrap(), rows)?;
-                    return Ok(self.chunk.pop_front());
+                    self.resume_count += 1;
+                    tracing::debug!(
+                        "resume reading stream: offset={}, count={}, status={:?}",
+                        self.offset,
+                        self.resume_count,
+                        status
+                    );
+                    let stream = &self.streams[self.stream_index].name;
+                    let resumed = Self::read_rows(&mut self.client, stream, self.offset, self.retry.clone()).await?;
+                    self.current_stream = Some(resumed);
+                    continue;
                 }
             }
 
-            if self.stream_index == self.session.streams.len() - 1 {
+            if self.stream_index == self.streams.len() - 1 {
+                self.current_stream = None;
                 return Ok(None);
             } else {
                 self.stream_index += 1
             }
-            let stream = &self.session.streams[self.stream_index].name;
-            self.current_stream = self
-                .client
-                .read_rows(
-                    ReadRowsRequest {
-                        read_stream: stream.to_string(),
-                        offset: 0,
-                    },
-                    self.retry.clone(),
-                )
-                .await?
-                .into_inner();
+            self.offset = 0;
+            let stream = &self.streams[self.stream_index].name;
+            let next = Self::read_rows(&mut self.client, stream, 0, self.retry.clone()).await?;
+            self.current_stream = Some(next);
         }
     }
+
+    async fn read_rows(
+        client: &mut StreamingReadClient,
+        stream: &str,
+        offset: i64,
+        retry: Option<RetrySetting>,
+    ) -> Result<Streaming<ReadRowsResponse>, Status> {
+        Ok(client
+            .read_rows(
+                ReadRowsRequest {
+                    read_stream: stream.to_string(),
+                    offset,
+                },
+                retry,
+            )
+            .await?
+            .into_inner())
+    }
 }
 
-fn rows_to_chunk<T>(schema: ArrowSchema, rows: Rows) -> Result<VecDeque<T>, Error>
+pub struct Iterator<T>
 where
     T: StructDecodable,
 {
+    inner: RecordBatchIterator,
+    // mutable
+    chunk: VecDeque<T>,
+}
+
+impl<T> Iterator<T>
+where
+    T: StructDecodable,
+{
+    pub async fn new(
+        client: StreamingReadClient,
+        session: ReadSession,
+        retry: Option<RetrySetting>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            inner: RecordBatchIterator::new(client, session, retry).await?,
+            chunk: VecDeque::new(),
+        })
+    }
+
+    /// new_with_streams reads only the specified streams of the read session.
+    pub async fn new_with_streams(
+        client: StreamingReadClient,
+        streams: Vec<ReadStream>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            inner: RecordBatchIterator::new_with_streams(client, streams, retry).await?,
+            chunk: VecDeque::new(),
+        })
+    }
+
+    pub async fn next(&mut self) -> Result<Option<T>, Error> {
+        loop {
+            if let Some(row) = self.chunk.pop_front() {
+                return Ok(Some(row));
+            }
+            match self.inner.next().await? {
+                Some(batch) => self.chunk = record_batch_to_chunk(&batch)?,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Reads the remaining rows as a stream.
+    pub fn into_stream(mut self) -> impl Stream<Item = Result<T, Error>> {
+        async_stream::try_stream! {
+            while let Some(row) = self.next().await? {
+                yield row;
+            }
+        }
+    }
+
+    /// Reads all the remaining rows into memory.
+    pub async fn collect(mut self) -> Result<Vec<T>, Error> {
+        let mut rows = vec![];
+        while let Some(row) = self.next().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+}
+
+fn rows_to_record_batches(schema: &ArrowSchema, rows: Rows) -> Result<VecDeque<RecordBatch>, Error> {
     match rows {
         Rows::ArrowRecordBatch(rows) => {
-            let mut rows_with_schema = schema.serialized_schema;
+            let mut rows_with_schema = schema.serialized_schema.clone();
             rows_with_schema.extend_from_slice(&rows.serialized_record_batch);
             let rows = Cursor::new(rows_with_schema);
             let rows: StreamReader<BufReader<Cursor<Vec<u8>>>> = StreamReader::try_new(BufReader::new(rows), None)?;
-            let mut chunk: VecDeque<T> = VecDeque::new();
-            for row in rows {
-                let row = row?;
-                for row_no in 0..row.num_rows() {
-                    chunk.push_back(T::decode_arrow(row.columns(), row_no)?)
-                }
+            let mut chunk = VecDeque::new();
+            for batch in rows {
+                chunk.push_back(batch?);
             }
             Ok(chunk)
         }
@@ -137,8 +247,27 @@ where
     }
 }
 
+fn record_batch_to_chunk<T>(batch: &RecordBatch) -> Result<VecDeque<T>, Error>
+where
+    T: StructDecodable,
+{
+    let mut chunk: VecDeque<T> = VecDeque::with_capacity(batch.num_rows());
+    for row_no in 0..batch.num_rows() {
+        chunk.push_back(T::decode_arrow_with_fields(batch.columns(), batch.schema().fields(), row_no)?)
+    }
+    Ok(chunk)
+}
+
 pub mod row {
-    use arrow::array::ArrayRef;
+    use arrow::array::{Array, ArrayRef, AsArray, ListArray};
+    use arrow::datatypes::{DataType, Fields};
+    use base64::prelude::*;
+    use bigdecimal::BigDecimal;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Map, Number, Value};
+    use time::format_description::well_known::Rfc3339;
+    use time::macros::format_description;
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
 
     use crate::storage::value::{Decodable, StructDecodable};
 
@@ -146,12 +275,21 @@ pub mod row {
     pub enum Error {
         #[error("UnexpectedColumnIndex: {0}")]
         UnexpectedColumnIndex(usize),
+        #[error("UnexpectedColumnName: {0}")]
+        UnexpectedColumnName(String),
         #[error(transparent)]
         ArrowError(#[from] super::value::Error),
+        #[error("no schema found for the row")]
+        NoSchemaFound,
+        #[error("failed to format {0}")]
+        Format(#[from] time::error::Format),
+        #[error(transparent)]
+        Deserialize(#[from] serde_json::Error),
     }
 
     pub struct Row {
         fields: Vec<ArrayRef>,
+        names: Option<Fields>,
         row_no: usize,
     }
 
@@ -159,6 +297,19 @@ pub mod row {
         fn decode_arrow(fields: &[ArrayRef], row_no: usize) -> Result<Row, super::value::Error> {
             Ok(Self {
                 fields: fields.to_vec(),
+                names: None,
+                row_no,
+            })
+        }
+
+        fn decode_arrow_with_fields(
+            fields: &[ArrayRef],
+            names: &Fields,
+            row_no: usize,
+        ) -> Result<Row, super::value::Error> {
+            Ok(Self {
+                fields: fields.to_vec(),
+                names: Some(names.clone()),
                 row_no,
             })
         }
@@ -169,6 +320,83 @@ pub mod row {
             let column = self.fields.get(index).ok_or(Error::UnexpectedColumnIndex(index))?;
             Ok(T::decode_arrow(column, self.row_no)?)
         }
+
+        /// Returns the value of the column with the name. The schema is required.
+        pub fn column_by_name<T: Decodable>(&self, name: &str) -> Result<T, Error> {
+            self.column(self.index_of(name, |v| v == name)?)
+        }
+
+        /// Returns the value of the column whose name matches ignoring the ASCII case. The schema is required.
+        pub fn column_by_name_ci<T: Decodable>(&self, name: &str) -> Result<T, Error> {
+            self.column(self.index_of(name, |v| v.eq_ignore_ascii_case(name))?)
+        }
+
+        fn index_of(&self, name: &str, matches: impl Fn(&str) -> bool) -> Result<usize, Error> {
+            let names = self.names.as_ref().ok_or(Error::NoSchemaFound)?;
+            names
+                .iter()
+                .position(|field| matches(field.name()))
+                .ok_or_else(|| Error::UnexpectedColumnName(name.to_string()))
+        }
+
+        /// Deserializes the row into `T` by matching the column names to the fields of `T`.
+        /// TIMESTAMP columns are provided as RFC3339 strings and BYTES columns as base64 encoded strings.
+        pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+            let names = self.names.as_ref().ok_or(Error::NoSchemaFound)?;
+            let value = struct_to_json(&self.fields, names, self.row_no)?;
+            Ok(serde_json::from_value(value)?)
+        }
+    }
+
+    fn struct_to_json(columns: &[ArrayRef], names: &Fields, row_no: usize) -> Result<Value, Error> {
+        let mut object = Map::with_capacity(columns.len());
+        for (column, field) in columns.iter().zip(names.iter()) {
+            object.insert(field.name().clone(), column_to_json(column.as_ref(), row_no)?);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn column_to_json(col: &dyn Array, row_no: usize) -> Result<Value, Error> {
+        if col.is_null(row_no) {
+            return Ok(Value::Null);
+        }
+        Ok(match col.data_type() {
+            DataType::Boolean => Value::Bool(bool::decode_arrow(col, row_no)?),
+            DataType::Int64 => Value::from(i64::decode_arrow(col, row_no)?),
+            DataType::Float64 => Number::from_f64(f64::decode_arrow(col, row_no)?).map_or(Value::Null, Value::Number),
+            DataType::Binary => Value::String(BASE64_STANDARD.encode(Vec::<u8>::decode_arrow(col, row_no)?)),
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                Value::String(super::value::decimal_to_string(BigDecimal::decode_arrow(col, row_no)?))
+            }
+            DataType::Date32 => {
+                Value::String(Date::decode_arrow(col, row_no)?.format(format_description!("[year]-[month]-[day]"))?)
+            }
+            DataType::Time64(_) => Value::String(
+                Time::decode_arrow(col, row_no)?
+                    .format(format_description!("[hour]:[minute]:[second].[subsecond digits:6]"))?,
+            ),
+            DataType::Timestamp(_, None) => Value::String(PrimitiveDateTime::decode_arrow(col, row_no)?.format(
+                format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:6]"),
+            )?),
+            DataType::Timestamp(_, _) => Value::String(OffsetDateTime::decode_arrow(col, row_no)?.format(&Rfc3339)?),
+            DataType::Struct(names) => {
+                let array = col.as_struct();
+                struct_to_json(array.columns(), names, row_no)?
+            }
+            DataType::List(_) => {
+                let list = col
+                    .as_any()
+                    .downcast_ref::<ListArray>()
+                    .ok_or(super::value::Error::InvalidDowncast(col.data_type().clone()))?;
+                let values = list.value(row_no);
+                let mut result = Vec::with_capacity(values.len());
+                for i in 0..values.len() {
+                    result.push(column_to_json(values.as_ref(), i)?);
+                }
+                Value::Array(result)
+            }
+            _ => Value::String(String::decode_arrow(col, row_no)?),
+        })
     }
 }
 
@@ -177,12 +405,14 @@ pub mod value {
 
     use arrow::array::{
         Array, ArrayRef, AsArray, BinaryArray, Date32Array, Decimal128Array, Decimal256Array, Float64Array, Int64Array,
-        ListArray, StringArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+        IntervalMonthDayNanoArray, ListArray, StringArray, Time64MicrosecondArray, TimestampMicrosecondArray,
     };
-    use arrow::datatypes::{DataType, TimeUnit};
+    use arrow::datatypes::{DataType, Fields, IntervalMonthDayNanoType, IntervalUnit, TimeUnit};
     use bigdecimal::BigDecimal;
-    use time::macros::date;
-    use time::{Date, Duration, OffsetDateTime, Time};
+    use time::macros::{date, format_description};
+    use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+    use crate::http::types::{Interval, ParseIntervalError};
 
     #[derive(thiserror::Error, Debug)]
     pub enum Error {
@@ -196,6 +426,12 @@ pub mod value {
         InvalidTime(#[from] time::error::ComponentRange),
         #[error(transparent)]
         InvalidDecimal(#[from] bigdecimal::ParseBigDecimalError),
+        #[error(transparent)]
+        InvalidInterval(#[from] ParseIntervalError),
+        #[error(transparent)]
+        InvalidJson(#[from] serde_json::Error),
+        #[error(transparent)]
+        InvalidFormat(#[from] time::error::Format),
     }
 
     /// https://cloud.google.com/bigquery/docs/reference/storage#arrow_schema_details
@@ -205,6 +441,12 @@ pub mod value {
 
     pub trait StructDecodable: Sized {
         fn decode_arrow(fields: &[ArrayRef], row_no: usize) -> Result<Self, Error>;
+
+        /// Decodes the row with the schema fields of the columns.
+        /// The fields are ignored by default.
+        fn decode_arrow_with_fields(fields: &[ArrayRef], _names: &Fields, row_no: usize) -> Result<Self, Error> {
+            Self::decode_arrow(fields, row_no)
+        }
     }
 
     impl<S> Decodable for S
@@ -213,7 +455,9 @@ pub mod value {
     {
         fn decode_arrow(col: &dyn Array, row_no: usize) -> Result<S, Error> {
             match col.data_type() {
-                DataType::Struct(_) => S::decode_arrow(downcast::<arrow::array::StructArray>(col)?.columns(), row_no),
+                DataType::Struct(fields) => {
+                    S::decode_arrow_with_fields(downcast::<arrow::array::StructArray>(col)?.columns(), fields, row_no)
+                }
                 _ => Err(Error::InvalidDataType(col.data_type().clone(), "struct")),
             }
         }
@@ -273,9 +517,11 @@ pub mod value {
                 return Err(Error::InvalidNullable);
             }
             match col.data_type() {
-                DataType::Decimal128(_, _) => BigDecimal::decode_arrow(col, row_no).map(|v| v.to_string()),
-                DataType::Decimal256(_, _) => BigDecimal::decode_arrow(col, row_no).map(|v| v.to_string()),
+                DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                    BigDecimal::decode_arrow(col, row_no).map(decimal_to_string)
+                }
                 DataType::Date32 => Date::decode_arrow(col, row_no).map(|v| v.to_string()),
+                DataType::Timestamp(_, None) => datetime_to_string(PrimitiveDateTime::decode_arrow(col, row_no)?),
                 DataType::Timestamp(_, _) => OffsetDateTime::decode_arrow(col, row_no).map(|v| v.to_string()),
                 DataType::Time64(_) => Time::decode_arrow(col, row_no).map(|v| v.to_string()),
                 DataType::Boolean => bool::decode_arrow(col, row_no).map(|v| v.to_string()),
@@ -287,6 +533,21 @@ pub mod value {
         }
     }
 
+    /// datetime_to_string formats DATETIME in the canonical format `YYYY-MM-DDTHH:MM:SS[.ffffff]` like the REST API does.
+    fn datetime_to_string(value: PrimitiveDateTime) -> Result<String, Error> {
+        let format = if value.microsecond() == 0 {
+            format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]")
+        } else {
+            format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:6]")
+        };
+        Ok(value.format(format)?)
+    }
+
+    /// decimal_to_string formats the decimal like the REST API does, without the trailing zeros of the scale.
+    pub(crate) fn decimal_to_string(value: BigDecimal) -> String {
+        value.normalized().to_plain_string()
+    }
+
     impl Decodable for BigDecimal {
         fn decode_arrow(col: &dyn Array, row_no: usize) -> Result<Self, Error> {
             if col.is_null(row_no) {
@@ -333,6 +594,35 @@ pub mod value {
         }
     }
 
+    impl Decodable for Interval {
+        fn decode_arrow(col: &dyn Array, row_no: usize) -> Result<Self, Error> {
+            if col.is_null(row_no) {
+                return Err(Error::InvalidNullable);
+            }
+            match col.data_type() {
+                DataType::Interval(IntervalUnit::MonthDayNano) => {
+                    let value = downcast::<IntervalMonthDayNanoArray>(col)?.value(row_no);
+                    let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(value);
+                    Ok(Interval { months, days, nanos })
+                }
+                DataType::Utf8 => Ok(downcast::<StringArray>(col)?.value(row_no).parse()?),
+                _ => Err(Error::InvalidDataType(col.data_type().clone(), "Interval")),
+            }
+        }
+    }
+
+    impl Decodable for serde_json::Value {
+        fn decode_arrow(col: &dyn Array, row_no: usize) -> Result<Self, Error> {
+            if col.is_null(row_no) {
+                return Err(Error::InvalidNullable);
+            }
+            match col.data_type() {
+                DataType::Utf8 => Ok(serde_json::from_str(downcast::<StringArray>(col)?.value(row_no))?),
+                _ => Err(Error::InvalidDataType(col.data_type().clone(), "JSON")),
+            }
+        }
+    }
+
     impl Decodable for Date {
         fn decode_arrow(col: &dyn Array, row_no: usize) -> Result<Self, Error> {
             if col.is_null(row_no) {
@@ -364,6 +654,23 @@ pub mod value {
         }
     }
 
+    impl Decodable for PrimitiveDateTime {
+        fn decode_arrow(col: &dyn Array, row_no: usize) -> Result<Self, Error> {
+            if col.is_null(row_no) {
+                return Err(Error::InvalidNullable);
+            }
+            match col.data_type() {
+                // DATETIME is the timestamp without the time zone.
+                DataType::Timestamp(TimeUnit::Microsecond, None) => {
+                    let micros = downcast::<TimestampMicrosecondArray>(col)?.value(row_no);
+                    let v = OffsetDateTime::from_unix_timestamp_nanos(micros as i128 * 1000)?;
+                    Ok(PrimitiveDateTime::new(v.date(), v.time()))
+                }
+                _ => Err(Error::InvalidDataType(col.data_type().clone(), "Datetime")),
+            }
+        }
+    }
+
     impl<T> Decodable for Option<T>
     where
         T: Decodable,
@@ -401,4 +708,40 @@ pub mod value {
             .downcast_ref::<T>()
             .ok_or(Error::InvalidDowncast(col.data_type().clone()))
     }
+
+    #[cfg(test)]
+    mod tests {
+        use arrow::array::{Int64Array, TimestampMicrosecondArray};
+        use time::macros::datetime;
+        use time::PrimitiveDateTime;
+
+        use crate::storage::value::{Decodable, Error};
+
+        #[test]
+        fn test_decode_datetime() {
+            let col = TimestampMicrosecondArray::from(vec![
+                Some(1_709_528_767_123_456),
+                Some(-1),
+                None,
+                Some(1_709_528_767_000_000),
+            ]);
+            let v = PrimitiveDateTime::decode_arrow(&col, 0).unwrap();
+            assert_eq!(v, datetime!(2024-03-04 05:06:07.123456));
+            let v = PrimitiveDateTime::decode_arrow(&col, 1).unwrap();
+            assert_eq!(v, datetime!(1969-12-31 23:59:59.999999));
+            assert!(matches!(PrimitiveDateTime::decode_arrow(&col, 2), Err(Error::InvalidNullable)));
+            assert_eq!(Option::<PrimitiveDateTime>::decode_arrow(&col, 2).unwrap(), None);
+
+            // DATETIME is formatted in the canonical format without the offset.
+            assert_eq!(String::decode_arrow(&col, 0).unwrap(), "2024-03-04T05:06:07.123456");
+            assert_eq!(String::decode_arrow(&col, 1).unwrap(), "1969-12-31T23:59:59.999999");
+            assert_eq!(String::decode_arrow(&col, 3).unwrap(), "2024-03-04T05:06:07");
+
+            let col = Int64Array::from(vec![1]);
+            assert!(matches!(
+                PrimitiveDateTime::decode_arrow(&col, 0),
+                Err(Error::InvalidDataType(_, "Datetime"))
+            ));
+        }
+    }
 }
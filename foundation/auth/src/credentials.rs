@@ -19,7 +19,7 @@ pub struct ServiceAccountImpersonationInfo {
 pub struct ExecutableConfig {
     pub(crate) command: String,
     pub(crate) timeout_millis: Option<i32>,
-    pub(crate) output_file: String,
+    pub(crate) output_file: Option<String>,
 }
 
 #[allow(dead_code)]
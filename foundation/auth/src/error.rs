@@ -8,6 +8,12 @@ pub enum Error {
     #[error("unsupported account {0}")]
     UnsupportedAccountType(String),
 
+    #[error("sub is only supported with service account credentials: {0}")]
+    SubRequiresServiceAccount(String),
+
+    #[error("scopes is required if the sub is specified, the audience can not be used with the sub")]
+    ScopeRequiredForSub,
+
     #[error("refresh token is required for user account credentials")]
     RefreshTokenIsRequired,
 
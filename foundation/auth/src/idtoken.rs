@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
 
 use crate::{
     credentials::CredentialsFile,
@@ -9,6 +12,11 @@ use crate::{
         service_account_token_source::OAuth2ServiceAccountTokenSource, TokenSource,
     },
 };
+#[cfg(feature = "external-account")]
+use crate::{project::EXTERNAL_ACCOUNT_KEY, token_source::impersonate_token_source::ImpersonateIdTokenSource};
+
+#[cfg(feature = "external-account")]
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
 
 #[derive(Clone, Default)]
 pub struct IdTokenSourceConfig {
@@ -73,7 +81,7 @@ pub(crate) async fn id_token_source_from_credentials(
     credentials: &CredentialsFile,
     audience: &str,
 ) -> Result<Box<dyn TokenSource>, error::Error> {
-    let ts = match credentials.tp.as_str() {
+    let ts: Box<dyn TokenSource> = match credentials.tp.as_str() {
         SERVICE_ACCOUNT_KEY => {
             let mut claims = custom_claims.clone();
             claims.insert("target_audience".into(), audience.into());
@@ -82,11 +90,78 @@ pub(crate) async fn id_token_source_from_credentials(
                 .with_use_id_token()
                 .with_private_claims(claims);
 
-            Ok(Box::new(source))
+            Ok(Box::new(source) as Box<dyn TokenSource>)
+        }
+        // the identity token is generated by impersonating the service account with the federated token.
+        #[cfg(feature = "external-account")]
+        EXTERNAL_ACCOUNT_KEY if credentials.service_account_impersonation_url.is_some() => {
+            let url = credentials
+                .service_account_impersonation_url
+                .as_deref()
+                .unwrap_or_default()
+                .replace(":generateAccessToken", ":generateIdToken");
+            let source = crate::token_source::external_account_source::ExternalAccountTokenSource::new(
+                CLOUD_PLATFORM_SCOPE.to_string(),
+                credentials.clone(),
+            )
+            .await?;
+            let source = ImpersonateIdTokenSource::new(
+                url,
+                credentials.delegates.clone().unwrap_or_default(),
+                audience,
+                Box::new(source),
+            );
+            Ok(Box::new(source) as Box<dyn TokenSource>)
         }
-        // TODO: support impersonation and external account
         _ => Err(error::Error::UnsupportedAccountType(credentials.tp.to_string())),
     }?;
     let token = ts.token().await?;
     Ok(Box::new(ReuseTokenSource::new(ts, token)))
 }
+
+/// Token source that returns an identity token for the audience.
+/// It can be used to call services that authenticate requests with identity tokens,
+/// such as Cloud Run or services protected by Identity-Aware Proxy.
+///
+/// The token is minted from the service account key file or the metadata server,
+/// in the same order of precedence as `DefaultTokenSourceProvider`.
+///
+/// ```no_run
+/// use google_cloud_auth::idtoken::IdTokenSource;
+/// use google_cloud_token::TokenSource;
+///
+/// async fn run() {
+///     let ts = IdTokenSource::new("https://my-service-xxxxx.a.run.app").await.unwrap();
+///     // "Bearer xxx"
+///     let authorization = ts.token().await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IdTokenSource {
+    inner: Arc<dyn TokenSource>,
+}
+
+impl IdTokenSource {
+    pub async fn new(audience: &str) -> Result<Self, error::Error> {
+        Self::new_with_config(IdTokenSourceConfig::new(), audience).await
+    }
+
+    pub async fn new_with_config(config: IdTokenSourceConfig, audience: &str) -> Result<Self, error::Error> {
+        let inner = create_id_token_source(config, audience).await?;
+        Ok(Self { inner: inner.into() })
+    }
+}
+
+#[async_trait]
+impl google_cloud_token::TokenSource for IdTokenSource {
+    async fn token(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.inner.token().await?;
+        Ok(token.value())
+    }
+}
+
+impl google_cloud_token::TokenSourceProvider for IdTokenSource {
+    fn token_source(&self) -> Arc<dyn google_cloud_token::TokenSource> {
+        Arc::new(self.clone())
+    }
+}
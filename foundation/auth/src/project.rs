@@ -9,13 +9,13 @@ use crate::token_source::compute_token_source::ComputeTokenSource;
 use crate::token_source::reuse_token_source::ReuseTokenSource;
 use crate::token_source::service_account_token_source::OAuth2ServiceAccountTokenSource;
 use crate::token_source::service_account_token_source::ServiceAccountTokenSource;
-use crate::token_source::TokenSource;
+use crate::token_source::{default_http_client, TokenSource};
 use crate::{credentials, error};
 
 pub(crate) const SERVICE_ACCOUNT_KEY: &str = "service_account";
 const USER_CREDENTIALS_KEY: &str = "authorized_user";
 #[cfg(feature = "external-account")]
-const EXTERNAL_ACCOUNT_KEY: &str = "external_account";
+pub(crate) const EXTERNAL_ACCOUNT_KEY: &str = "external_account";
 
 #[derive(Debug, Clone, Default)]
 pub struct Config<'a> {
@@ -23,6 +23,7 @@ pub struct Config<'a> {
     scopes: Option<&'a [&'a str]>,
     sub: Option<&'a str>,
     use_id_token: bool,
+    http_client: Option<reqwest::Client>,
 }
 
 impl<'a> Config<'a> {
@@ -43,6 +44,9 @@ impl<'a> Config<'a> {
         self
     }
 
+    /// Sets the user to impersonate with domain-wide delegation.
+    /// Only service account credentials are supported, and the OAuth 2.0 flow is always used
+    /// because a self-signed JWT can not be used for domain-wide delegation.
     pub fn with_sub(mut self, value: &'a str) -> Self {
         self.sub = Some(value);
         self
@@ -52,6 +56,17 @@ impl<'a> Config<'a> {
         self.use_id_token = value;
         self
     }
+
+    /// Uses the given HTTP client to call the token endpoints and the metadata server
+    /// instead of the default one. Use this to configure a proxy or custom root certificates.
+    pub fn with_http_client(mut self, value: reqwest::Client) -> Self {
+        self.http_client = Some(value);
+        self
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        self.http_client.clone().unwrap_or_else(default_http_client)
+    }
 }
 
 #[derive(Clone)]
@@ -126,15 +141,20 @@ pub async fn create_token_source_from_project(
             }
         }
         Project::FromMetadataServer(_) => {
+            if config.sub.is_some() {
+                return Err(error::Error::SubRequiresServiceAccount("metadata server".to_string()));
+            }
             if config.use_id_token {
-                let ts = ComputeIdentitySource::new(config.audience.unwrap_or_default())?;
+                let ts = ComputeIdentitySource::new(config.audience.unwrap_or_default())?
+                    .with_http_client(config.http_client());
                 let token = ts.token().await?;
                 Ok(Box::new(ReuseTokenSource::new(Box::new(ts), token)))
             } else {
                 if config.scopes.is_none() {
                     return Err(error::Error::ScopeOrAudienceRequired);
                 }
-                let ts = ComputeTokenSource::new(config.scopes_to_string(",").as_str())?;
+                let ts = ComputeTokenSource::new(config.scopes_to_string(",").as_str())?
+                    .with_http_client(config.http_client());
                 let token = ts.token().await?;
                 Ok(Box::new(ReuseTokenSource::new(Box::new(ts), token)))
             }
@@ -154,12 +174,19 @@ async fn credentials_from_json_with_params(
     credentials: &CredentialsFile,
     config: &Config<'_>,
 ) -> Result<Box<dyn TokenSource>, error::Error> {
+    if config.sub.is_some() && credentials.tp != SERVICE_ACCOUNT_KEY {
+        return Err(error::Error::SubRequiresServiceAccount(credentials.tp.to_string()));
+    }
     match credentials.tp.as_str() {
         SERVICE_ACCOUNT_KEY => {
-            match config.audience {
+            // domain-wide delegation is only supported by the OAuth 2.0 flow.
+            match config.audience.filter(|_| config.sub.is_none()) {
                 None => {
                     if config.scopes.is_none() {
-                        return Err(error::Error::ScopeOrAudienceRequired);
+                        return Err(match config.sub {
+                            Some(_) => error::Error::ScopeRequiredForSub,
+                            None => error::Error::ScopeOrAudienceRequired,
+                        });
                     }
 
                     // use Standard OAuth 2.0 Flow
@@ -167,7 +194,8 @@ async fn credentials_from_json_with_params(
                         credentials,
                         config.scopes_to_string(" ").as_str(),
                         config.sub,
-                    )?;
+                    )?
+                    .with_http_client(config.http_client());
                     Ok(Box::new(source))
                 }
                 Some(audience) => {
@@ -177,14 +205,17 @@ async fn credentials_from_json_with_params(
                 }
             }
         }
-        USER_CREDENTIALS_KEY => Ok(Box::new(UserAccountTokenSource::new(credentials)?)),
+        USER_CREDENTIALS_KEY => Ok(Box::new(
+            UserAccountTokenSource::new(credentials)?.with_http_client(config.http_client()),
+        )),
         #[cfg(feature = "external-account")]
         EXTERNAL_ACCOUNT_KEY => {
             let ts = crate::token_source::external_account_source::ExternalAccountTokenSource::new(
                 config.scopes_to_string(" "),
                 credentials.clone(),
             )
-            .await?;
+            .await?
+            .with_http_client(config.http_client());
             if let Some(impersonation_url) = &credentials.service_account_impersonation_url {
                 let url = impersonation_url.clone();
                 let mut scopes = config.scopes.map(|v| v.to_vec()).unwrap_or(vec![]);
@@ -196,11 +227,12 @@ async fn credentials_from_json_with_params(
                     .map(|v| v.token_lifetime_seconds);
                 let ts = crate::token_source::impersonate_token_source::ImpersonateTokenSource::new(
                     url,
-                    vec![],
+                    credentials.delegates.clone().unwrap_or_default(),
                     scopes,
                     lifetime,
                     Box::new(ts),
-                );
+                )
+                .with_http_client(config.http_client());
                 Ok(Box::new(ts))
             } else {
                 Ok(Box::new(ts))
@@ -209,3 +241,52 @@ async fn credentials_from_json_with_params(
         _ => Err(error::Error::UnsupportedAccountType(credentials.tp.to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::credentials::CredentialsFile;
+    use crate::error::Error;
+    use crate::project::{credentials_from_json_with_params, Config};
+
+    #[tokio::test]
+    async fn test_sub_requires_service_account() {
+        let credentials = CredentialsFile::new_from_str(
+            r#"{
+            "type": "authorized_user",
+            "client_id": "fake_client_id",
+            "client_secret": "fake_client_secret",
+            "refresh_token": "fake_refresh_token"
+        }"#,
+        )
+        .await
+        .unwrap();
+        let config = Config::default()
+            .with_scopes(&["https://www.googleapis.com/auth/drive"])
+            .with_sub("user@example.com");
+        match credentials_from_json_with_params(&credentials, &config).await {
+            Err(Error::SubRequiresServiceAccount(tp)) => assert_eq!(tp, "authorized_user"),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sub_requires_scopes() {
+        let credentials = CredentialsFile::new_from_str(
+            r#"{
+            "type": "service_account",
+            "client_email": "fake@example.iam.gserviceaccount.com",
+            "private_key_id": "fake_private_key_id",
+            "private_key": "fake_private_key"
+        }"#,
+        )
+        .await
+        .unwrap();
+        let config = Config::default()
+            .with_audience("https://example.com")
+            .with_sub("user@example.com");
+        match credentials_from_json_with_params(&credentials, &config).await {
+            Err(Error::ScopeRequiredForSub) => {}
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
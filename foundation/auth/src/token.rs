@@ -13,6 +13,7 @@ use crate::project::{
 use crate::token_source::TokenSource as InternalTokenSource;
 
 pub const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const QUOTA_PROJECT_ENV: &str = "GOOGLE_CLOUD_QUOTA_PROJECT";
 
 #[derive(Debug, Clone)]
 pub struct Token {
@@ -88,6 +89,15 @@ impl DefaultTokenSourceProvider {
             source_credentials,
         })
     }
+
+    /// The project billed for the quota of the requests, which is required by some APIs with the user credentials.
+    /// `GOOGLE_CLOUD_QUOTA_PROJECT` takes precedence over the `quota_project_id` of the credentials.
+    pub fn quota_project_id(&self) -> Option<String> {
+        std::env::var(QUOTA_PROJECT_ENV)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .or_else(|| self.source_credentials.as_ref()?.quota_project_id.clone())
+    }
 }
 
 impl TokenSourceProvider for DefaultTokenSourceProvider {
@@ -38,6 +38,11 @@ impl UserAccountTokenSource {
         };
         Ok(ts)
     }
+
+    pub(crate) fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -4,7 +4,7 @@ use serde::Deserialize;
 use time::OffsetDateTime;
 use urlencoding::encode;
 
-use google_cloud_metadata::{METADATA_FLAVOR_KEY, METADATA_GOOGLE, METADATA_HOST_ENV, METADATA_IP};
+use google_cloud_metadata::{metadata_host, METADATA_FLAVOR_KEY, METADATA_GOOGLE};
 
 use crate::error::Error;
 use crate::token::Token;
@@ -34,10 +34,7 @@ impl std::fmt::Debug for ComputeIdentitySource {
 
 impl ComputeIdentitySource {
     pub(crate) fn new(audience: &str) -> Result<ComputeIdentitySource, Error> {
-        let host = match std::env::var(METADATA_HOST_ENV) {
-            Ok(s) => s,
-            Err(_e) => METADATA_IP.to_string(),
-        };
+        let host = metadata_host();
 
         // Only used to extract the expiry without checking the signature.
         let mut validation = Validation::default();
@@ -56,6 +53,11 @@ impl ComputeIdentitySource {
             validation,
         })
     }
+
+    pub(crate) fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[derive(Deserialize)]
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use urlencoding::encode;
 
-use google_cloud_metadata::{METADATA_FLAVOR_KEY, METADATA_GOOGLE, METADATA_HOST_ENV, METADATA_IP};
+use google_cloud_metadata::{metadata_host, METADATA_FLAVOR_KEY, METADATA_GOOGLE};
 
 use crate::error::Error;
 use crate::token::Token;
@@ -17,10 +17,7 @@ pub struct ComputeTokenSource {
 
 impl ComputeTokenSource {
     pub(crate) fn new(scope: &str) -> Result<ComputeTokenSource, Error> {
-        let host = match std::env::var(METADATA_HOST_ENV) {
-            Ok(s) => s,
-            Err(_e) => METADATA_IP.to_string(),
-        };
+        let host = metadata_host();
 
         Ok(ComputeTokenSource {
             token_url: format!(
@@ -31,6 +28,11 @@ impl ComputeTokenSource {
             client: default_http_client(),
         })
     }
+
+    pub(crate) fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
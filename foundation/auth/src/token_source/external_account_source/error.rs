@@ -79,4 +79,31 @@ pub enum Error {
 
     #[error("Failed to get subject token: status={0}, detail={1}")]
     UnexpectedStatusOnGetSubjectToken(u16, String),
+
+    #[error("Executables need to be explicitly allowed (set GOOGLE_EXTERNAL_ACCOUNT_ALLOW_EXECUTABLES to '1') to run")]
+    ExecutablesNotAllowed,
+
+    #[error("Missing Command")]
+    MissingCommand,
+
+    #[error("Executable timeout must be between 5 and 120 seconds: {0}ms")]
+    InvalidExecutableTimeout(i32),
+
+    #[error("Executable timed out")]
+    ExecutableTimeout,
+
+    #[error("Executable failed : exit_code={0}, stderr={1}")]
+    ExecutableFailed(i32, String),
+
+    #[error("Invalid executable response : missing {0}")]
+    InvalidExecutableResponse(&'static str),
+
+    #[error("Executable response version {0} is not supported")]
+    UnsupportedExecutableVersion(i32),
+
+    #[error("Executable returned an error : code={0}, message={1}")]
+    ExecutableResponseError(String, String),
+
+    #[error("Executable response is expired")]
+    ExecutableResponseExpired,
 }
@@ -0,0 +1,189 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use tokio::process::Command;
+
+use crate::credentials::ExecutableConfig;
+use crate::token_source::external_account_source::error::Error;
+use crate::token_source::external_account_source::subject_token_source::SubjectTokenSource;
+
+const ALLOW_EXECUTABLES: &str = "GOOGLE_EXTERNAL_ACCOUNT_ALLOW_EXECUTABLES";
+const DEFAULT_TIMEOUT_MILLIS: i32 = 30 * 1000;
+const MIN_TIMEOUT_MILLIS: i32 = 5 * 1000;
+const MAX_TIMEOUT_MILLIS: i32 = 120 * 1000;
+const EXECUTABLE_SUPPORTED_MAX_VERSION: i32 = 1;
+
+const SAML_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:saml2";
+const JWT_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:jwt";
+const ID_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:id_token";
+
+#[derive(Deserialize)]
+struct ExecutableResponse {
+    version: Option<i32>,
+    success: Option<bool>,
+    token_type: Option<String>,
+    expiration_time: Option<i64>,
+    id_token: Option<String>,
+    saml_response: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+pub struct ExecutableSubjectTokenSource {
+    command: String,
+    timeout: Duration,
+    output_file: Option<String>,
+    envs: Vec<(&'static str, String)>,
+}
+
+impl ExecutableSubjectTokenSource {
+    pub fn new(
+        config: ExecutableConfig,
+        audience: Option<String>,
+        subject_token_type: String,
+        impersonated_email: Option<String>,
+    ) -> Result<Self, Error> {
+        if std::env::var(ALLOW_EXECUTABLES).unwrap_or_default() != "1" {
+            return Err(Error::ExecutablesNotAllowed);
+        }
+        if config.command.is_empty() {
+            return Err(Error::MissingCommand);
+        }
+        let timeout_millis = config.timeout_millis.unwrap_or(DEFAULT_TIMEOUT_MILLIS);
+        if !(MIN_TIMEOUT_MILLIS..=MAX_TIMEOUT_MILLIS).contains(&timeout_millis) {
+            return Err(Error::InvalidExecutableTimeout(timeout_millis));
+        }
+
+        let mut envs = vec![
+            ("GOOGLE_EXTERNAL_ACCOUNT_AUDIENCE", audience.unwrap_or_default()),
+            ("GOOGLE_EXTERNAL_ACCOUNT_TOKEN_TYPE", subject_token_type),
+            // interactive mode is not supported
+            ("GOOGLE_EXTERNAL_ACCOUNT_INTERACTIVE", "0".to_string()),
+        ];
+        if let Some(email) = impersonated_email {
+            envs.push(("GOOGLE_EXTERNAL_ACCOUNT_IMPERSONATED_EMAIL", email));
+        }
+        if let Some(output_file) = &config.output_file {
+            envs.push(("GOOGLE_EXTERNAL_ACCOUNT_OUTPUT_FILE", output_file.clone()));
+        }
+
+        Ok(Self {
+            command: config.command,
+            timeout: Duration::from_millis(timeout_millis as u64),
+            output_file: config.output_file,
+            envs,
+        })
+    }
+
+    async fn read_output_file(&self) -> Option<Result<String, Error>> {
+        let output_file = self.output_file.as_ref()?;
+        let content = tokio::fs::read_to_string(output_file).await.ok()?;
+        if content.trim().is_empty() {
+            return None;
+        }
+        match parse_response(&content, OffsetDateTime::now_utc()) {
+            // an expired cached response means the executable must be run again
+            Err(Error::ExecutableResponseExpired) => None,
+            other => Some(other),
+        }
+    }
+
+    async fn run_command(&self) -> Result<String, Error> {
+        let args = self.command.split_whitespace().collect::<Vec<_>>();
+        let (program, args) = args.split_first().ok_or(Error::MissingCommand)?;
+        let child = Command::new(program)
+            .args(args)
+            .envs(self.envs.iter().map(|(k, v)| (*k, v.as_str())))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| Error::ExecutableTimeout)??;
+        if !output.status.success() {
+            return Err(Error::ExecutableFailed(
+                output.status.code().unwrap_or(-1),
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+        parse_response(&String::from_utf8_lossy(&output.stdout), OffsetDateTime::now_utc())
+    }
+}
+
+#[async_trait]
+impl SubjectTokenSource for ExecutableSubjectTokenSource {
+    async fn subject_token(&self) -> Result<String, Error> {
+        if let Some(token) = self.read_output_file().await {
+            return token;
+        }
+        self.run_command().await
+    }
+}
+
+fn parse_response(content: &str, now: OffsetDateTime) -> Result<String, Error> {
+    let response: ExecutableResponse = serde_json::from_str(content)?;
+    let version = response.version.ok_or(Error::InvalidExecutableResponse("version"))?;
+    if version > EXECUTABLE_SUPPORTED_MAX_VERSION {
+        return Err(Error::UnsupportedExecutableVersion(version));
+    }
+    if !response.success.ok_or(Error::InvalidExecutableResponse("success"))? {
+        return Err(Error::ExecutableResponseError(
+            response.code.unwrap_or_default(),
+            response.message.unwrap_or_default(),
+        ));
+    }
+    if let Some(expiration_time) = response.expiration_time {
+        if expiration_time < now.unix_timestamp() {
+            return Err(Error::ExecutableResponseExpired);
+        }
+    }
+    let token = match response.token_type.as_deref() {
+        Some(SAML_TOKEN_TYPE) => response.saml_response,
+        Some(JWT_TOKEN_TYPE) | Some(ID_TOKEN_TYPE) => response.id_token,
+        Some(_) => return Err(Error::UnsupportedFormatType),
+        None => return Err(Error::InvalidExecutableResponse("token_type")),
+    };
+    token.ok_or(Error::MissingSubjectTokenFieldName)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::token_source::external_account_source::error::Error;
+    use crate::token_source::external_account_source::executable_subject_token_source::parse_response;
+
+    #[test]
+    fn test_parse_response() {
+        let now = datetime!(2024-01-01 00:00:00 UTC);
+        let jwt = r#"{"version":1,"success":true,"token_type":"urn:ietf:params:oauth:token-type:jwt","id_token":"jwt-token","expiration_time":1704070800}"#;
+        assert_eq!(parse_response(jwt, now).unwrap(), "jwt-token");
+
+        let saml = r#"{"version":1,"success":true,"token_type":"urn:ietf:params:oauth:token-type:saml2","saml_response":"saml-token"}"#;
+        assert_eq!(parse_response(saml, now).unwrap(), "saml-token");
+
+        let expired = r#"{"version":1,"success":true,"token_type":"urn:ietf:params:oauth:token-type:jwt","id_token":"jwt-token","expiration_time":1704063600}"#;
+        assert!(matches!(parse_response(expired, now), Err(Error::ExecutableResponseExpired)));
+
+        let failed = r#"{"version":1,"success":false,"code":"401","message":"denied"}"#;
+        match parse_response(failed, now) {
+            Err(Error::ExecutableResponseError(code, message)) => {
+                assert_eq!(code, "401");
+                assert_eq!(message, "denied");
+            }
+            _ => unreachable!(),
+        }
+
+        let unsupported = r#"{"version":2,"success":true}"#;
+        assert!(matches!(
+            parse_response(unsupported, now),
+            Err(Error::UnsupportedExecutableVersion(2))
+        ));
+    }
+}
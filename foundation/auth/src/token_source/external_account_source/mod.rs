@@ -14,6 +14,7 @@ use crate::token_source::{default_http_client, InternalToken, TokenSource};
 
 mod aws_subject_token_source;
 pub mod error;
+mod executable_subject_token_source;
 mod file_credential_source;
 mod subject_token_source;
 mod url_subject_token_source;
@@ -24,6 +25,8 @@ pub struct ExternalAccountTokenSource {
     url: String,
     audience: Option<String>,
     auth_header: Option<String>,
+    impersonated_email: Option<String>,
+    workforce_pool_user_project: Option<String>,
     scopes: String,
     client: reqwest::Client,
 }
@@ -42,7 +45,7 @@ impl ExternalAccountTokenSource {
                 credentials.client_id.as_ref().unwrap(),
                 credentials.client_secret.as_ref().unwrap()
             );
-            Some(format!("Basic: {}", BASE64_STANDARD.encode(plain_text)))
+            Some(format!("Basic {}", BASE64_STANDARD.encode(plain_text)))
         } else {
             None
         };
@@ -53,16 +56,32 @@ impl ExternalAccountTokenSource {
             url: credentials.token_url_external.ok_or(Error::MissingTokenURL)?,
             audience: credentials.audience,
             auth_header,
+            impersonated_email: credentials
+                .service_account_impersonation_url
+                .as_deref()
+                .and_then(impersonated_email),
+            workforce_pool_user_project: credentials.workforce_pool_user_project,
             scopes,
             client: default_http_client(),
         })
     }
+
+    pub(crate) fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
 impl TokenSource for ExternalAccountTokenSource {
     async fn token(&self) -> Result<Token, crate::error::Error> {
-        let subject_token_source = subject_token_source(self.audience.clone(), self.source.clone()).await?;
+        let subject_token_source = subject_token_source(
+            self.audience.clone(),
+            &self.subject_token_type,
+            self.impersonated_email.clone(),
+            self.source.clone(),
+        )
+        .await?;
 
         let mut builder = self.client.post(&self.url);
         if let Some(auth_header) = &self.auth_header {
@@ -74,8 +93,14 @@ impl TokenSource for ExternalAccountTokenSource {
             None => "",
         };
 
+        // The workforce pool user project is only sent when no client authentication is used.
+        let options = match (&self.auth_header, &self.workforce_pool_user_project) {
+            (None, Some(project)) => Some(serde_json::json!({ "userProject": project }).to_string()),
+            _ => None,
+        };
+
         let subject_token = subject_token_source.subject_token().await?;
-        let sts_request = vec![
+        let mut sts_request = vec![
             ("grant_type", "urn:ietf:params:oauth:grant-type:token-exchange"),
             ("audience", audience),
             ("scope", &self.scopes),
@@ -83,6 +108,9 @@ impl TokenSource for ExternalAccountTokenSource {
             ("subject_token", &subject_token),
             ("requested_token_type", "urn:ietf:params:oauth:token-type:access_token"),
         ];
+        if let Some(options) = options.as_ref() {
+            sts_request.push(("options", options));
+        }
         let response = builder.form(&sts_request).send().await?;
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -96,6 +124,8 @@ impl TokenSource for ExternalAccountTokenSource {
 
 async fn subject_token_source(
     audience: Option<String>,
+    subject_token_type: &str,
+    impersonated_email: Option<String>,
     source: CredentialSource,
 ) -> Result<Box<dyn SubjectTokenSource>, Error> {
     let environment_id = &source.environment_id.unwrap_or_empty();
@@ -111,8 +141,35 @@ async fn subject_token_source(
     } else if let Some(file) = source.file {
         let ts = file_credential_source::FileCredentialSource::new(file, source.format);
         Ok(Box::new(ts))
+    } else if let Some(executable) = source.executable {
+        let ts = executable_subject_token_source::ExecutableSubjectTokenSource::new(
+            executable,
+            audience,
+            subject_token_type.to_string(),
+            impersonated_email,
+        )?;
+        Ok(Box::new(ts))
     } else {
-        // TODO: support executable type
         Err(Error::UnsupportedSubjectTokenSource)
     }
 }
+
+/// Extracts the service account email from an impersonation url such as
+/// `https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{email}:generateAccessToken`.
+fn impersonated_email(url: &str) -> Option<String> {
+    let (_, rest) = url.rsplit_once("/serviceAccounts/")?;
+    let (email, _) = rest.split_once(':')?;
+    Some(email.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token_source::external_account_source::impersonated_email;
+
+    #[test]
+    fn test_impersonated_email() {
+        let url = "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/sa@project.iam.gserviceaccount.com:generateAccessToken";
+        assert_eq!(impersonated_email(url), Some("sa@project.iam.gserviceaccount.com".to_string()));
+        assert_eq!(impersonated_email("https://iamcredentials.googleapis.com/test"), None);
+    }
+}
@@ -11,15 +11,15 @@ use crate::token_source::external_account_source::subject_token_source::SubjectT
 pub struct UrlSubjectTokenSource {
     url: Url,
     headers: HashMap<String, String>,
-    format: Format,
+    format: Option<Format>,
 }
 
 impl UrlSubjectTokenSource {
     pub async fn new(value: CredentialSource) -> Result<Self, Error> {
         let url = value.url.ok_or(Error::MissingTokenURL)?;
         let url = Url::parse(&url).map_err(Error::URLError)?;
-        let headers = value.headers.ok_or(Error::MissingHeaders)?;
-        let format = value.format.ok_or(Error::MissingFormat)?;
+        let headers = value.headers.unwrap_or_default();
+        let format = value.format;
 
         Ok(Self { url, headers, format })
     }
@@ -41,11 +41,10 @@ impl UrlSubjectTokenSource {
         let body = response.text_with_charset("utf-8").await?;
         let body = body.chars().take(1 << 20).collect::<String>(); // Limiting the response body to 1MB
 
-        let format_type = self.format.tp.as_str();
-        match format_type {
+        match self.format.as_ref().map(|f| f.tp.as_str()).unwrap_or("") {
             "json" => {
                 let data: Value = serde_json::from_str(&body).map_err(Error::JsonError)?;
-                if let Some(token) = data[&self.format.subject_token_field_name].as_str() {
+                if let Some(token) = data[&self.format.as_ref().unwrap().subject_token_field_name].as_str() {
                     Ok(token.to_string())
                 } else {
                     Err(Error::MissingSubjectTokenFieldName)
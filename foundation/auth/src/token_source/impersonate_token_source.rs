@@ -4,7 +4,7 @@ use time::format_description::well_known::Rfc3339;
 
 use crate::error::Error;
 use crate::token::Token;
-use crate::token_source::{default_http_client, TokenSource};
+use crate::token_source::{default_http_client, InternalIdToken, TokenSource};
 
 #[derive(Debug)]
 pub struct ImpersonateTokenSource {
@@ -34,6 +34,12 @@ impl ImpersonateTokenSource {
             client: default_http_client(),
         }
     }
+
+    #[cfg(feature = "external-account")]
+    pub(crate) fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
@@ -72,6 +78,83 @@ impl TokenSource for ImpersonateTokenSource {
     }
 }
 
+/// Generates an identity token for the audience as the impersonated service account.
+/// The `url` is the `generateIdToken` endpoint of the service account.
+#[derive(Debug)]
+pub struct ImpersonateIdTokenSource {
+    target: Box<dyn TokenSource>,
+    audience: String,
+    delegates: Vec<String>,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl ImpersonateIdTokenSource {
+    #[cfg(feature = "external-account")]
+    pub(crate) fn new(url: String, delegates: Vec<String>, audience: &str, target: Box<dyn TokenSource>) -> Self {
+        ImpersonateIdTokenSource {
+            target,
+            audience: audience.to_string(),
+            delegates,
+            url,
+            client: default_http_client(),
+        }
+    }
+
+    fn request_body(&self) -> ImpersonateIdTokenRequest {
+        ImpersonateIdTokenRequest {
+            audience: self.audience.clone(),
+            delegates: self.delegates.clone(),
+            include_email: true,
+        }
+    }
+}
+
+#[async_trait]
+impl TokenSource for ImpersonateIdTokenSource {
+    async fn token(&self) -> Result<Token, Error> {
+        let body = self.request_body();
+
+        let auth_token = self.target.token().await?;
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .header(
+                "Authorization",
+                format!("{} {}", auth_token.token_type, auth_token.access_token),
+            )
+            .send()
+            .await?;
+        let response = if !response.status().is_success() {
+            let status = response.status().as_u16();
+            return Err(Error::UnexpectedImpersonateTokenResponse(status, response.text().await?));
+        } else {
+            response.json::<ImpersonateIdTokenResponse>().await?
+        };
+        response.into_token(&self.audience)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImpersonateIdTokenRequest {
+    pub audience: String,
+    pub delegates: Vec<String>,
+    pub include_email: bool,
+}
+
+#[derive(Deserialize)]
+struct ImpersonateIdTokenResponse {
+    pub token: String,
+}
+
+impl ImpersonateIdTokenResponse {
+    fn into_token(self, audience: &str) -> Result<Token, Error> {
+        InternalIdToken { id_token: self.token }.to_token(audience)
+    }
+}
+
 #[derive(Serialize)]
 struct ImpersonateTokenRequest {
     pub delegates: Vec<String>,
@@ -85,3 +168,65 @@ struct ImpersonateTokenResponse {
     pub access_token: String,
     pub expire_time: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use crate::error::Error;
+    use crate::token::Token;
+    use crate::token_source::default_http_client;
+    use crate::token_source::impersonate_token_source::{ImpersonateIdTokenResponse, ImpersonateIdTokenSource};
+    use crate::token_source::TokenSource;
+
+    const AUDIENCE: &str = "https://example.com";
+
+    #[derive(Debug)]
+    struct EmptyTokenSource;
+
+    #[async_trait]
+    impl TokenSource for EmptyTokenSource {
+        async fn token(&self) -> Result<Token, Error> {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_id_token_request_body() {
+        let source = ImpersonateIdTokenSource {
+            target: Box::new(EmptyTokenSource),
+            audience: AUDIENCE.to_string(),
+            delegates: vec!["projects/-/serviceAccounts/delegate@example.com".to_string()],
+            url: "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/sa@example.com:generateIdToken"
+                .to_string(),
+            client: default_http_client(),
+        };
+        let body = serde_json::to_value(source.request_body()).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "audience": AUDIENCE,
+                "delegates": ["projects/-/serviceAccounts/delegate@example.com"],
+                "includeEmail": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_id_token_response() {
+        let claims = json!({"aud": AUDIENCE, "exp": 2_000_000_000});
+        let id_token = jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+        let body = json!({ "token": id_token }).to_string();
+
+        let response: ImpersonateIdTokenResponse = serde_json::from_str(&body).unwrap();
+        let token = response.into_token(AUDIENCE).unwrap();
+        assert_eq!(token.access_token, id_token);
+        assert_eq!(token.token_type, "Bearer");
+        assert_eq!(token.expiry.unwrap().unix_timestamp(), 2_000_000_000);
+
+        let response: ImpersonateIdTokenResponse = serde_json::from_str(&body).unwrap();
+        assert!(response.into_token("https://other.example.com").is_err());
+    }
+}
@@ -170,6 +170,11 @@ impl OAuth2ServiceAccountTokenSource {
         self
     }
 
+    pub(crate) fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Checks whether an HTTP response is successful and returns it, or returns an error.
     async fn check_response_status(response: Response) -> Result<Response, Error> {
         // Check the status code, returning the response if it is not an error.
@@ -3,20 +3,158 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::Duration;
 
 use http::header::AUTHORIZATION;
-use http::{HeaderValue, Request};
+use http::{HeaderMap, HeaderValue, Request};
 use tonic::body::BoxBody;
+pub use tonic::codec::CompressionEncoding;
 use tonic::transport::{Channel as TonicChannel, ClientTlsConfig, Endpoint};
 use tonic::{Code, Status};
 use tower::filter::{AsyncFilter, AsyncFilterLayer, AsyncPredicate};
 use tower::util::Either;
 use tower::{BoxError, ServiceBuilder};
+#[cfg(feature = "trace")]
+use tracing::Instrument;
 
 use google_cloud_token::{TokenSource, TokenSourceProvider};
 
-pub type Channel = Either<AsyncFilter<TonicChannel, AsyncAuthInterceptor>, TonicChannel>;
+/// The header of the project billed for the quota of the requests.
+pub const QUOTA_PROJECT_HEADER: &str = "x-goog-user-project";
+
+/// The header of the client information.
+pub const API_CLIENT_HEADER: &str = "x-goog-api-client";
+
+/// The token of the library in the `x-goog-api-client` header.
+pub const API_CLIENT: &str = "rust";
+
+/// The token of the library in the `user-agent` header.
+pub const USER_AGENT: &str = "google-cloud-rust";
+
+#[cfg(not(feature = "trace"))]
+pub type Channel = InterceptService<Either<AsyncFilter<TonicChannel, AsyncAuthInterceptor>, TonicChannel>>;
+#[cfg(feature = "trace")]
+pub type Channel =
+    TraceService<InterceptService<Either<AsyncFilter<TonicChannel, AsyncAuthInterceptor>, TonicChannel>>>;
+
+/// RpcInterceptor is called on every RPC sent through the channels of the `ConnectionManager`.
+/// It can be used to add custom metadata or to collect metrics.
+pub trait RpcInterceptor: Debug + Send + Sync {
+    /// Called before the request is sent with the full gRPC method name like `/google.pubsub.v1.Publisher/Publish`.
+    /// Returning an error fails the RPC without sending it.
+    fn on_request(&self, _method: &str, _metadata: &mut HeaderMap) -> Result<(), Status> {
+        Ok(())
+    }
+
+    /// Called when the response headers are received, or with `None` if the request failed to be sent.
+    fn on_response(&self, _method: &str, _metadata: Option<&HeaderMap>) {}
+}
+
+/// InterceptService calls the `RpcInterceptor`s around each RPC.
+#[derive(Clone, Debug)]
+pub struct InterceptService<S> {
+    inner: S,
+    interceptors: Arc<[Arc<dyn RpcInterceptor>]>,
+}
+
+impl<S, B> tower::Service<Request<BoxBody>> for InterceptService<S>
+where
+    S: tower::Service<Request<BoxBody>, Response = http::Response<B>>,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut request: Request<BoxBody>) -> Self::Future {
+        if self.interceptors.is_empty() {
+            let future = self.inner.call(request);
+            return Box::pin(async move { future.await.map_err(Into::into) });
+        }
+        let method = request.uri().path().to_string();
+        for interceptor in self.interceptors.iter() {
+            if let Err(status) = interceptor.on_request(&method, request.headers_mut()) {
+                return Box::pin(async move { Err(status.into()) });
+            }
+        }
+        let future = self.inner.call(request);
+        let interceptors = self.interceptors.clone();
+        Box::pin(async move {
+            let result = future.await.map_err(Into::into);
+            let metadata = result.as_ref().ok().map(|response| response.headers());
+            for interceptor in interceptors.iter() {
+                interceptor.on_response(&method, metadata);
+            }
+            result
+        })
+    }
+}
+
+/// TraceService emits a span for each RPC.
+/// The span is named after the gRPC method like `google.spanner.v1.Spanner/ExecuteSql` through the `otel.name` field.
+#[cfg(feature = "trace")]
+#[derive(Clone, Debug)]
+pub struct TraceService<S> {
+    inner: S,
+}
+
+#[cfg(feature = "trace")]
+impl<S, B> tower::Service<Request<BoxBody>> for TraceService<S>
+where
+    S: tower::Service<Request<BoxBody>, Response = http::Response<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<BoxBody>) -> Self::Future {
+        let path = request.uri().path().trim_start_matches('/');
+        let (service, method) = path.split_once('/').unwrap_or((path, ""));
+        let span = tracing::info_span!(
+            "grpc",
+            otel.name = path,
+            otel.kind = "client",
+            rpc.system = "grpc",
+            rpc.service = service,
+            rpc.method = method,
+            rpc.grpc.status_code = tracing::field::Empty,
+        );
+        let future = self.inner.call(request);
+        Box::pin(
+            async move {
+                let response = future.await?;
+                // Only the trailers-only responses have the status in the headers.
+                // Otherwise the status arrives in the trailers after the body, so the code is left empty.
+                if let Some(code) = response.headers().get("grpc-status").and_then(|v| v.to_str().ok()) {
+                    tracing::Span::current().record("rpc.grpc.status_code", code);
+                }
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(feature = "trace")]
+fn trace<S>(inner: S) -> TraceService<S> {
+    TraceService { inner }
+}
+
+#[cfg(not(feature = "trace"))]
+fn trace<S>(inner: S) -> S {
+    inner
+}
 
 #[derive(Clone, Debug)]
 pub struct AsyncAuthInterceptor {
@@ -87,14 +225,48 @@ where
     }
 }
 
+/// KeepAliveConfig configures the HTTP/2 keepalive pings to detect the connections silently dropped
+/// by NAT or load balancers, which is important for the long-lived streams.
+#[derive(Debug, Clone)]
+pub struct KeepAliveConfig {
+    /// The interval of the pings. `None` disables the keepalive.
+    pub interval: Option<Duration>,
+    /// The connection is closed when the ping is not acknowledged within the timeout.
+    pub timeout: Duration,
+    /// Sends the pings even when there are no active streams.
+    pub while_idle: bool,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: Some(Duration::from_secs(30)),
+            timeout: Duration::from_secs(10),
+            while_idle: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ConnectionOptions {
     pub timeout: Option<Duration>,
     pub connect_timeout: Option<Duration>,
+    /// The encoding to compress the requests with and to accept for the responses.
+    /// Disabled by default.
+    pub compression: Option<CompressionEncoding>,
+    /// The interceptors called on every RPC in the order of the list.
+    pub interceptors: Vec<Arc<dyn RpcInterceptor>>,
+    /// The project billed for the quota of the requests, sent as the `x-goog-user-project` header.
+    pub quota_project: Option<String>,
+    /// The HTTP/2 keepalive. Pings every 30 seconds by default.
+    pub keep_alive: KeepAliveConfig,
+    /// The token of the application like `my-service/1.0` appended to the token of the library
+    /// in the `user-agent` and `x-goog-api-client` headers.
+    pub user_agent: Option<String>,
 }
 
 impl ConnectionOptions {
-    fn apply(&self, mut endpoint: Endpoint) -> Endpoint {
+    fn apply(&self, mut endpoint: Endpoint) -> Result<Endpoint, Error> {
         endpoint = match self.timeout {
             Some(t) => endpoint.timeout(t),
             None => endpoint,
@@ -103,16 +275,75 @@ impl ConnectionOptions {
             Some(t) => endpoint.connect_timeout(t),
             None => endpoint,
         };
-        endpoint
+        endpoint = match self.keep_alive.interval {
+            Some(t) => endpoint
+                .http2_keep_alive_interval(t)
+                .keep_alive_timeout(self.keep_alive.timeout)
+                .keep_alive_while_idle(self.keep_alive.while_idle),
+            None => endpoint,
+        };
+        endpoint = match &self.user_agent {
+            Some(v) => endpoint.user_agent(format!("{USER_AGENT} {v}"))?,
+            None => endpoint,
+        };
+        Ok(endpoint)
+    }
+
+    fn intercept<S>(&self, inner: S) -> InterceptService<S> {
+        let mut interceptors = self.interceptors.clone();
+        if let Some(project) = &self.quota_project {
+            let quota_project: Arc<dyn RpcInterceptor> = Arc::new(QuotaProjectInterceptor(project.clone()));
+            interceptors.insert(0, quota_project);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            let api_client: Arc<dyn RpcInterceptor> =
+                Arc::new(ApiClientInterceptor(format!("{API_CLIENT} {user_agent}")));
+            interceptors.insert(0, api_client);
+        }
+        InterceptService {
+            inner,
+            interceptors: interceptors.into(),
+        }
+    }
+}
+
+/// QuotaProjectInterceptor sets the project billed for the quota of the requests.
+#[derive(Debug)]
+struct QuotaProjectInterceptor(String);
+
+impl RpcInterceptor for QuotaProjectInterceptor {
+    fn on_request(&self, _method: &str, metadata: &mut HeaderMap) -> Result<(), Status> {
+        let value = HeaderValue::from_str(&self.0)
+            .map_err(|e| Status::invalid_argument(format!("invalid quota project: {e}")))?;
+        metadata.insert(QUOTA_PROJECT_HEADER, value);
+        Ok(())
     }
 }
 
+/// ApiClientInterceptor sets the client information including the token of the application.
+#[derive(Debug)]
+struct ApiClientInterceptor(String);
+
+impl RpcInterceptor for ApiClientInterceptor {
+    fn on_request(&self, _method: &str, metadata: &mut HeaderMap) -> Result<(), Status> {
+        let value =
+            HeaderValue::from_str(&self.0).map_err(|e| Status::invalid_argument(format!("invalid api client: {e}")))?;
+        metadata.insert(API_CLIENT_HEADER, value);
+        Ok(())
+    }
+}
+
+/// ConnectionManager holds a pool of gRPC channels and distributes the RPCs across them in round-robin.
+/// Each channel is a single HTTP/2 connection, so using more channels mitigates the limit of
+/// concurrent streams per connection and head-of-line blocking under heavy concurrency.
 #[derive(Debug)]
 pub struct ConnectionManager {
     inner: AtomicRing<Channel>,
+    compression: Option<CompressionEncoding>,
 }
 
 impl<'a> ConnectionManager {
+    /// Creates `pool_size` channels. At least one channel is always created.
     pub async fn new(
         pool_size: usize,
         domain_name: impl Into<String>,
@@ -120,17 +351,19 @@ impl<'a> ConnectionManager {
         environment: &Environment,
         conn_options: &'a ConnectionOptions,
     ) -> Result<Self, Error> {
+        let pool_size = pool_size.max(1);
         let conns = match environment {
             Environment::GoogleCloud(ts_provider) => {
                 Self::create_connections(pool_size, domain_name, audience, ts_provider.as_ref(), conn_options).await?
             }
-            Environment::Emulator(host) => Self::create_emulator_connections(host, conn_options).await?,
+            Environment::Emulator(host) => Self::create_emulator_connections(pool_size, host, conn_options).await?,
         };
         Ok(Self {
             inner: AtomicRing {
                 index: AtomicUsize::new(0),
                 values: conns,
             },
+            compression: conn_options.compression,
         })
     }
 
@@ -148,32 +381,37 @@ impl<'a> ConnectionManager {
 
         for _i_ in 0..pool_size {
             let endpoint = TonicChannel::from_static(audience).tls_config(tls_config.clone())?;
-            let endpoint = conn_options.apply(endpoint);
+            let endpoint = conn_options.apply(endpoint)?;
 
             let con = Self::connect(endpoint).await?;
             // use GCP token per call
             let auth_layer = Some(AsyncFilterLayer::new(AsyncAuthInterceptor::new(Arc::clone(&ts))));
             let auth_con = ServiceBuilder::new().option_layer(auth_layer).service(con);
-            conns.push(auth_con);
+            conns.push(trace(conn_options.intercept(auth_con)));
         }
         Ok(conns)
     }
 
     async fn create_emulator_connections(
+        pool_size: usize,
         host: &str,
         conn_options: &'a ConnectionOptions,
     ) -> Result<Vec<Channel>, Error> {
-        let mut conns = Vec::with_capacity(1);
+        let mut conns = Vec::with_capacity(pool_size);
         let endpoint = TonicChannel::from_shared(format!("http://{host}").into_bytes())
             .map_err(|_| Error::InvalidEmulatorHOST(host.to_string()))?;
-        let endpoint = conn_options.apply(endpoint);
+        let endpoint = conn_options.apply(endpoint)?;
 
-        let con = Self::connect(endpoint).await?;
-        conns.push(
-            ServiceBuilder::new()
-                .option_layer::<AsyncFilterLayer<AsyncAuthInterceptor>>(None)
-                .service(con),
-        );
+        for _i_ in 0..pool_size {
+            let con = Self::connect(endpoint.clone()).await?;
+            conns.push(trace(
+                conn_options.intercept(
+                    ServiceBuilder::new()
+                        .option_layer::<AsyncFilterLayer<AsyncAuthInterceptor>>(None)
+                        .service(con),
+                ),
+            ));
+        }
         Ok(conns)
     }
 
@@ -189,14 +427,120 @@ impl<'a> ConnectionManager {
     pub fn conn(&self) -> Channel {
         self.inner.next()
     }
+
+    /// The compression to be enabled on the clients created from the channels
+    /// with `send_compressed` and `accept_compressed`.
+    pub fn compression(&self) -> Option<CompressionEncoding> {
+        self.compression
+    }
 }
 
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
     use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
 
-    use crate::conn::AtomicRing;
+    use http::{HeaderMap, HeaderValue, Request, Response};
+    use tonic::body::{empty_body, BoxBody};
+    use tonic::{Code, Status};
+    use tower::{service_fn, BoxError, ServiceExt};
+
+    use std::time::Duration;
+
+    use crate::conn::{AtomicRing, ConnectionOptions, RpcInterceptor, API_CLIENT_HEADER, QUOTA_PROJECT_HEADER};
+
+    #[derive(Debug, Default)]
+    struct TenantInterceptor {
+        responses: AtomicUsize,
+    }
+
+    impl RpcInterceptor for TenantInterceptor {
+        fn on_request(&self, method: &str, metadata: &mut HeaderMap) -> Result<(), Status> {
+            if method.ends_with("/Forbidden") {
+                return Err(Status::permission_denied("forbidden"));
+            }
+            metadata.insert("x-tenant-id", HeaderValue::from_static("tenant1"));
+            Ok(())
+        }
+
+        fn on_response(&self, _method: &str, metadata: Option<&HeaderMap>) {
+            assert!(metadata.is_some());
+            self.responses.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_intercept() {
+        let interceptor = Arc::new(TenantInterceptor::default());
+        let options = ConnectionOptions {
+            interceptors: vec![interceptor.clone()],
+            ..Default::default()
+        };
+        let inner = service_fn(|request: Request<BoxBody>| async move {
+            assert_eq!(request.headers()["x-tenant-id"], "tenant1");
+            Ok::<_, BoxError>(Response::new(empty_body()))
+        });
+        let service = options.intercept(inner);
+
+        let request = Request::builder()
+            .uri("/test.Service/Method")
+            .body(empty_body())
+            .unwrap();
+        service.clone().oneshot(request).await.unwrap();
+        assert_eq!(interceptor.responses.load(Ordering::SeqCst), 1);
+
+        let request = Request::builder()
+            .uri("/test.Service/Forbidden")
+            .body(empty_body())
+            .unwrap();
+        let error = service.oneshot(request).await.unwrap_err();
+        let status = error.downcast::<Status>().unwrap();
+        assert_eq!(status.code(), Code::PermissionDenied);
+        assert_eq!(interceptor.responses.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_quota_project() {
+        let options = ConnectionOptions {
+            quota_project: Some("billing-project".to_string()),
+            ..Default::default()
+        };
+        let inner = service_fn(|request: Request<BoxBody>| async move {
+            assert_eq!(request.headers()[QUOTA_PROJECT_HEADER], "billing-project");
+            Ok::<_, BoxError>(Response::new(empty_body()))
+        });
+        let request = Request::builder()
+            .uri("/test.Service/Method")
+            .body(empty_body())
+            .unwrap();
+        options.intercept(inner).oneshot(request).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_user_agent() {
+        let options = ConnectionOptions {
+            user_agent: Some("my-service/1.0".to_string()),
+            ..Default::default()
+        };
+        let inner = service_fn(|request: Request<BoxBody>| async move {
+            assert_eq!(request.headers()[API_CLIENT_HEADER], "rust my-service/1.0");
+            Ok::<_, BoxError>(Response::new(empty_body()))
+        });
+        let request = Request::builder()
+            .uri("/test.Service/Method")
+            .body(empty_body())
+            .unwrap();
+        options.intercept(inner).oneshot(request).await.unwrap();
+    }
+
+    #[test]
+    fn test_keep_alive_default() {
+        let options = ConnectionOptions::default();
+        assert_eq!(options.keep_alive.interval, Some(Duration::from_secs(30)));
+        assert_eq!(options.keep_alive.timeout, Duration::from_secs(10));
+        assert!(options.keep_alive.while_idle);
+    }
 
     #[test]
     fn test_atomic_ring() {
@@ -8,5 +8,22 @@ pub fn create_request<T>(param_string: String, into_request: impl grpc::IntoRequ
     if !param_string.is_empty() {
         target.append("x-goog-request-params", param_string.parse().unwrap());
     }
+    if let Ok(timeout) = retry::CALL_TIMEOUT.try_with(|v| *v) {
+        request.set_timeout(timeout);
+    }
     request
 }
+
+/// The span of an HTTP request for the REST clients, which records the status code of the response
+/// in the `http.response.status_code` field.
+#[cfg(feature = "trace")]
+pub fn http_request_span(method: &str, url: &str) -> tracing::Span {
+    tracing::info_span!(
+        "http",
+        otel.name = method,
+        otel.kind = "client",
+        http.request.method = method,
+        url.full = url,
+        http.response.status_code = tracing::field::Empty,
+    )
+}
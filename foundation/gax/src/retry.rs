@@ -1,13 +1,59 @@
+use std::convert::TryFrom;
 use std::future::Future;
 use std::iter::Take;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use prost::Message;
 pub use tokio_retry2::strategy::ExponentialBackoff;
 use tokio_retry2::{Action, RetryIf};
 pub use tokio_retry2::{Condition, MapErr};
+use tracing::Instrument;
 
 use crate::grpc::{Code, Status};
 
+const RETRY_INFO_KEY: &str = "google.rpc.retryinfo-bin";
+const RETRY_INFO_TYPE: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+/// RetryInfo is the error detail of google.rpc.RetryInfo, which tells how long to wait before retrying.
+#[derive(Clone, PartialEq, Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<prost_types::Duration>,
+}
+
+/// RpcStatus is the google.rpc.Status carried in the details of the status.
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+/// retry_delay returns the delay suggested by the server in the RetryInfo of the status.
+/// The RetryInfo is read from the trailers first and then from the details of the status.
+pub fn retry_delay(status: &Status) -> Option<Duration> {
+    let info = match status.metadata().get_bin(RETRY_INFO_KEY) {
+        Some(value) => value.to_bytes().ok().and_then(|v| RetryInfo::decode(v).ok()),
+        None => RpcStatus::decode(status.details()).ok().and_then(|v| {
+            v.details
+                .into_iter()
+                .find(|d| d.type_url == RETRY_INFO_TYPE)
+                .and_then(|d| RetryInfo::decode(d.value.as_slice()).ok())
+        }),
+    };
+    info.and_then(|v| v.retry_delay)
+        .and_then(|v| Duration::try_from(v).ok())
+}
+
+tokio::task_local! {
+    /// The deadline of the current attempt, read by `create_request`.
+    pub(crate) static CALL_TIMEOUT: Duration;
+}
+
 pub trait TryAs<T> {
     fn try_as(&self) -> Option<&T>;
 }
@@ -22,6 +68,111 @@ pub trait Retry<E: TryAs<Status>, T: Condition<E>> {
     fn strategy(&self) -> Take<ExponentialBackoff>;
     fn condition(&self) -> T;
     fn notify(error: &E, duration: Duration);
+
+    /// Randomizes each delay of the strategy between 50% and 150% of its value.
+    fn jitter(&self) -> bool {
+        false
+    }
+
+    /// Stops retrying once the next delay would exceed this time since the first attempt.
+    fn max_elapsed(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The deadline of each attempt.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// backoff returns the delays of the retry strategy with jitter and max elapsed time applied.
+pub fn backoff<E, C, RT>(retry: &RT) -> impl Iterator<Item = Duration>
+where
+    E: TryAs<Status>,
+    C: Condition<E>,
+    RT: Retry<E, C>,
+{
+    let jitter = retry.jitter();
+    let deadline = retry.max_elapsed().map(|v| Instant::now() + v);
+    retry
+        .strategy()
+        .map(move |d| {
+            if jitter {
+                d.mul_f64(rand::random::<f64>() + 0.5)
+            } else {
+                d
+            }
+        })
+        .map_while(move |d| match deadline {
+            Some(deadline) if Instant::now() + d > deadline => None,
+            _ => Some(d),
+        })
+}
+
+/// attempt runs a single attempt within the deadline.
+/// The deadline is also sent to the server as the `grpc-timeout` of the requests created by `create_request`.
+/// The attempt exceeding the deadline fails with `DeadlineExceeded`, which is retried as any other error.
+fn attempt<F: Future>(
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> F,
+) -> impl Future<Output = Result<F::Output, Status>> {
+    let future = with_deadline(timeout, f);
+    async move {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, future)
+                .await
+                .map_err(|_| Status::deadline_exceeded(format!("attempt timed out after {timeout:?}"))),
+            None => Ok(future.await),
+        }
+    }
+}
+
+/// with_deadline makes the deadline of the attempt available to `create_request` while the attempt runs.
+fn with_deadline<F: Future>(timeout: Option<Duration>, f: impl FnOnce() -> F) -> impl Future<Output = F::Output> {
+    let (timeout, future) = match timeout {
+        Some(timeout) => (Some(timeout), CALL_TIMEOUT.sync_scope(timeout, f)),
+        None => (None, f()),
+    };
+    async move {
+        match timeout {
+            Some(timeout) => CALL_TIMEOUT.scope(timeout, future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// attempt_span is the span of the n-th attempt, which becomes the parent of the span of the RPC.
+#[cfg(feature = "trace")]
+fn attempt_span(n: usize) -> tracing::Span {
+    tracing::info_span!("attempt", attempt = n, code = tracing::field::Empty)
+}
+
+#[cfg(not(feature = "trace"))]
+fn attempt_span(_n: usize) -> tracing::Span {
+    tracing::Span::none()
+}
+
+/// RetryInfoCondition keeps the delay suggested by the server for the error to be retried,
+/// which the strategy waits at least.
+struct RetryInfoCondition<C> {
+    inner: C,
+    delay: Arc<Mutex<Option<Duration>>>,
+}
+
+impl<E, C> Condition<E> for RetryInfoCondition<C>
+where
+    E: TryAs<Status>,
+    C: Condition<E>,
+{
+    fn should_retry(&mut self, error: &E) -> bool {
+        let retry = self.inner.should_retry(error);
+        *self.delay.lock().unwrap() = if retry {
+            error.try_as().and_then(retry_delay)
+        } else {
+            None
+        };
+        retry
+    }
 }
 
 pub struct CodeCondition {
@@ -50,18 +201,103 @@ where
     }
 }
 
+/// RetrySetting configures the retry of a call.
+/// The n-th retry waits `factor * from_millis^n` milliseconds, capped by `max_delay`.
+/// ```
+/// use std::time::Duration;
+/// use google_cloud_gax::grpc::Code;
+/// use google_cloud_gax::retry::RetrySetting;
+///
+/// let setting = RetrySetting::default()
+///     .with_from_millis(2)
+///     .with_factor(100)
+///     .with_max_delay(Duration::from_secs(10))
+///     .with_jitter(true)
+///     .with_take(10)
+///     .with_max_elapsed(Duration::from_secs(60))
+///     .with_timeout(Duration::from_secs(5))
+///     .with_code(Code::ResourceExhausted);
+/// ```
 #[derive(Clone, Debug)]
 pub struct RetrySetting {
+    /// The base of the exponential backoff in milliseconds.
     pub from_millis: u64,
+    /// The upper bound of each delay.
     pub max_delay: Option<Duration>,
+    /// The multiplier applied to each delay.
     pub factor: u64,
+    /// The maximum number of retries.
     pub take: usize,
+    /// The codes to retry.
     pub codes: Vec<Code>,
+    /// Randomizes each delay between 50% and 150% of its value.
+    pub jitter: bool,
+    /// Stops retrying once the next delay would exceed this time since the first attempt.
+    pub max_elapsed: Option<Duration>,
+    /// The deadline of each attempt. An attempt exceeding it fails with `DEADLINE_EXCEEDED`.
+    pub timeout: Option<Duration>,
+}
+
+impl RetrySetting {
+    pub fn with_from_millis(mut self, value: u64) -> Self {
+        self.from_millis = value;
+        self
+    }
+
+    pub fn with_max_delay(mut self, value: Duration) -> Self {
+        self.max_delay = Some(value);
+        self
+    }
+
+    pub fn with_factor(mut self, value: u64) -> Self {
+        self.factor = value;
+        self
+    }
+
+    pub fn with_take(mut self, value: usize) -> Self {
+        self.take = value;
+        self
+    }
+
+    pub fn with_jitter(mut self, value: bool) -> Self {
+        self.jitter = value;
+        self
+    }
+
+    pub fn with_max_elapsed(mut self, value: Duration) -> Self {
+        self.max_elapsed = Some(value);
+        self
+    }
+
+    pub fn with_timeout(mut self, value: Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    /// Replaces the codes to retry.
+    pub fn with_codes(mut self, value: Vec<Code>) -> Self {
+        self.codes = value;
+        self
+    }
+
+    /// Adds a code to retry.
+    pub fn with_code(mut self, value: Code) -> Self {
+        if !self.codes.contains(&value) {
+            self.codes.push(value);
+        }
+        self
+    }
+
+    /// Removes a code to retry.
+    pub fn without_code(mut self, value: Code) -> Self {
+        self.codes.retain(|c| *c != value);
+        self
+    }
 }
 
 impl Retry<Status, CodeCondition> for RetrySetting {
     fn strategy(&self) -> Take<ExponentialBackoff> {
-        let mut st = ExponentialBackoff::from_millis(self.from_millis);
+        let mut st = ExponentialBackoff::from_millis(self.from_millis).factor(self.factor);
         if let Some(max_delay) = self.max_delay {
             st = st.max_delay(max_delay);
         }
@@ -72,8 +308,20 @@ impl Retry<Status, CodeCondition> for RetrySetting {
         CodeCondition::new(self.codes.clone())
     }
 
-    fn notify(_error: &Status, _duration: Duration) {
-        tracing::trace!("retry fn");
+    fn notify(error: &Status, duration: Duration) {
+        tracing::trace!(code = ?error.code(), ?duration, "retry fn");
+    }
+
+    fn jitter(&self) -> bool {
+        self.jitter
+    }
+
+    fn max_elapsed(&self) -> Option<Duration> {
+        self.max_elapsed
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
     }
 }
 
@@ -85,6 +333,9 @@ impl Default for RetrySetting {
             factor: 1u64,
             take: 5,
             codes: vec![Code::Unavailable, Code::Unknown, Code::Aborted],
+            jitter: false,
+            max_elapsed: None,
+            timeout: None,
         }
     }
 }
@@ -97,10 +348,45 @@ where
     RT: Retry<E, C> + Default,
 {
     let retry = retry.unwrap_or_default();
-    RetryIf::spawn(retry.strategy(), action, retry.condition(), RT::notify).await
+    let timeout = retry.timeout();
+    let mut action = action;
+    let mut n = 0;
+    let action = move || {
+        n += 1;
+        let span = attempt_span(n);
+        // the future is created here so that the borrowed action doesn't escape the closure.
+        let future = match timeout {
+            Some(timeout) => CALL_TIMEOUT.sync_scope(timeout, || action.run()),
+            None => action.run(),
+        };
+        let future = attempt(timeout, move || future);
+        async move {
+            match future.await {
+                Ok(result) => result,
+                Err(status) => {
+                    tracing::Span::current().record("code", tracing::field::debug(status.code()));
+                    Err::<R, E>(status.into()).map_transient_err()
+                }
+            }
+        }
+        .instrument(span)
+    };
+    let delay = Arc::new(Mutex::new(None));
+    let condition = RetryInfoCondition {
+        inner: retry.condition(),
+        delay: delay.clone(),
+    };
+    let strategy = backoff::<E, C, RT>(&retry).map(move |d| match delay.lock().unwrap().take() {
+        Some(suggested) => d.max(suggested),
+        None => d,
+    });
+    RetryIf::spawn(strategy, action, condition, RT::notify).await
 }
 /// Repeats retries when the specified error is detected.
 /// The argument specified by 'v' can be reused for each retry.
+/// The attempt exceeding the timeout is cancelled as `invoke` does, and fails with `DeadlineExceeded`
+/// without retrying since 'v' can't be taken back from the cancelled attempt.
+/// The `DeadlineExceeded` returned by the server is retried as any other error.
 pub async fn invoke_fn<R, V, A, RT, C, E>(retry: Option<RT>, mut f: impl FnMut(V) -> A, mut v: V) -> Result<R, E>
 where
     E: TryAs<Status> + From<Status>,
@@ -109,18 +395,34 @@ where
     RT: Retry<E, C> + Default,
 {
     let retry = retry.unwrap_or_default();
-    let mut strategy = retry.strategy();
+    let mut strategy = backoff::<E, C, RT>(&retry);
+    let timeout = retry.timeout();
+    let mut n = 0;
     loop {
-        let result = f(v).await;
+        n += 1;
+        let span = attempt_span(n);
+        let result = attempt(timeout, || f(v)).instrument(span.clone()).await;
         let status = match result {
-            Ok(s) => return Ok(s),
-            Err(e) => {
+            Ok(Ok(s)) => return Ok(s),
+            Ok(Err(e)) => {
                 v = e.1;
                 e.0
             }
+            Err(status) => {
+                span.record("code", tracing::field::debug(status.code()));
+                return Err(status.into());
+            }
         };
+        if let Some(status) = status.try_as() {
+            span.record("code", tracing::field::debug(status.code()));
+        }
         if retry.condition().should_retry(&status) {
+            let suggested = status.try_as().and_then(retry_delay);
             let duration = strategy.next().ok_or(status)?;
+            let duration = match suggested {
+                Some(suggested) => duration.max(suggested),
+                None => duration,
+            };
             tokio::time::sleep(duration).await;
         } else {
             return Err(status);
@@ -130,12 +432,92 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryInto;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
+    use prost::Message;
     use tokio_retry2::MapErr;
+    use tonic::metadata::MetadataValue;
     use tonic::{Code, Status};
 
-    use crate::retry::{invoke, RetrySetting};
+    use crate::create_request;
+    use crate::retry::{backoff, invoke, invoke_fn, retry_delay, RetryInfo, RetrySetting, RpcStatus, RETRY_INFO_KEY};
+
+    fn resource_exhausted(delay: Duration) -> Status {
+        let info = RetryInfo {
+            retry_delay: Some(delay.try_into().unwrap()),
+        };
+        let mut status = Status::resource_exhausted("quota exceeded");
+        status
+            .metadata_mut()
+            .insert_bin(RETRY_INFO_KEY, MetadataValue::from_bytes(&info.encode_to_vec()));
+        status
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(
+            retry_delay(&resource_exhausted(Duration::from_millis(1500))),
+            Some(Duration::from_millis(1500))
+        );
+
+        let info = RetryInfo {
+            retry_delay: Some(prost_types::Duration {
+                seconds: 1,
+                nanos: 500_000_000,
+            }),
+        };
+        let details = RpcStatus {
+            code: Code::ResourceExhausted as i32,
+            message: "".to_string(),
+            details: vec![prost_types::Any {
+                type_url: "type.googleapis.com/google.rpc.RetryInfo".to_string(),
+                value: info.encode_to_vec(),
+            }],
+        };
+        let status = Status::with_details(Code::ResourceExhausted, "", details.encode_to_vec().into());
+        assert_eq!(retry_delay(&status), Some(Duration::from_millis(1500)));
+
+        assert_eq!(retry_delay(&Status::resource_exhausted("")), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_retry_info() {
+        let retry = RetrySetting::default()
+            .with_from_millis(1)
+            .with_code(Code::ResourceExhausted);
+        let counter = Arc::new(Mutex::new(0));
+        let action = || async {
+            let mut lock = counter.lock().unwrap();
+            *lock += 1;
+            let result: Result<i32, Status> = if *lock == 1 {
+                Err(resource_exhausted(Duration::from_millis(200)))
+            } else {
+                Ok(1)
+            };
+            result.map_transient_err()
+        };
+        let start = std::time::Instant::now();
+        assert_eq!(invoke(Some(retry.clone()), action).await.unwrap(), 1);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let start = std::time::Instant::now();
+        let result = invoke_fn(
+            Some(retry),
+            |n: usize| async move {
+                if n == 0 {
+                    Err((resource_exhausted(Duration::from_millis(200)), n + 1))
+                } else {
+                    Ok(n)
+                }
+            },
+            0,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 1);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
 
     #[tokio::test]
     async fn test_retry() {
@@ -152,4 +534,126 @@ mod tests {
         assert_eq!(actual.code(), expected.code());
         assert_eq!(*counter.lock().unwrap(), 6);
     }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let retry = RetrySetting::default()
+            .with_code(Code::DeadlineExceeded)
+            .with_timeout(Duration::from_millis(10));
+        let counter = Arc::new(Mutex::new(0));
+        let action = || async {
+            *counter.lock().unwrap() += 1;
+            let request = create_request(String::new(), ());
+            assert!(request.metadata().get("grpc-timeout").is_some());
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok::<(), Status>(()).map_transient_err()
+        };
+        let actual = invoke(Some(retry.clone()), action).await.unwrap_err();
+        assert_eq!(actual.code(), Code::DeadlineExceeded);
+        assert_eq!(*counter.lock().unwrap(), 6);
+
+        // the attempt is cancelled locally and not retried since the argument is consumed.
+        let counter = Arc::new(Mutex::new(0));
+        let actual = invoke_fn(
+            Some(retry.clone()),
+            |v: ()| {
+                let counter = counter.clone();
+                async move {
+                    *counter.lock().unwrap() += 1;
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    Ok::<(), (Status, ())>(v)
+                }
+            },
+            (),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(actual.code(), Code::DeadlineExceeded);
+        assert_eq!(*counter.lock().unwrap(), 1);
+
+        // the server returns DeadlineExceeded for the grpc-timeout.
+        let actual = invoke_fn(
+            Some(retry),
+            |v: ()| async move {
+                let request = create_request(String::new(), ());
+                assert!(request.metadata().get("grpc-timeout").is_some());
+                Err::<(), (Status, ())>((Status::deadline_exceeded("error"), v))
+            },
+            (),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(actual.code(), Code::DeadlineExceeded);
+    }
+
+    #[tokio::test]
+    async fn test_retry_timed_out_attempt() {
+        let retry = RetrySetting::default()
+            .with_code(Code::DeadlineExceeded)
+            .with_timeout(Duration::from_millis(10));
+        let counter = Arc::new(Mutex::new(0));
+        let action = || async {
+            let n = {
+                let mut lock = counter.lock().unwrap();
+                *lock += 1;
+                *lock
+            };
+            // only the first attempt exceeds the timeout.
+            if n == 1 {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            Ok::<usize, Status>(n).map_transient_err()
+        };
+        assert_eq!(invoke(Some(retry.clone()), action).await.unwrap(), 2);
+
+        let actual = invoke_fn(
+            Some(retry),
+            |n: usize| async move {
+                if n == 1 {
+                    Err((Status::deadline_exceeded("deadline exceeded"), n + 1))
+                } else {
+                    Ok::<usize, (Status, usize)>(n)
+                }
+            },
+            1,
+        )
+        .await
+        .unwrap();
+        assert_eq!(actual, 2);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let setting = RetrySetting::default()
+            .with_from_millis(2)
+            .with_factor(10)
+            .with_max_delay(Duration::from_millis(50))
+            .with_take(4);
+        let delays: Vec<Duration> = backoff(&setting).collect();
+        assert_eq!(
+            delays,
+            vec![20, 40, 50, 50]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+
+        let setting = setting.with_jitter(true);
+        for (i, d) in backoff(&setting).enumerate() {
+            let base = delays[i].as_secs_f64();
+            assert!(d.as_secs_f64() >= base * 0.5 && d.as_secs_f64() <= base * 1.5);
+        }
+
+        let setting = RetrySetting::default().with_max_elapsed(Duration::ZERO);
+        assert_eq!(backoff(&setting).count(), 0);
+    }
+
+    #[test]
+    fn test_codes() {
+        let setting = RetrySetting::default()
+            .with_code(Code::ResourceExhausted)
+            .with_code(Code::ResourceExhausted)
+            .without_code(Code::Aborted);
+        assert_eq!(setting.codes, vec![Code::Unavailable, Code::Unknown, Code::ResourceExhausted]);
+    }
 }
@@ -18,6 +18,9 @@ pub fn default_retry_setting() -> RetrySetting {
         factor: 1u64,
         take: 20,
         codes: vec![Code::Unavailable, Code::Unknown],
+        jitter: false,
+        max_elapsed: None,
+        timeout: None,
     }
 }
 
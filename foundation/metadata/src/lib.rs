@@ -1,4 +1,5 @@
 use std::string;
+use std::sync::OnceLock;
 use std::time::Duration;
 
 use reqwest::header::{HeaderValue, USER_AGENT};
@@ -15,6 +16,8 @@ static ON_GCE: OnceCell<bool> = OnceCell::const_new();
 
 static PROJECT_ID: OnceCell<String> = OnceCell::const_new();
 
+static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("invalid response code: {0}")]
@@ -25,6 +28,22 @@ pub enum Error {
     HttpError(#[from] reqwest::Error),
 }
 
+/// set_http_client overrides the HTTP client used to access the metadata server.
+/// It must be called before any other function of this crate, otherwise the default client is already in use
+/// and the given client is returned as an error.
+pub fn set_http_client(client: reqwest::Client) -> Result<(), reqwest::Client> {
+    HTTP_CLIENT.set(client)
+}
+
+fn http_client() -> &'static reqwest::Client {
+    HTTP_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(3))
+            .build()
+            .unwrap()
+    })
+}
+
 pub async fn on_gce() -> bool {
     match ON_GCE.get_or_try_init(test_on_gce).await {
         Ok(s) => *s,
@@ -38,10 +57,7 @@ async fn test_on_gce() -> Result<bool, Error> {
         return Ok(true);
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(3))
-        .build()
-        .unwrap();
+    let client = http_client();
     let url = format!("http://{METADATA_IP}");
 
     let response = client.get(&url).send().await;
@@ -86,18 +102,65 @@ pub async fn email(service_account: &str) -> Result<String, Error> {
     get_etag_with_trim(&format!("instance/service-accounts/{service_account}/email")).await
 }
 
+/// get returns the value of the metadata at the path relative to `computeMetadata/v1/`.
+/// For example `instance/hostname`.
+pub async fn get(path: &str) -> Result<String, Error> {
+    get_etag(path.trim_start_matches('/')).await
+}
+
+/// zone returns the zone of the instance such as `us-central1-a`.
+pub async fn zone() -> Result<String, Error> {
+    let zone = get_etag_with_trim("instance/zone").await?;
+    Ok(last_segment(&zone).to_string())
+}
+
+/// region returns the region of the instance such as `us-central1`, derived from the zone.
+pub async fn region() -> Result<String, Error> {
+    Ok(region_from_zone(&zone().await?).to_string())
+}
+
+/// instance_id returns the numeric ID of the instance.
+pub async fn instance_id() -> Result<String, Error> {
+    get_etag_with_trim("instance/id").await
+}
+
+/// instance_attribute returns the value of the custom metadata of the instance.
+pub async fn instance_attribute(key: &str) -> Result<String, Error> {
+    get_etag(&format!("instance/attributes/{key}")).await
+}
+
+/// project_attribute returns the value of the custom metadata of the project.
+pub async fn project_attribute(key: &str) -> Result<String, Error> {
+    get_etag(&format!("project/attributes/{key}")).await
+}
+
+/// last_segment returns the last segment of the path, such as the zone of `projects/{project_number}/zones/{zone}`.
+fn last_segment(value: &str) -> &str {
+    value.rsplit('/').next().unwrap_or(value)
+}
+
+fn region_from_zone(zone: &str) -> &str {
+    match zone.rfind('-') {
+        Some(index) => &zone[..index],
+        None => zone,
+    }
+}
+
 async fn get_etag_with_trim(suffix: &str) -> Result<String, Error> {
     let result = get_etag(suffix).await?;
     Ok(result.trim().to_string())
 }
 
+/// metadata_host returns the host of the metadata server.
+/// GCE_METADATA_HOST is used if set, otherwise the well-known IP address is used to avoid DNS lookups.
+pub fn metadata_host() -> String {
+    std::env::var(METADATA_HOST_ENV).unwrap_or_else(|_| METADATA_IP.to_string())
+}
+
 async fn get_etag(suffix: &str) -> Result<String, Error> {
-    let host = std::env::var(METADATA_HOST_ENV).unwrap_or_else(|_| METADATA_GOOGLE_HOST.to_string());
+    let host = metadata_host();
     let url = format!("http://{host}/computeMetadata/v1/{suffix}");
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(3))
-        .build()
-        .unwrap();
+    let client = http_client();
     let response = client
         .get(url)
         .header(METADATA_FLAVOR_KEY, HeaderValue::from_str(METADATA_GOOGLE).unwrap())
@@ -110,3 +173,17 @@ async fn get_etag(suffix: &str) -> Result<String, Error> {
     }
     Err(Error::InvalidResponse(response.status().as_u16()))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{last_segment, region_from_zone};
+
+    #[test]
+    fn test_zone_and_region() {
+        let zone = last_segment("projects/123456789/zones/us-central1-a");
+        assert_eq!(zone, "us-central1-a");
+        assert_eq!(region_from_zone(zone), "us-central1");
+        assert_eq!(last_segment("asia-northeast1-b"), "asia-northeast1-b");
+        assert_eq!(region_from_zone("invalid"), "invalid");
+    }
+}
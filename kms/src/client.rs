@@ -4,11 +4,16 @@ use std::sync::Arc;
 #[cfg(feature = "auth")]
 pub use google_cloud_auth;
 use google_cloud_gax::conn::{ConnectionOptions, Environment, Error};
+use google_cloud_gax::grpc::Status;
+use google_cloud_gax::retry::RetrySetting;
 
 use google_cloud_token::{NopeTokenSourceProvider, TokenSourceProvider};
 
 use crate::grpc::apiv1::conn_pool::{ConnectionManager, KMS, SCOPES};
 use crate::grpc::apiv1::kms_client::Client as KmsGrpcClient;
+use crate::grpc::kms::v1::{
+    digest, AsymmetricSignRequest, Digest, GetPublicKeyRequest, MacSignRequest, MacVerifyRequest,
+};
 
 #[derive(Debug)]
 pub struct ClientConfig {
@@ -38,6 +43,7 @@ impl ClientConfig {
     }
 
     async fn with_token_source(mut self, ts: google_cloud_auth::token::DefaultTokenSourceProvider) -> Self {
+        self.connection_option.quota_project = self.connection_option.quota_project.or(ts.quota_project_id());
         self.token_source_provider = Box::new(ts);
         self
     }
@@ -79,6 +85,112 @@ impl Client {
     }
 }
 
+/// Errors of the operations verifying the CRC32C checksums of the requests and the responses.
+#[derive(thiserror::Error, Debug)]
+pub enum IntegrityError {
+    #[error(transparent)]
+    GRPC(#[from] Status),
+    #[error("integrity check failed: {0}")]
+    Corrupted(&'static str),
+}
+
+fn crc32c(data: &[u8]) -> Option<i64> {
+    Some(crc32c::crc32c(data) as i64)
+}
+
+fn check(ok: bool, field: &'static str) -> Result<(), IntegrityError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(IntegrityError::Corrupted(field))
+    }
+}
+
+impl Client {
+    /// Signs the SHA-256, SHA-384 or SHA-512 digest with the asymmetric key version.
+    /// The checksums of the digest and the signature are verified, and the signature is returned.
+    ///
+    /// key_version format: "projects/{project}/locations/{location}/keyRings/{key_ring}/cryptoKeys/{key}/cryptoKeyVersions/{version}"
+    pub async fn sign_digest(
+        &self,
+        key_version: &str,
+        digest: digest::Digest,
+        retry: Option<RetrySetting>,
+    ) -> Result<Vec<u8>, IntegrityError> {
+        let digest_crc32c = match &digest {
+            digest::Digest::Sha256(v) | digest::Digest::Sha384(v) | digest::Digest::Sha512(v) => crc32c(v),
+        };
+        let request = AsymmetricSignRequest {
+            name: key_version.to_string(),
+            digest: Some(Digest { digest: Some(digest) }),
+            digest_crc32c,
+            ..Default::default()
+        };
+        let response = self.kms_client.asymmetric_sign(request, retry).await?;
+        check(response.name == key_version, "name")?;
+        check(response.verified_digest_crc32c, "digest_crc32c")?;
+        check(response.signature_crc32c == crc32c(&response.signature), "signature_crc32c")?;
+        Ok(response.signature)
+    }
+
+    /// Returns the PEM encoded public key of the asymmetric key version after verifying its checksum.
+    pub async fn public_key_pem(
+        &self,
+        key_version: &str,
+        retry: Option<RetrySetting>,
+    ) -> Result<String, IntegrityError> {
+        let request = GetPublicKeyRequest {
+            name: key_version.to_string(),
+        };
+        let response = self.kms_client.get_public_key(request, retry).await?;
+        check(response.name == key_version, "name")?;
+        check(response.pem_crc32c == crc32c(response.pem.as_bytes()), "pem_crc32c")?;
+        Ok(response.pem)
+    }
+
+    /// Signs the data with the MAC key version and returns the MAC after verifying the checksums.
+    pub async fn sign_mac(
+        &self,
+        key_version: &str,
+        data: Vec<u8>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Vec<u8>, IntegrityError> {
+        let request = MacSignRequest {
+            name: key_version.to_string(),
+            data_crc32c: crc32c(&data),
+            data,
+        };
+        let response = self.kms_client.mac_sign(request, retry).await?;
+        check(response.name == key_version, "name")?;
+        check(response.verified_data_crc32c, "data_crc32c")?;
+        check(response.mac_crc32c == crc32c(&response.mac), "mac_crc32c")?;
+        Ok(response.mac)
+    }
+
+    /// Verifies the MAC of the data with the MAC key version after verifying the checksums.
+    pub async fn verify_mac(
+        &self,
+        key_version: &str,
+        data: Vec<u8>,
+        mac: Vec<u8>,
+        retry: Option<RetrySetting>,
+    ) -> Result<bool, IntegrityError> {
+        let request = MacVerifyRequest {
+            name: key_version.to_string(),
+            data_crc32c: crc32c(&data),
+            data,
+            mac_crc32c: crc32c(&mac),
+            mac,
+        };
+        let response = self.kms_client.mac_verify(request, retry).await?;
+        check(response.name == key_version, "name")?;
+        check(response.verified_data_crc32c, "data_crc32c")?;
+        check(response.verified_mac_crc32c, "mac_crc32c")?;
+        check(response.verified_success_integrity, "success")?;
+        Ok(response.success)
+    }
+}
+
 impl Deref for Client {
     type Target = KmsGrpcClient;
 
@@ -91,12 +203,16 @@ impl Deref for Client {
 mod tests {
     use serial_test::serial;
 
+    use crate::grpc::kms::v1::crypto_key_version::CryptoKeyVersionState;
     use crate::grpc::kms::v1::{
-        AsymmetricSignRequest, CreateKeyRingRequest, DecryptRequest, EncryptRequest, GenerateRandomBytesRequest,
+        AsymmetricSignRequest, CreateCryptoKeyVersionRequest, CreateKeyRingRequest, DecryptRequest,
+        DestroyCryptoKeyVersionRequest, EncryptRequest, GenerateRandomBytesRequest, GetCryptoKeyRequest,
         GetKeyRingRequest, GetPublicKeyRequest, ListKeyRingsRequest, MacSignRequest, MacVerifyRequest, ProtectionLevel,
+        RestoreCryptoKeyVersionRequest, UpdateCryptoKeyPrimaryVersionRequest,
     };
 
-    use crate::client::{Client, ClientConfig};
+    use crate::client::{crc32c, Client, ClientConfig};
+    use crate::grpc::kms::v1::digest;
 
     async fn new_client() -> (Client, String) {
         let cred = google_cloud_auth::credentials::CredentialsFile::new().await.unwrap();
@@ -244,6 +360,84 @@ mod tests {
         assert_eq!(data.to_vec(), raw.plaintext);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_crypto_key_version_lifecycle() {
+        let (client, project) = new_client().await;
+
+        let key = format!("projects/{project}/locations/asia-northeast1/keyRings/gcr_test/cryptoKeys/gcr_test");
+        let crypto_key = client
+            .get_crypto_key(GetCryptoKeyRequest { name: key.clone() }, None)
+            .await
+            .unwrap();
+        let primary = crypto_key.primary.unwrap().name;
+        let version_id = |name: &str| name.rsplit('/').next().unwrap().to_string();
+
+        // rotate
+        let version = client
+            .create_crypto_key_version(
+                CreateCryptoKeyVersionRequest {
+                    parent: key.clone(),
+                    crypto_key_version: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let updated = client
+            .update_crypto_key_primary_version(
+                UpdateCryptoKeyPrimaryVersionRequest {
+                    name: key.clone(),
+                    crypto_key_version_id: version_id(&version.name),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.primary.unwrap().name, version.name);
+
+        // rollback
+        let updated = client
+            .update_crypto_key_primary_version(
+                UpdateCryptoKeyPrimaryVersionRequest {
+                    name: key.clone(),
+                    crypto_key_version_id: version_id(&primary),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.primary.unwrap().name, primary);
+
+        let destroyed = client
+            .destroy_crypto_key_version(
+                DestroyCryptoKeyVersionRequest {
+                    name: version.name.clone(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(destroyed.state(), CryptoKeyVersionState::DestroyScheduled);
+
+        let restored = client
+            .restore_crypto_key_version(
+                RestoreCryptoKeyVersionRequest {
+                    name: version.name.clone(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(restored.state(), CryptoKeyVersionState::Disabled);
+
+        // leave no versions enabled except the primary
+        client
+            .destroy_crypto_key_version(DestroyCryptoKeyVersionRequest { name: version.name }, None)
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_mac_sign_verify() {
@@ -270,4 +464,39 @@ mod tests {
         let raw = client.mac_verify(request, None).await.unwrap();
         assert!(raw.success);
     }
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b"123456789"), Some(0xE3069283));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sign_digest() {
+        let (client, project) = new_client().await;
+        let key = format!(
+            "projects/{project}/locations/asia-northeast1/keyRings/gcr_test/cryptoKeys/eth-sign/cryptoKeyVersions/1"
+        );
+        let signature = client
+            .sign_digest(&key, digest::Digest::Sha256(vec![1; 32]), None)
+            .await
+            .unwrap();
+        assert!(!signature.is_empty());
+
+        let pem = client.public_key_pem(&key, None).await.unwrap();
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sign_verify_mac() {
+        let (client, project) = new_client().await;
+        let key = format!(
+            "projects/{project}/locations/asia-northeast1/keyRings/gcr_test/cryptoKeys/mac-test/cryptoKeyVersions/1"
+        );
+        let data = vec![1, 2, 3, 4, 5];
+        let mac = client.sign_mac(&key, data.clone(), None).await.unwrap();
+        assert!(client.verify_mac(&key, data.clone(), mac, None).await.unwrap());
+        assert!(!client.verify_mac(&key, data, vec![0; 32], None).await.unwrap());
+    }
 }
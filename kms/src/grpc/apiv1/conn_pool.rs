@@ -28,6 +28,10 @@ impl ConnectionManager {
     }
 
     pub fn conn(&self) -> KeyManagementServiceClient<Channel> {
-        KeyManagementServiceClient::new(self.inner.conn()).max_decoding_message_size(i32::MAX as usize)
+        let client = KeyManagementServiceClient::new(self.inner.conn()).max_decoding_message_size(i32::MAX as usize);
+        match self.inner.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        }
     }
 }
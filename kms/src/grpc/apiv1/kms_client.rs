@@ -24,6 +24,8 @@ use crate::grpc::kms::v1::ListCryptoKeysRequest;
 use crate::grpc::kms::v1::ListCryptoKeysResponse;
 use crate::grpc::kms::v1::ListKeyRingsRequest;
 use crate::grpc::kms::v1::ListKeyRingsResponse;
+use crate::grpc::kms::v1::RestoreCryptoKeyVersionRequest;
+use crate::grpc::kms::v1::UpdateCryptoKeyPrimaryVersionRequest;
 use crate::grpc::kms::v1::{
     AsymmetricSignRequest, AsymmetricSignResponse, CreateCryptoKeyRequest, DecryptRequest, DecryptResponse,
     EncryptRequest, EncryptResponse, GetPublicKeyRequest, MacSignRequest, MacSignResponse, MacVerifyRequest,
@@ -37,6 +39,9 @@ fn default_setting() -> RetrySetting {
         factor: 1u64,
         take: 20,
         codes: vec![Code::Unavailable, Code::Unknown],
+        jitter: false,
+        max_elapsed: None,
+        timeout: None,
     }
 }
 
@@ -153,6 +158,52 @@ impl Client {
             .map(|r| r.into_inner())
     }
 
+    /// Restore crypto key version
+    ///
+    /// <https://cloud.google.com/kms/docs/reference/rpc/google.cloud.kms.v1#google.cloud.kms.v1.KeyManagementService.RestoreCryptoKeyVersion>
+    ///
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn restore_crypto_key_version(
+        &self,
+        req: RestoreCryptoKeyVersionRequest,
+        retry: Option<RetrySetting>,
+    ) -> Result<CryptoKeyVersion, Status> {
+        let action = || async {
+            let request = create_request(format!("name={}", req.name), req.clone());
+            self.cm
+                .conn()
+                .restore_crypto_key_version(request)
+                .await
+                .map_transient_err()
+        };
+        invoke(Some(retry.unwrap_or_else(default_setting)), action)
+            .await
+            .map(|r| r.into_inner())
+    }
+
+    /// Update crypto key primary version
+    ///
+    /// <https://cloud.google.com/kms/docs/reference/rpc/google.cloud.kms.v1#google.cloud.kms.v1.KeyManagementService.UpdateCryptoKeyPrimaryVersion>
+    ///
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn update_crypto_key_primary_version(
+        &self,
+        req: UpdateCryptoKeyPrimaryVersionRequest,
+        retry: Option<RetrySetting>,
+    ) -> Result<CryptoKey, Status> {
+        let action = || async {
+            let request = create_request(format!("name={}", req.name), req.clone());
+            self.cm
+                .conn()
+                .update_crypto_key_primary_version(request)
+                .await
+                .map_transient_err()
+        };
+        invoke(Some(retry.unwrap_or_else(default_setting)), action)
+            .await
+            .map(|r| r.into_inner())
+    }
+
     /// Get crypto key
     ///
     /// <https://cloud.google.com/kms/docs/reference/rpc/google.cloud.kms.v1#google.cloud.kms.v1.KeyManagementService.GetCryptoKey>
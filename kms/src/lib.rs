@@ -113,6 +113,58 @@
 //!}
 //!```
 //!
+//! #### Key rotation
+//!
+//! ```
+//! use google_cloud_googleapis::cloud::kms::v1::{CreateCryptoKeyVersionRequest, UpdateCryptoKeyPrimaryVersionRequest};
+//! use google_cloud_kms::client::Client;
+//!
+//! async fn run(client: &Client) {
+//!     let key = "projects/project/locations/asia-northeast1/keyRings/ring/cryptoKeys/key";
+//!     let version = client
+//!         .create_crypto_key_version(
+//!             CreateCryptoKeyVersionRequest {
+//!                 parent: key.to_string(),
+//!                 crypto_key_version: None,
+//!             },
+//!             None,
+//!         )
+//!         .await
+//!         .unwrap();
+//!     let version_id = version.name.rsplit('/').next().unwrap().to_string();
+//!     client
+//!         .update_crypto_key_primary_version(
+//!             UpdateCryptoKeyPrimaryVersionRequest {
+//!                 name: key.to_string(),
+//!                 crypto_key_version_id: version_id,
+//!             },
+//!             None,
+//!         )
+//!         .await
+//!         .unwrap();
+//! }
+//! ```
+//!
+//! #### Signing with integrity verification
+//!
+//! The CRC32C checksums of the requests and the responses are verified.
+//!
+//! ```
+//! use google_cloud_googleapis::cloud::kms::v1::digest::Digest;
+//! use google_cloud_kms::client::Client;
+//!
+//! async fn run(client: &Client, digest: Vec<u8>, data: Vec<u8>) {
+//!     let key = "projects/project/locations/asia-northeast1/keyRings/ring/cryptoKeys/key/cryptoKeyVersions/1";
+//!     let signature = client.sign_digest(key, Digest::Sha256(digest), None).await.unwrap();
+//!     // PEM encoded public key to verify the signature offline
+//!     let pem = client.public_key_pem(key, None).await.unwrap();
+//!
+//!     let mac_key = "projects/project/locations/asia-northeast1/keyRings/ring/cryptoKeys/mac/cryptoKeyVersions/1";
+//!     let mac = client.sign_mac(mac_key, data.clone(), None).await.unwrap();
+//!     let valid = client.verify_mac(mac_key, data, mac, None).await.unwrap();
+//! }
+//! ```
+//!
 //! ### Ethereum Integration
 //!
 //! Enable 'eth' feature.
@@ -1,4 +1,4 @@
-use google_cloud_gax::conn::{Channel, Environment};
+use google_cloud_gax::conn::{Channel, CompressionEncoding, Environment};
 use google_cloud_gax::conn::{ConnectionManager as GRPCConnectionManager, ConnectionOptions, Error};
 
 pub const AUDIENCE: &str = "https://pubsub.googleapis.com/";
@@ -32,4 +32,8 @@ impl ConnectionManager {
     pub fn conn(&self) -> Channel {
         self.inner.conn()
     }
+
+    pub fn compression(&self) -> Option<CompressionEncoding> {
+        self.inner.compression()
+    }
 }
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use google_cloud_gax::conn::Channel;
+use google_cloud_gax::create_request;
+use google_cloud_gax::grpc::{Response, Status};
+use google_cloud_gax::retry::{invoke, MapErr, RetrySetting};
+use google_cloud_googleapis::iam::v1::iam_policy_client::IamPolicyClient as InternalIamPolicyClient;
+use google_cloud_googleapis::iam::v1::{
+    GetIamPolicyRequest, Policy, SetIamPolicyRequest, TestIamPermissionsRequest, TestIamPermissionsResponse,
+};
+
+use crate::apiv1::conn_pool::ConnectionManager;
+
+/// IamPolicyClient manages the access control policies of the topics and subscriptions.
+#[derive(Clone, Debug)]
+pub(crate) struct IamPolicyClient {
+    cm: Arc<ConnectionManager>,
+}
+
+impl IamPolicyClient {
+    pub fn new(cm: Arc<ConnectionManager>) -> IamPolicyClient {
+        IamPolicyClient { cm }
+    }
+
+    #[inline]
+    fn client(&self) -> InternalIamPolicyClient<Channel> {
+        let client = InternalIamPolicyClient::new(self.cm.conn());
+        match self.cm.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        }
+    }
+
+    /// set_iam_policy sets the access control policy on the specified resource. Replaces any existing policy.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn set_iam_policy(
+        &self,
+        req: SetIamPolicyRequest,
+        retry: Option<RetrySetting>,
+    ) -> Result<Response<Policy>, Status> {
+        let resource = &req.resource;
+        let action = || async {
+            let mut client = self.client();
+            let request = create_request(format!("resource={resource}"), req.clone());
+            client.set_iam_policy(request).await.map_transient_err()
+        };
+        invoke(retry, action).await
+    }
+
+    /// get_iam_policy gets the access control policy for a resource.
+    /// Returns an empty policy if the resource exists and does not have a policy set.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn get_iam_policy(
+        &self,
+        req: GetIamPolicyRequest,
+        retry: Option<RetrySetting>,
+    ) -> Result<Response<Policy>, Status> {
+        let resource = &req.resource;
+        let action = || async {
+            let mut client = self.client();
+            let request = create_request(format!("resource={resource}"), req.clone());
+            client.get_iam_policy(request).await.map_transient_err()
+        };
+        invoke(retry, action).await
+    }
+
+    /// test_iam_permissions returns the permissions that the caller has on the specified resource.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn test_iam_permissions(
+        &self,
+        req: TestIamPermissionsRequest,
+        retry: Option<RetrySetting>,
+    ) -> Result<Response<TestIamPermissionsResponse>, Status> {
+        let resource = &req.resource;
+        let action = || async {
+            let mut client = self.client();
+            let request = create_request(format!("resource={resource}"), req.clone());
+            client.test_iam_permissions(request).await.map_transient_err()
+        };
+        invoke(retry, action).await
+    }
+}
@@ -2,6 +2,7 @@ use google_cloud_gax::grpc::Code;
 use google_cloud_gax::retry::RetrySetting;
 
 pub mod conn_pool;
+pub mod iam_policy_client;
 pub mod publisher_client;
 pub mod schema_client;
 pub mod subscriber_client;
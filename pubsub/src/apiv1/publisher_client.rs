@@ -13,6 +13,8 @@ use google_cloud_googleapis::pubsub::v1::{
 };
 
 use crate::apiv1::conn_pool::ConnectionManager;
+use crate::apiv1::iam_policy_client::IamPolicyClient;
+use crate::apiv1::schema_client::SchemaClient;
 use crate::apiv1::PUBSUB_MESSAGE_LIMIT;
 
 #[derive(Clone, Debug)]
@@ -29,9 +31,23 @@ impl PublisherClient {
 
     #[inline]
     fn client(&self) -> InternalPublisherClient<Channel> {
-        InternalPublisherClient::new(self.cm.conn())
+        let client = InternalPublisherClient::new(self.cm.conn())
             .max_decoding_message_size(PUBSUB_MESSAGE_LIMIT)
-            .max_encoding_message_size(PUBSUB_MESSAGE_LIMIT)
+            .max_encoding_message_size(PUBSUB_MESSAGE_LIMIT);
+        match self.cm.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        }
+    }
+
+    /// iam returns the client to manage the access control policies sharing the connections.
+    pub(crate) fn iam(&self) -> IamPolicyClient {
+        IamPolicyClient::new(self.cm.clone())
+    }
+
+    /// schema returns the client to manage the schemas sharing the connections.
+    pub(crate) fn schema(&self) -> SchemaClient {
+        SchemaClient::new(self.cm.clone())
     }
 
     /// create_topic creates the given topic with the given name. See the [resource name rules]
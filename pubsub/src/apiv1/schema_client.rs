@@ -20,13 +20,17 @@ pub(crate) struct SchemaClient {
 
 #[allow(dead_code)]
 impl SchemaClient {
-    /// create new publisher client
-    pub fn new(cm: ConnectionManager) -> SchemaClient {
-        SchemaClient { cm: Arc::new(cm) }
+    /// create new schema client
+    pub fn new(cm: Arc<ConnectionManager>) -> SchemaClient {
+        SchemaClient { cm }
     }
 
     fn client(&self) -> SchemaServiceClient<Channel> {
-        SchemaServiceClient::new(self.cm.conn())
+        let client = SchemaServiceClient::new(self.cm.conn());
+        match self.cm.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        }
     }
 
     /// create_schema creates a schema.
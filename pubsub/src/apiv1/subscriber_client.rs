@@ -15,6 +15,7 @@ use google_cloud_googleapis::pubsub::v1::{
 };
 
 use crate::apiv1::conn_pool::ConnectionManager;
+use crate::apiv1::iam_policy_client::IamPolicyClient;
 use crate::apiv1::PUBSUB_MESSAGE_LIMIT;
 
 pub(crate) fn create_empty_streaming_pull_request() -> StreamingPullRequest {
@@ -48,22 +49,35 @@ impl SubscriberClient {
 
     #[inline]
     fn client(&self) -> InternalSubscriberClient<Channel> {
-        InternalSubscriberClient::new(self.cm.conn())
+        let client = InternalSubscriberClient::new(self.cm.conn())
             .max_decoding_message_size(PUBSUB_MESSAGE_LIMIT)
-            .max_encoding_message_size(PUBSUB_MESSAGE_LIMIT)
+            .max_encoding_message_size(PUBSUB_MESSAGE_LIMIT);
+        match self.cm.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        }
     }
 
     #[inline]
     fn client_for_streaming_pull(&self) -> InternalSubscriberClient<Channel> {
-        InternalSubscriberClient::new(self.streaming_pull_cm.conn())
+        let client = InternalSubscriberClient::new(self.streaming_pull_cm.conn())
             .max_decoding_message_size(PUBSUB_MESSAGE_LIMIT)
-            .max_encoding_message_size(PUBSUB_MESSAGE_LIMIT)
+            .max_encoding_message_size(PUBSUB_MESSAGE_LIMIT);
+        match self.streaming_pull_cm.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        }
     }
 
     pub(crate) fn streaming_pool_size(&self) -> usize {
         self.streaming_pull_cm.num()
     }
 
+    /// iam returns the client to manage the access control policies sharing the connections.
+    pub(crate) fn iam(&self) -> IamPolicyClient {
+        IamPolicyClient::new(self.cm.clone())
+    }
+
     /// create_subscription creates a subscription to a given topic. See the [resource name rules]
     /// (https://cloud.google.com/pubsub/docs/admin#resource_names (at https://cloud.google.com/pubsub/docs/admin#resource_names)).
     /// If the subscription already exists, returns ALREADY_EXISTS.
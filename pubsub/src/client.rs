@@ -1,22 +1,34 @@
 use std::env::var;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{OwnedRwLockReadGuard, RwLock};
+use tokio_util::sync::CancellationToken;
 
 use google_cloud_gax::conn::{ConnectionOptions, Environment};
 use google_cloud_gax::grpc::Status;
 use google_cloud_gax::retry::RetrySetting;
 use google_cloud_googleapis::pubsub::v1::{
-    DetachSubscriptionRequest, ListSnapshotsRequest, ListSubscriptionsRequest, ListTopicsRequest, Snapshot,
+    CreateSchemaRequest, DeleteSchemaRequest, DetachSubscriptionRequest, GetSchemaRequest, ListSnapshotsRequest,
+    ListSubscriptionsRequest, ListTopicsRequest, Schema, SchemaView, Snapshot,
 };
 use google_cloud_token::NopeTokenSourceProvider;
 
 use crate::apiv1::conn_pool::{ConnectionManager, PUBSUB};
 use crate::apiv1::publisher_client::PublisherClient;
+use crate::apiv1::schema_client::SchemaClient;
 use crate::apiv1::subscriber_client::SubscriberClient;
+use crate::publisher::{Publisher, WeakPublisher};
 use crate::subscription::{Subscription, SubscriptionConfig};
 use crate::topic::{Topic, TopicConfig};
 
 #[derive(Debug)]
 pub struct ClientConfig {
-    /// gRPC channel pool size
+    /// gRPC channel pool size. RPCs are distributed across the channels in round-robin.
+    ///
+    /// The publisher, the subscriber and the StreamingPull each have a pool of this size.
+    /// Increase it when a single HTTP/2 connection's concurrent stream limit becomes the bottleneck.
+    /// `SubscribeConfig::with_enable_multiple_subscriber` opens a StreamingPull stream per channel.
     pub pool_size: Option<usize>,
     /// Pub/Sub project_id
     pub project_id: Option<String>,
@@ -55,6 +67,7 @@ impl ClientConfig {
         if let Environment::GoogleCloud(_) = self.environment {
             let ts = google_cloud_auth::token::DefaultTokenSourceProvider::new(Self::auth_config()).await?;
             self.project_id = self.project_id.or(ts.project_id.clone());
+            self.connection_option.quota_project = self.connection_option.quota_project.or(ts.quota_project_id());
             self.environment = Environment::GoogleCloud(Box::new(ts))
         }
         Ok(self)
@@ -71,6 +84,7 @@ impl ClientConfig {
             )
             .await?;
             self.project_id = self.project_id.or(ts.project_id.clone());
+            self.connection_option.quota_project = self.connection_option.quota_project.or(ts.quota_project_id());
             self.environment = Environment::GoogleCloud(Box::new(ts))
         }
         Ok(self)
@@ -100,6 +114,8 @@ pub struct Client {
     project_id: String,
     pubc: PublisherClient,
     subc: SubscriberClient,
+    schc: SchemaClient,
+    lifecycle: Lifecycle,
 }
 
 impl Client {
@@ -132,13 +148,29 @@ impl Client {
             )
             .await?,
         );
+        let schc = pubc.schema();
         Ok(Self {
             project_id: config.project_id.ok_or(Error::ProjectIdNotFound)?,
             pubc,
             subc,
+            schc,
+            lifecycle: Lifecycle::default(),
         })
     }
 
+    /// shutdown gracefully stops the background tasks of the topics and the subscriptions created from the client.
+    ///
+    /// The publishers created by `Topic::new_publisher` stop accepting messages and publish all the buffered messages.
+    /// The running `Subscription::receive` stop pulling messages, and return after the received messages are handled.
+    /// The `MessageStream`s created by `Subscription::subscribe` are cancelled.
+    ///
+    /// shutdown waits for all of them to finish, so call it before the process exits not to lose any messages.
+    /// When it is called from the handler of `Subscription::receive`, it doesn't wait for the receive,
+    /// which waits for the handler itself.
+    pub async fn shutdown(&self) {
+        self.lifecycle.shutdown().await;
+    }
+
     /// create_subscription creates a new subscription on a topic.
     ///
     /// id is the name of the subscription to create. It must start with a letter,
@@ -181,7 +213,7 @@ impl Client {
         };
         self.subc.list_subscriptions(req, retry).await.map(|v| {
             v.into_iter()
-                .map(|x| Subscription::new(x.name, self.subc.clone()))
+                .map(|x| Subscription::new(x.name, self.subc.clone()).with_lifecycle(self.lifecycle.clone()))
                 .collect()
         })
     }
@@ -189,6 +221,7 @@ impl Client {
     /// subscription creates a reference to a subscription.
     pub fn subscription(&self, id: &str) -> Subscription {
         Subscription::new(self.fully_qualified_subscription_name(id), self.subc.clone())
+            .with_lifecycle(self.lifecycle.clone())
     }
 
     /// detach_subscription detaches a subscription from its topic. All messages
@@ -242,6 +275,7 @@ impl Client {
     /// Avoid creating many Topic instances if you use them to publish.
     pub fn topic(&self, id: &str) -> Topic {
         Topic::new(self.fully_qualified_topic_name(id), self.pubc.clone(), self.subc.clone())
+            .with_lifecycle(self.lifecycle.clone())
     }
 
     /// get_snapshots lists the existing snapshots. Snapshots are used in Seek (at https://cloud.google.com/pubsub/docs/replay-overview) operations, which
@@ -257,6 +291,36 @@ impl Client {
         self.subc.list_snapshots(req, retry).await
     }
 
+    /// create_schema creates a new schema, which can be bound to a topic by `TopicConfig.schema_settings`.
+    ///
+    /// The schema ID must start with a letter, and contain only letters, numbers, dashes (-),
+    /// underscores (_), periods (.), tildes (~), plus (+) or percent signs (%).
+    pub async fn create_schema(&self, id: &str, schema: Schema, retry: Option<RetrySetting>) -> Result<Schema, Status> {
+        let req = CreateSchemaRequest {
+            parent: self.fully_qualified_project_name(),
+            schema: Some(schema),
+            schema_id: id.to_string(),
+        };
+        self.schc.create_schema(req, retry).await.map(|v| v.into_inner())
+    }
+
+    /// get_schema gets the schema. The definition is included only if the view is `SchemaView::Full`.
+    pub async fn get_schema(&self, id: &str, view: SchemaView, retry: Option<RetrySetting>) -> Result<Schema, Status> {
+        let req = GetSchemaRequest {
+            name: self.fully_qualified_schema_name(id),
+            view: view.into(),
+        };
+        self.schc.get_schema(req, retry).await.map(|v| v.into_inner())
+    }
+
+    /// delete_schema deletes the schema.
+    pub async fn delete_schema(&self, id: &str, retry: Option<RetrySetting>) -> Result<(), Status> {
+        let req = DeleteSchemaRequest {
+            name: self.fully_qualified_schema_name(id),
+        };
+        self.schc.delete_schema(req, retry).await.map(|_v| ())
+    }
+
     pub fn fully_qualified_topic_name(&self, id: &str) -> String {
         if id.contains('/') {
             id.to_string()
@@ -273,14 +337,76 @@ impl Client {
         }
     }
 
+    pub fn fully_qualified_schema_name(&self, id: &str) -> String {
+        if id.contains('/') {
+            id.to_string()
+        } else {
+            format!("projects/{}/schemas/{}", self.project_id, id)
+        }
+    }
+
     fn fully_qualified_project_name(&self) -> String {
         format!("projects/{}", self.project_id)
     }
 }
 
+tokio::task_local! {
+    /// Set while the handler of `Subscription::receive` is running.
+    static HANDLING: ();
+}
+
+/// Lifecycle is shared by the topics and the subscriptions created from the same client
+/// so that their background tasks are shut down together by `Client::shutdown`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Lifecycle {
+    cancel: CancellationToken,
+    publishers: Arc<Mutex<Vec<WeakPublisher>>>,
+    receivers: Arc<RwLock<()>>,
+}
+
+impl Lifecycle {
+    /// The token cancelled on shutdown.
+    pub(crate) fn cancellable(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Registers the publisher to be flushed on shutdown.
+    /// Only the weak handle is kept so that dropping the publisher still stops its workers,
+    /// and the handles of the dropped publishers are removed on the next registration.
+    pub(crate) fn register(&self, publisher: &Publisher) {
+        let mut publishers = self.publishers.lock().unwrap();
+        publishers.retain(|p| !p.is_dropped());
+        publishers.push(publisher.downgrade());
+    }
+
+    /// Returns the guard which the shutdown waits for to be dropped.
+    pub(crate) async fn track(&self) -> OwnedRwLockReadGuard<()> {
+        self.receivers.clone().read_owned().await
+    }
+
+    /// Runs the handler of the receive, from which the shutdown doesn't wait for the receive not to deadlock.
+    pub(crate) async fn handle<F: Future<Output = ()>>(f: F) {
+        HANDLING.scope((), f).await
+    }
+
+    async fn shutdown(&self) {
+        self.cancel.cancel();
+        let publishers = std::mem::take(&mut *self.publishers.lock().unwrap());
+        for publisher in publishers {
+            publisher.shutdown().await;
+        }
+        if HANDLING.try_with(|_| ()).is_err() {
+            let _ = self.receivers.write().await;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+    use std::sync::{Arc, Mutex};
     use std::thread;
     use std::time::Duration;
 
@@ -288,11 +414,13 @@ mod tests {
     use tokio_util::sync::CancellationToken;
     use uuid::Uuid;
 
-    use google_cloud_googleapis::pubsub::v1::PubsubMessage;
+    use google_cloud_googleapis::pubsub::v1::{schema, Encoding, PubsubMessage, Schema, SchemaSettings, SchemaView};
 
     use crate::client::Client;
+    use crate::publisher::PublisherConfig;
     use crate::subscriber::SubscriberConfig;
     use crate::subscription::{ReceiveConfig, SubscriptionConfig};
+    use crate::topic::TopicConfig;
 
     #[ctor::ctor]
     fn init() {
@@ -329,6 +457,7 @@ mod tests {
         let config = ReceiveConfig {
             worker_count: 2,
             channel_capacity: None,
+            max_concurrency: None,
             subscriber_config: Some(SubscriberConfig {
                 ping_interval: Duration::from_secs(1),
                 ..Default::default()
@@ -404,6 +533,162 @@ mod tests {
         publisher.shutdown().await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_shutdown() {
+        let client = create_client().await;
+        let uuid = Uuid::new_v4().hyphenated().to_string();
+        let topic_id = &format!("t{}", &uuid);
+        let subscription_id = &format!("s{}", &uuid);
+        let topic = client.create_topic(topic_id.as_str(), None, None).await.unwrap();
+        let subscription = client
+            .create_subscription(subscription_id.as_str(), topic_id.as_str(), SubscriptionConfig::default(), None)
+            .await
+            .unwrap();
+
+        // The buffered messages are not sent until shutdown.
+        let publisher = topic.new_publisher(Some(PublisherConfig {
+            flush_interval: Duration::from_secs(60),
+            bundle_size: 100,
+            ..Default::default()
+        }));
+        let mut awaiters = Vec::with_capacity(10);
+        for _ in 0..10 {
+            awaiters.push(publisher.publish(PubsubMessage::default()).await);
+        }
+
+        let (s, mut r) = tokio::sync::mpsc::channel(100);
+        let cancellation_token = CancellationToken::new();
+        let handle = tokio::spawn(async move {
+            subscription
+                .receive(
+                    move |v, _ctx| {
+                        let s = s.clone();
+                        async move {
+                            let _ = v.ack().await;
+                            let _ = s.send(v.message.message_id.clone()).await;
+                        }
+                    },
+                    cancellation_token,
+                    None,
+                )
+                .await
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        client.shutdown().await;
+        for awaiter in awaiters {
+            assert!(!awaiter.get().await.unwrap().is_empty());
+        }
+        // receive is finished by the shutdown.
+        let result = tokio::time::timeout(Duration::from_secs(1), handle).await.unwrap();
+        result.unwrap().unwrap();
+
+        let mut count = 0;
+        while r.recv().await.is_some() {
+            count += 1;
+        }
+        assert!(count <= 10);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_shutdown_from_handler() {
+        let client = create_client().await;
+        let uuid = Uuid::new_v4().hyphenated().to_string();
+        let topic_id = &format!("t{}", &uuid);
+        let subscription_id = &format!("s{}", &uuid);
+        let topic = client.create_topic(topic_id.as_str(), None, None).await.unwrap();
+        let subscription = client
+            .create_subscription(subscription_id.as_str(), topic_id.as_str(), SubscriptionConfig::default(), None)
+            .await
+            .unwrap();
+        let mut publisher = topic.new_publisher(None);
+        publisher.publish(PubsubMessage::default()).await.get().await.unwrap();
+        publisher.shutdown().await;
+
+        let handler_client = client.clone();
+        let receive = subscription.receive(
+            move |v, _ctx| {
+                let client = handler_client.clone();
+                async move {
+                    let _ = v.ack().await;
+                    client.shutdown().await;
+                }
+            },
+            CancellationToken::new(),
+            None,
+        );
+        tokio::time::timeout(Duration::from_secs(10), receive)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_shutdown_waits_for_handler() {
+        let client = create_client().await;
+        let uuid = Uuid::new_v4().hyphenated().to_string();
+        let topic_id = &format!("t{}", &uuid);
+        let subscription_id = &format!("s{}", &uuid);
+        let topic = client.create_topic(topic_id.as_str(), None, None).await.unwrap();
+        let subscription = client
+            .create_subscription(subscription_id.as_str(), topic_id.as_str(), SubscriptionConfig::default(), None)
+            .await
+            .unwrap();
+        let mut publisher = topic.new_publisher(None);
+        publisher.publish(PubsubMessage::default()).await.get().await.unwrap();
+        publisher.shutdown().await;
+
+        let (started_sender, started_receiver) = tokio::sync::oneshot::channel();
+        let started_sender = Arc::new(Mutex::new(Some(started_sender)));
+        let handled = Arc::new(AtomicBool::new(false));
+        let handled_in_handler = handled.clone();
+        let receive = tokio::spawn(async move {
+            subscription
+                .receive(
+                    move |v, _ctx| {
+                        let started_sender = started_sender.lock().unwrap().take();
+                        let handled = handled_in_handler.clone();
+                        async move {
+                            if let Some(sender) = started_sender {
+                                let _ = sender.send(());
+                            }
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                            let _ = v.ack().await;
+                            handled.store(true, SeqCst);
+                        }
+                    },
+                    CancellationToken::new(),
+                    None,
+                )
+                .await
+        });
+        started_receiver.await.unwrap();
+        client.shutdown().await;
+        assert!(handled.load(SeqCst));
+        receive.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_lifecycle_releases_dropped_publishers() {
+        let client = create_client().await;
+        let uuid = Uuid::new_v4().hyphenated().to_string();
+        let topic = client.create_topic(&format!("t{}", &uuid), None, None).await.unwrap();
+
+        for _ in 0..3 {
+            let publisher = topic.new_publisher(None);
+            drop(publisher);
+        }
+        let publisher = topic.new_publisher(None);
+        // only the handle of the live publisher remains.
+        assert_eq!(client.lifecycle.publishers.lock().unwrap().len(), 1);
+        drop(publisher);
+        client.shutdown().await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[serial]
     async fn test_publish_subscribe_ordered() {
@@ -458,6 +743,56 @@ mod tests {
         assert_eq!(1, subs_after.len() - subs.len());
         assert_eq!(1, snapshots_after.len() - snapshots.len());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_create_topic_with_schema() {
+        let client = create_client().await;
+
+        let uuid = Uuid::new_v4().hyphenated().to_string();
+        let schema_id = &format!("schema{}", &uuid);
+        let topic_id = &format!("t{}", &uuid);
+        let definition = r#"{"type":"record","name":"User","fields":[{"name":"name","type":"string"}]}"#;
+        let schema = client
+            .create_schema(
+                schema_id,
+                Schema {
+                    r#type: schema::Type::Avro.into(),
+                    definition: definition.to_string(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(schema.name, client.fully_qualified_schema_name(schema_id));
+
+        let fetched = client.get_schema(schema_id, SchemaView::Full, None).await.unwrap();
+        assert_eq!(fetched.definition, definition);
+
+        let config = TopicConfig {
+            schema_settings: Some(SchemaSettings {
+                schema: schema.name.to_string(),
+                encoding: Encoding::Json.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let topic = client.create_topic(topic_id, Some(config), None).await.unwrap();
+        let publisher = topic.new_publisher(None);
+        let awaiter = publisher
+            .publish(PubsubMessage {
+                data: r#"{"name":"alice"}"#.into(),
+                ..Default::default()
+            })
+            .await;
+        awaiter.get().await.unwrap();
+
+        let mut publisher = publisher;
+        publisher.shutdown().await;
+        topic.delete(None).await.unwrap();
+        client.delete_schema(schema_id, None).await.unwrap();
+    }
 }
 
 #[cfg(test)]
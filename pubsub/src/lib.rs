@@ -57,6 +57,7 @@
 //! use google_cloud_googleapis::pubsub::v1::PubsubMessage;
 //! use google_cloud_pubsub::topic::TopicConfig;
 //! use google_cloud_pubsub::subscription::SubscriptionConfig;
+//! use google_cloud_pubsub::publisher::PublishError;
 //! use google_cloud_gax::grpc::Status;
 //! use tokio::task::JoinHandle;
 //! use tokio_util::sync::CancellationToken;
@@ -76,7 +77,7 @@
 //!     let publisher = topic.new_publisher(None);
 //!
 //!     // Publish message.
-//!     let tasks : Vec<JoinHandle<Result<String,Status>>> = (0..10).into_iter().map(|_i| {
+//!     let tasks : Vec<JoinHandle<Result<String,PublishError>>> = (0..10).into_iter().map(|_i| {
 //!         let publisher = publisher.clone();
 //!         tokio::spawn(async move {
 //!             let msg = PubsubMessage {
@@ -100,6 +101,7 @@
 //!     }
 //!
 //!     // Wait for publishers in topic finish.
+//!     // Use `shutdown_with_timeout` to bound the wait and get back the messages not published in time.
 //!     let mut publisher = publisher;
 //!     publisher.shutdown();
 //!
@@ -227,6 +229,80 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ### Zero-copy Payloads
+//!
+//! With the `bytes` feature, `PubsubMessage.data` is a `bytes::Bytes` instead of a `Vec<u8>`.
+//! Published and received payloads are then shared without copying the buffer.
+//! The `bytes` crate is re-exported as `google_cloud_pubsub::bytes`.
+//!
+//! ```toml
+//! [dependencies]
+//! google-cloud-pubsub = { version = "version", features = ["bytes"] }
+//! ```
+//!
+//! ```ignore
+//! use google_cloud_googleapis::pubsub::v1::PubsubMessage;
+//! use google_cloud_pubsub::bytes::Bytes;
+//!
+//! fn message(payload: Bytes) -> PubsubMessage {
+//!     // Cloning the message only increments the reference count of the payload.
+//!     PubsubMessage {
+//!         data: payload,
+//!         ..Default::default()
+//!     }
+//! }
+//! ```
+//!
+//! ### IAM Policy
+//!
+//! The access control policies of topics and subscriptions are managed with `get_iam_policy`, `set_iam_policy` and `test_iam_permissions`.
+//!
+//! ```rust
+//! use google_cloud_gax::grpc::Status;
+//! use google_cloud_googleapis::iam::v1::Binding;
+//! use google_cloud_pubsub::subscription::Subscription;
+//!
+//! async fn grant(subscription: &Subscription, member: &str) -> Result<(), Status> {
+//!     let mut policy = subscription.get_iam_policy(None).await?;
+//!     policy.bindings.push(Binding {
+//!         role: "roles/pubsub.subscriber".to_string(),
+//!         members: vec![member.to_string()],
+//!         condition: None,
+//!     });
+//!     // The etag of the fetched policy prevents overwriting a concurrent update.
+//!     subscription.set_iam_policy(policy, None).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Topic with Schema
+//!
+//! A topic bound to a schema rejects the messages that do not conform to it.
+//!
+//! ```rust
+//! use google_cloud_gax::grpc::Status;
+//! use google_cloud_googleapis::pubsub::v1::{schema, Encoding, Schema, SchemaSettings};
+//! use google_cloud_pubsub::client::Client;
+//! use google_cloud_pubsub::topic::{Topic, TopicConfig};
+//!
+//! async fn create(client: &Client) -> Result<Topic, Status> {
+//!     let schema = client.create_schema("user-schema", Schema {
+//!         r#type: schema::Type::Avro.into(),
+//!         definition: r#"{"type":"record","name":"User","fields":[{"name":"name","type":"string"}]}"#.to_string(),
+//!         ..Default::default()
+//!     }, None).await?;
+//!     let config = TopicConfig {
+//!         schema_settings: Some(SchemaSettings {
+//!             schema: schema.name,
+//!             encoding: Encoding::Json.into(),
+//!             ..Default::default()
+//!         }),
+//!         ..Default::default()
+//!     };
+//!     client.create_topic("user-topic", Some(config), None).await
+//! }
+//! ```
 pub mod apiv1;
 pub mod client;
 pub mod publisher;
@@ -234,3 +310,6 @@ pub mod subscriber;
 pub mod subscription;
 pub mod topic;
 pub mod util;
+
+#[cfg(feature = "bytes")]
+pub use bytes;
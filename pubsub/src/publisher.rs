@@ -1,24 +1,89 @@
-use std::collections::HashMap;
-use std::ops::{Deref, DerefMut};
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex, Weak};
 use std::time::Duration;
 
 use async_channel::Receiver;
+use prost::Message;
 use tokio::sync::oneshot;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tokio::time::timeout;
+use tokio::time::{timeout, timeout_at, Instant};
 
-use google_cloud_gax::grpc::Status;
+use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::RetrySetting;
 use google_cloud_googleapis::pubsub::v1::{PublishRequest, PubsubMessage};
 
 use crate::apiv1::publisher_client::PublisherClient;
 use crate::util::ToUsize;
 
+#[cfg(feature = "bytes")]
+type Data = bytes::Bytes;
+#[cfg(not(feature = "bytes"))]
+type Data = Vec<u8>;
+
+/// MessageBuilder builds the [`PubsubMessage`] to publish.
+///
+/// ```
+/// use google_cloud_pubsub::publisher::MessageBuilder;
+///
+/// let message = MessageBuilder::new("payload")
+///     .with_ordering_key("order")
+///     .with_attribute("event", "created")
+///     .build();
+/// assert_eq!(message.ordering_key, "order");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    message: PubsubMessage,
+}
+
+impl MessageBuilder {
+    pub fn new(data: impl Into<Data>) -> Self {
+        Self::default().with_data(data)
+    }
+
+    pub fn with_data(mut self, data: impl Into<Data>) -> Self {
+        self.message.data = data.into();
+        self
+    }
+
+    pub fn with_ordering_key(mut self, ordering_key: impl Into<String>) -> Self {
+        self.message.ordering_key = ordering_key.into();
+        self
+    }
+
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.message.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_attributes<K, V>(mut self, attributes: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.message
+            .attributes
+            .extend(attributes.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    pub fn build(self) -> PubsubMessage {
+        self.message
+    }
+}
+
+impl From<MessageBuilder> for PubsubMessage {
+    fn from(builder: MessageBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[derive(Debug)]
 pub(crate) struct ReservedMessage {
-    pub producer: oneshot::Sender<Result<String, Status>>,
+    pub producer: oneshot::Sender<Result<String, PublishError>>,
     pub message: PubsubMessage,
 }
 
@@ -27,14 +92,87 @@ pub(crate) enum Reserved {
     Multi(Vec<ReservedMessage>),
 }
 
+/// PublishError is the error of the message returned from `Awaiter::get`.
+#[derive(thiserror::Error, Debug)]
+pub enum PublishError {
+    #[error(transparent)]
+    GRPC(#[from] Status),
+    /// The publishing of the message with the ordering key failed.
+    /// The publishing for the key is paused until `Publisher::resume_ordering_key` is called.
+    #[error("publishing for ordering key {ordering_key} failed: {}", status.message())]
+    OrderingKeyFailed {
+        ordering_key: String,
+        #[source]
+        status: Status,
+    },
+    /// The message was not published since the publishing for the ordering key is paused by the previous failure.
+    #[error("publishing for ordering key {0} paused due to previous error. Call resume_ordering_key(key) to resume publishing")]
+    OrderingKeyPaused(String),
+}
+
+impl PublishError {
+    /// ordering_key returns the ordering key whose publishing is paused by the error.
+    pub fn ordering_key(&self) -> Option<&str> {
+        match self {
+            Self::GRPC(_) => None,
+            Self::OrderingKeyFailed { ordering_key, .. } => Some(ordering_key),
+            Self::OrderingKeyPaused(ordering_key) => Some(ordering_key),
+        }
+    }
+
+    /// code returns the code of the status, or `FailedPrecondition` for the paused ordering key.
+    pub fn code(&self) -> Code {
+        match self {
+            Self::GRPC(status) => status.code(),
+            Self::OrderingKeyFailed { status, .. } => status.code(),
+            Self::OrderingKeyPaused(_) => Code::FailedPrecondition,
+        }
+    }
+}
+
+impl From<PublishError> for Status {
+    fn from(e: PublishError) -> Self {
+        match e {
+            PublishError::GRPC(status) => status,
+            e => Status::new(e.code(), e.to_string()),
+        }
+    }
+}
+
+/// The ordering keys whose publishing is paused due to the previous failure.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PausedKeys {
+    inner: Arc<StdMutex<HashSet<String>>>,
+}
+
+impl PausedKeys {
+    fn pause(&self, key: &str) {
+        self.inner.lock().unwrap().insert(key.to_string());
+    }
+
+    fn resume(&self, key: &str) {
+        self.inner.lock().unwrap().remove(key);
+    }
+
+    fn is_paused(&self, key: &str) -> bool {
+        !key.is_empty() && self.inner.lock().unwrap().contains(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.lock().unwrap().iter().cloned().collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PublisherConfig {
     /// worker count. each workers have gRPC channel
     pub workers: usize,
-    /// interval for flush bundle message
+    /// max time a message waits in the bundle before the bundle is flushed
     pub flush_interval: Duration,
-    /// max bundle size to flush
+    /// max number of messages in the bundle to flush
     pub bundle_size: usize,
+    /// max total bytes of the messages in the bundle to flush
+    pub bundle_byte_size: usize,
     pub retry_setting: Option<RetrySetting>,
 }
 
@@ -44,23 +182,24 @@ impl Default for PublisherConfig {
             workers: 3,
             flush_interval: Duration::from_millis(100),
             bundle_size: 3,
+            bundle_byte_size: 1_000_000,
             retry_setting: None,
         }
     }
 }
 
 pub struct Awaiter {
-    consumer: oneshot::Receiver<Result<String, Status>>,
+    consumer: oneshot::Receiver<Result<String, PublishError>>,
 }
 
 impl Awaiter {
-    pub(crate) fn new(consumer: oneshot::Receiver<Result<String, Status>>) -> Self {
+    pub(crate) fn new(consumer: oneshot::Receiver<Result<String, PublishError>>) -> Self {
         Self { consumer }
     }
-    pub async fn get(self) -> Result<String, Status> {
+    pub async fn get(self) -> Result<String, PublishError> {
         match self.consumer.await {
             Ok(v) => v,
-            Err(_e) => Err(Status::cancelled("closed")),
+            Err(_e) => Err(Status::cancelled("closed").into()),
         }
     }
 }
@@ -76,6 +215,7 @@ pub struct Publisher {
     tasks: Arc<Mutex<Tasks>>,
     fqtn: String,
     pubc: PublisherClient,
+    paused_keys: PausedKeys,
 }
 
 impl Publisher {
@@ -99,12 +239,20 @@ impl Publisher {
             ordering_senders.push(sender);
         }
 
+        let paused_keys = PausedKeys::default();
         Self {
             sender,
             ordering_senders: Arc::new(ordering_senders),
-            tasks: Arc::new(Mutex::new(Tasks::new(fqtn.clone(), pubc.clone(), receivers, config))),
+            tasks: Arc::new(Mutex::new(Tasks::new(
+                fqtn.clone(),
+                pubc.clone(),
+                receivers,
+                config,
+                paused_keys.clone(),
+            ))),
             fqtn,
             pubc,
+            paused_keys,
         }
     }
 
@@ -131,9 +279,15 @@ impl Publisher {
     ///
     /// publish returns a non-nil Awaiter which will be ready when the
     /// message has been sent (or has failed to be sent) to the server.
+    ///
+    /// If a previous publish for the message's ordering key failed, the publishing for
+    /// that key is paused and the Awaiter returns `PublishError::OrderingKeyPaused` until resume_ordering_key is called.
+    /// The paused key is taken from the error by `PublishError::ordering_key`.
     pub async fn publish(&self, message: PubsubMessage) -> Awaiter {
         let (producer, consumer) = oneshot::channel();
-        if message.ordering_key.is_empty() {
+        if self.paused_keys.is_paused(&message.ordering_key) {
+            let _ = producer.send(Err(PublishError::OrderingKeyPaused(message.ordering_key.clone())));
+        } else if message.ordering_key.is_empty() {
             let _ = self
                 .sender
                 .send(Reserved::Single(ReservedMessage { producer, message }))
@@ -153,7 +307,9 @@ impl Publisher {
     /// The actual publishing to the server is done asynchronously.
     pub fn publish_blocking(&self, message: PubsubMessage) -> Awaiter {
         let (producer, consumer) = oneshot::channel();
-        if message.ordering_key.is_empty() {
+        if self.paused_keys.is_paused(&message.ordering_key) {
+            let _ = producer.send(Err(PublishError::OrderingKeyPaused(message.ordering_key.clone())));
+        } else if message.ordering_key.is_empty() {
             let _ = self
                 .sender
                 .send_blocking(Reserved::Single(ReservedMessage { producer, message }));
@@ -176,6 +332,10 @@ impl Publisher {
         for message in messages {
             let (producer, consumer) = oneshot::channel();
             awaiters.push(Awaiter::new(consumer));
+            if self.paused_keys.is_paused(&message.ordering_key) {
+                let _ = producer.send(Err(PublishError::OrderingKeyPaused(message.ordering_key.clone())));
+                continue;
+            }
             split_by_key
                 .entry(message.ordering_key.clone())
                 .or_default()
@@ -194,18 +354,88 @@ impl Publisher {
         awaiters
     }
 
+    /// resume_ordering_key resumes accepting messages for the given ordering key.
+    /// Publishing using an ordering key might be paused if an error is encountered while publishing,
+    /// to prevent messages from being published out of order.
+    pub fn resume_ordering_key(&self, ordering_key: &str) {
+        self.paused_keys.resume(ordering_key);
+    }
+
+    /// paused_ordering_keys returns the ordering keys whose publishing is paused due to the previous failure.
+    /// The messages of the other ordering keys are published while these keys are paused.
+    pub fn paused_ordering_keys(&self) -> Vec<String> {
+        self.paused_keys.keys()
+    }
+
+    /// shutdown stops accepting messages and waits for the buffered messages to be published.
+    /// It can be called more than once from any clone of the publisher,
+    /// and every call returns after the buffered messages are published.
     pub async fn shutdown(&mut self) {
+        self.close();
+        self.tasks.lock().await.done().await;
+    }
+
+    /// shutdown_with_timeout stops accepting messages and waits for the buffered messages to be published within the timeout.
+    /// The messages which could not be published before the timeout are returned, so that the caller can persist them.
+    /// The messages being published at the timeout are not returned and their Awaiters are still notified of the result.
+    pub async fn shutdown_with_timeout(&mut self, duration: Duration) -> Result<(), Vec<PubsubMessage>> {
+        self.close();
+        let mut tasks = self.tasks.lock().await;
+        if timeout(duration, tasks.done()).await.is_ok() {
+            return Ok(());
+        }
+        let unpublished = tasks.unpublished();
+        if unpublished.is_empty() {
+            Ok(())
+        } else {
+            Err(unpublished)
+        }
+    }
+
+    fn close(&self) {
         self.sender.close();
         for s in self.ordering_senders.iter() {
             s.close();
         }
-        self.tasks.lock().await.done().await;
     }
+
+    /// downgrade returns the handle which doesn't keep the publisher alive.
+    pub(crate) fn downgrade(&self) -> WeakPublisher {
+        WeakPublisher {
+            tasks: Arc::downgrade(&self.tasks),
+        }
+    }
+}
+
+/// WeakPublisher shuts down the publisher if it is still alive.
+/// Once all the clones of the publisher are dropped, the workers stop by themselves.
+#[derive(Clone, Debug)]
+pub(crate) struct WeakPublisher {
+    tasks: Weak<Mutex<Tasks>>,
 }
 
+impl WeakPublisher {
+    pub(crate) fn is_dropped(&self) -> bool {
+        self.tasks.strong_count() == 0
+    }
+
+    pub(crate) async fn shutdown(&self) {
+        if let Some(tasks) = self.tasks.upgrade() {
+            let mut tasks = tasks.lock().await;
+            tasks.close();
+            tasks.done().await;
+        }
+    }
+}
+
+/// The bundles to be flushed by any worker after the publisher is closed.
+type PendingBundles = Arc<StdMutex<Vec<Vec<ReservedMessage>>>>;
+
 #[derive(Debug)]
 struct Tasks {
     inner: Option<Vec<JoinHandle<()>>>,
+    receivers: Vec<Receiver<Reserved>>,
+    pending: PendingBundles,
 }
 
 impl Tasks {
@@ -214,24 +444,34 @@ impl Tasks {
         pubc: PublisherClient,
         receivers: Vec<async_channel::Receiver<Reserved>>,
         config: PublisherConfig,
+        paused_keys: PausedKeys,
     ) -> Self {
+        let pending = PendingBundles::default();
         let tasks = receivers
-            .into_iter()
+            .iter()
             .map(|receiver| {
                 Self::run_task(
-                    receiver,
+                    receiver.clone(),
                     pubc.clone(),
                     topic.clone(),
                     config.retry_setting.clone(),
                     config.flush_interval,
                     config.bundle_size,
+                    config.bundle_byte_size,
+                    paused_keys.clone(),
+                    pending.clone(),
                 )
             })
             .collect();
 
-        Self { inner: Some(tasks) }
+        Self {
+            inner: Some(tasks),
+            receivers,
+            pending,
+        }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn run_task(
         receiver: Receiver<Reserved>,
         mut client: PublisherClient,
@@ -239,35 +479,44 @@ impl Tasks {
         retry: Option<RetrySetting>,
         flush_interval: Duration,
         bundle_size: usize,
+        bundle_byte_size: usize,
+        paused_keys: PausedKeys,
+        pending: PendingBundles,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
             //TODO enable manage task by ordering_key
             let mut bundle = MessageBundle::new();
+            // the bundle is flushed at the latest flush_interval after its first message.
+            let mut deadline = Instant::now() + flush_interval;
             while !receiver.is_closed() {
-                let result = match timeout(flush_interval, &mut receiver.recv()).await {
+                let result = match timeout_at(deadline, &mut receiver.recv()).await {
                     Ok(result) => result,
                     //timed out
                     Err(_e) => {
                         if !bundle.is_empty() {
                             tracing::trace!("elapsed: flush buffer : {}", topic);
                             for value in bundle.key_by() {
-                                Self::flush(&mut client, topic.as_str(), value, retry.clone()).await;
+                                Self::flush(&mut client, topic.as_str(), value, retry.clone(), &paused_keys).await;
                             }
                             bundle = MessageBundle::new();
                         }
+                        deadline = Instant::now() + flush_interval;
                         continue;
                     }
                 };
                 match result {
                     Ok(reserved) => {
+                        if bundle.is_empty() {
+                            deadline = Instant::now() + flush_interval;
+                        }
                         match reserved {
                             Reserved::Single(message) => bundle.push(message),
                             Reserved::Multi(messages) => bundle.extend(messages),
                         }
-                        if bundle.len() >= bundle_size {
+                        if bundle.len() >= bundle_size || bundle.byte_size >= bundle_byte_size {
                             tracing::trace!("bundle size max: {}", topic);
                             for value in bundle.key_by() {
-                                Self::flush(&mut client, topic.as_str(), value, retry.clone()).await;
+                                Self::flush(&mut client, topic.as_str(), value, retry.clone(), &paused_keys).await;
                             }
                             bundle = MessageBundle::new();
                         }
@@ -278,10 +527,23 @@ impl Tasks {
             }
 
             tracing::trace!("stop publisher : {}", topic);
+            // the messages sent before the channel was closed remain in the channel.
+            while let Ok(reserved) = receiver.try_recv() {
+                match reserved {
+                    Reserved::Single(message) => bundle.push(message),
+                    Reserved::Multi(messages) => bundle.extend(messages),
+                }
+            }
             if !bundle.is_empty() {
                 tracing::trace!("flush rest buffer : {}", topic);
-                for value in bundle.key_by() {
-                    Self::flush(&mut client, topic.as_str(), value, retry.clone()).await;
+                pending.lock().unwrap().extend(bundle.key_by());
+            }
+            // the pending bundles are shared so that the rest can be taken by shutdown_with_timeout.
+            loop {
+                let value = pending.lock().unwrap().pop();
+                match value {
+                    Some(value) => Self::flush(&mut client, topic.as_str(), value, retry.clone(), &paused_keys).await,
+                    None => break,
                 }
             }
         })
@@ -293,9 +555,24 @@ impl Tasks {
         topic: &str,
         bundle: Vec<ReservedMessage>,
         retry_setting: Option<RetrySetting>,
+        paused_keys: &PausedKeys,
     ) {
+        // the bundle is already split by the ordering key.
+        let ordering_key = match bundle.first() {
+            Some(v) => v.message.ordering_key.clone(),
+            None => return,
+        };
+        if paused_keys.is_paused(&ordering_key) {
+            for r in bundle {
+                let _ = r
+                    .producer
+                    .send(Err(PublishError::OrderingKeyPaused(ordering_key.clone())));
+            }
+            return;
+        }
+
         let mut data = Vec::<PubsubMessage>::with_capacity(bundle.len());
-        let mut callback = Vec::<oneshot::Sender<Result<String, Status>>>::with_capacity(bundle.len());
+        let mut callback = Vec::<oneshot::Sender<Result<String, PublishError>>>::with_capacity(bundle.len());
         bundle.into_iter().for_each(|r| {
             data.push(r.message);
             callback.push(r.producer);
@@ -320,10 +597,22 @@ impl Tasks {
                 }
             }
             Err(status) => {
+                if !ordering_key.is_empty() {
+                    tracing::debug!("pause publishing for ordering key : {}", ordering_key);
+                    paused_keys.pause(&ordering_key);
+                }
                 for p in callback.into_iter() {
                     let code = status.code();
-                    let status = Status::new(code, (*status.message()).to_string());
-                    if p.send(Err(status)).is_err() {
+                    let status = Status::new(status.code(), status.message());
+                    let error = if ordering_key.is_empty() {
+                        PublishError::GRPC(status)
+                    } else {
+                        PublishError::OrderingKeyFailed {
+                            ordering_key: ordering_key.clone(),
+                            status,
+                        }
+                    };
+                    if p.send(Err(error)).is_err() {
                         tracing::error!("failed to notify : status={}", code);
                     }
                 }
@@ -339,15 +628,54 @@ impl Tasks {
             }
         }
     }
+
+    /// close stops accepting messages, same as closing the senders of the publisher.
+    fn close(&self) {
+        for receiver in self.receivers.iter() {
+            receiver.close();
+        }
+    }
+
+    /// unpublished takes the messages not yet flushed by the workers.
+    fn unpublished(&self) -> Vec<PubsubMessage> {
+        let mut messages = vec![];
+        for receiver in self.receivers.iter() {
+            while let Ok(reserved) = receiver.try_recv() {
+                match reserved {
+                    Reserved::Single(r) => messages.push(r.message),
+                    Reserved::Multi(rs) => messages.extend(rs.into_iter().map(|r| r.message)),
+                }
+            }
+        }
+        for bundle in self.pending.lock().unwrap().drain(..) {
+            messages.extend(bundle.into_iter().map(|r| r.message));
+        }
+        messages
+    }
 }
 
 struct MessageBundle {
     inner: Vec<ReservedMessage>,
+    byte_size: usize,
 }
 
 impl MessageBundle {
     fn new() -> Self {
-        Self { inner: vec![] }
+        Self {
+            inner: vec![],
+            byte_size: 0,
+        }
+    }
+
+    fn push(&mut self, value: ReservedMessage) {
+        self.byte_size += value.message.encoded_len();
+        self.inner.push(value);
+    }
+
+    fn extend(&mut self, values: Vec<ReservedMessage>) {
+        for value in values {
+            self.push(value);
+        }
     }
 
     fn key_by(self) -> Vec<Vec<ReservedMessage>> {
@@ -379,16 +707,12 @@ impl Deref for MessageBundle {
     }
 }
 
-impl DerefMut for MessageBundle {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
-    }
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::publisher::{MessageBundle, ReservedMessage};
+    use crate::publisher::{MessageBuilder, MessageBundle, PausedKeys, PublishError, ReservedMessage};
+    use google_cloud_gax::grpc::{Code, Status};
     use google_cloud_googleapis::pubsub::v1::PubsubMessage;
+    use prost::Message;
     use tokio::sync::oneshot;
 
     fn msg(key: &str) -> ReservedMessage {
@@ -419,4 +743,65 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_message_bundle_byte_size() {
+        let mut bundle = MessageBundle::new();
+        let value = msg("a");
+        let size = value.message.encoded_len();
+        bundle.push(value);
+        bundle.extend(vec![msg("a"), msg("a")]);
+        assert_eq!(3, bundle.len());
+        assert_eq!(size * 3, bundle.byte_size);
+    }
+
+    #[test]
+    fn test_paused_keys() {
+        let keys = PausedKeys::default();
+        keys.pause("a");
+        assert!(keys.is_paused("a"));
+        assert!(!keys.is_paused("b"));
+        assert!(!keys.is_paused(""));
+        assert_eq!(keys.keys(), vec!["a".to_string()]);
+        keys.resume("a");
+        assert!(!keys.is_paused("a"));
+        assert!(keys.keys().is_empty());
+    }
+
+    #[test]
+    fn test_publish_error() {
+        let error = PublishError::OrderingKeyFailed {
+            ordering_key: "a".to_string(),
+            status: Status::unavailable("unavailable"),
+        };
+        assert_eq!(error.code(), Code::Unavailable);
+        assert_eq!(error.ordering_key(), Some("a"));
+        assert_eq!(error.to_string(), "publishing for ordering key a failed: unavailable");
+        let status = Status::from(error);
+        assert_eq!(status.code(), Code::Unavailable);
+        assert_eq!(status.message(), "publishing for ordering key a failed: unavailable");
+
+        let error = PublishError::OrderingKeyPaused("キー".to_string());
+        assert_eq!(error.code(), Code::FailedPrecondition);
+        assert_eq!(error.ordering_key(), Some("キー"));
+
+        let error = PublishError::from(Status::unavailable("unavailable"));
+        assert_eq!(error.code(), Code::Unavailable);
+        assert!(error.ordering_key().is_none());
+        assert_eq!(Status::from(error).message(), "unavailable");
+    }
+
+    #[test]
+    fn test_message_builder() {
+        let message = MessageBuilder::new("abc")
+            .with_ordering_key("order")
+            .with_attribute("event", "created")
+            .with_attributes([("source", "test"), ("event", "updated")])
+            .build();
+        assert_eq!(&message.data[..], b"abc");
+        assert_eq!(message.ordering_key, "order");
+        assert_eq!(message.attributes.len(), 2);
+        assert_eq!(message.attributes["event"], "updated");
+        assert_eq!(message.attributes["source"], "test");
+    }
 }
@@ -1,4 +1,8 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use prost::Message;
 
 use tokio::select;
 use tokio::task::JoinHandle;
@@ -6,15 +10,21 @@ use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 
 use google_cloud_gax::grpc::{Code, Status, Streaming};
-use google_cloud_gax::retry::RetrySetting;
+use google_cloud_gax::retry::{backoff, RetrySetting};
 use google_cloud_googleapis::pubsub::v1::{
     AcknowledgeRequest, ModifyAckDeadlineRequest, PubsubMessage, ReceivedMessage as InternalReceivedMessage,
     StreamingPullResponse,
 };
+use google_cloud_googleapis::rpc::{ErrorInfo, Status as RpcStatus};
 
 use crate::apiv1::default_retry_setting;
 use crate::apiv1::subscriber_client::{create_empty_streaming_pull_request, SubscriberClient};
 
+/// The error returned by [`ReceivedMessage::require_attribute`] when the attribute is not set.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("attribute {0} was not found in the message")]
+pub struct MissingAttribute(pub String);
+
 #[derive(Debug)]
 pub struct ReceivedMessage {
     pub message: PubsubMessage,
@@ -22,6 +32,7 @@ pub struct ReceivedMessage {
     subscription: String,
     subscriber_client: SubscriberClient,
     delivery_attempt: Option<usize>,
+    exactly_once_delivery: bool,
 }
 
 impl ReceivedMessage {
@@ -31,6 +42,7 @@ impl ReceivedMessage {
         message: PubsubMessage,
         ack_id: String,
         delivery_attempt: Option<usize>,
+        exactly_once_delivery: bool,
     ) -> Self {
         Self {
             message,
@@ -38,6 +50,7 @@ impl ReceivedMessage {
             subscription,
             subscriber_client: subc,
             delivery_attempt,
+            exactly_once_delivery,
         }
     }
 
@@ -45,32 +58,46 @@ impl ReceivedMessage {
         self.ack_id.as_str()
     }
 
+    /// ack acknowledges the message.
+    /// On the subscription with exactly-once delivery, the transient failures of the ack are retried
+    /// and `Ok` means the ack is durable. An error means the message may be redelivered.
     pub async fn ack(&self) -> Result<(), Status> {
-        ack(
-            &self.subscriber_client,
-            self.subscription.to_string(),
-            vec![self.ack_id.to_string()],
-        )
+        let ack_ids = vec![self.ack_id.to_string()];
+        if !self.exactly_once_delivery {
+            return ack(&self.subscriber_client, self.subscription.to_string(), ack_ids).await;
+        }
+        invoke_exactly_once(ack_ids, |ack_ids| {
+            ack(&self.subscriber_client, self.subscription.to_string(), ack_ids)
+        })
         .await
     }
 
     pub async fn nack(&self) -> Result<(), Status> {
-        nack(
-            &self.subscriber_client,
-            self.subscription.to_string(),
-            vec![self.ack_id.to_string()],
-        )
-        .await
+        self.modify_ack_deadline(0).await
+    }
+
+    /// nack_with_delay defers the redelivery of the message by the given delay instead of redelivering it immediately.
+    /// The delay is rounded up to seconds and capped at the server maximum of 600 seconds.
+    pub async fn nack_with_delay(&self, delay: Duration) -> Result<(), Status> {
+        self.modify_ack_deadline(redelivery_deadline_seconds(delay)).await
     }
 
+    /// modify_ack_deadline modifies the ack deadline of the message.
+    /// On the subscription with exactly-once delivery, the transient failures are retried as well as `ack`.
     pub async fn modify_ack_deadline(&self, ack_deadline_seconds: i32) -> Result<(), Status> {
-        modify_ack_deadline(
-            &self.subscriber_client,
-            self.subscription.to_string(),
-            vec![self.ack_id.to_string()],
-            ack_deadline_seconds,
-        )
-        .await
+        let ack_ids = vec![self.ack_id.to_string()];
+        let action = |ack_ids: Vec<String>| {
+            modify_ack_deadline(
+                &self.subscriber_client,
+                self.subscription.to_string(),
+                ack_ids,
+                ack_deadline_seconds,
+            )
+        };
+        if !self.exactly_once_delivery {
+            return action(ack_ids).await;
+        }
+        invoke_exactly_once(ack_ids, action).await
     }
 
     /// The approximate number of times that Cloud Pub/Sub has attempted to deliver
@@ -83,6 +110,25 @@ impl ReceivedMessage {
     pub fn delivery_attempt(&self) -> Option<usize> {
         self.delivery_attempt
     }
+
+    /// attribute returns the value of the attribute with the given key, if any.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        attribute(&self.message, key)
+    }
+
+    /// require_attribute returns the value of the attribute with the given key,
+    /// or [`MissingAttribute`] if the message doesn't have it.
+    pub fn require_attribute(&self, key: &str) -> Result<&str, MissingAttribute> {
+        require_attribute(&self.message, key)
+    }
+}
+
+fn attribute<'a>(message: &'a PubsubMessage, key: &str) -> Option<&'a str> {
+    message.attributes.get(key).map(String::as_str)
+}
+
+fn require_attribute<'a>(message: &'a PubsubMessage, key: &str) -> Result<&'a str, MissingAttribute> {
+    attribute(message, key).ok_or_else(|| MissingAttribute(key.to_string()))
 }
 
 #[derive(Debug, Clone)]
@@ -95,6 +141,9 @@ pub struct SubscriberConfig {
     /// the first request on the stream, but it can also be updated on subsequent
     /// requests from client to server. The minimum deadline you can specify is 10
     /// seconds. The maximum deadline you can specify is 600 seconds (10 minutes).
+    /// When no `SubscriberConfig` is given to the subscription, the `ack_deadline_seconds` of the subscription
+    /// clamped to 10..=600 is used. When a `SubscriberConfig` is given, this value (60 by default) is used as is
+    /// and the `ack_deadline_seconds` of the subscription is ignored.
     pub stream_ack_deadline_seconds: i32,
     /// Flow control settings for the maximum number of outstanding messages. When
     /// there are `max_outstanding_messages` or more currently sent to the
@@ -107,6 +156,14 @@ pub struct SubscriberConfig {
     /// `INVALID_ARGUMENT`.
     pub max_outstanding_messages: i64,
     pub max_outstanding_bytes: i64,
+    /// Backoff between the attempts to re-establish the streaming pull after a retryable error or the close by the server.
+    /// The backoff is reset when the stream has been alive longer than `max_delay`,
+    /// and the subscriber stops when `take` or `max_elapsed` is exhausted.
+    pub reconnect_setting: RetrySetting,
+    /// Retry the transient failures of `ack` and `modify_ack_deadline` of the received messages,
+    /// and return the permanent ones, for the subscription with exactly-once delivery.
+    /// It is also enabled when the server reports that the subscription has exactly-once delivery.
+    pub enable_exactly_once_delivery: bool,
 }
 
 impl Default for SubscriberConfig {
@@ -117,10 +174,25 @@ impl Default for SubscriberConfig {
             stream_ack_deadline_seconds: 60,
             max_outstanding_messages: 50,
             max_outstanding_bytes: 1000 * 1000 * 1000,
+            reconnect_setting: default_reconnect_setting(),
+            enable_exactly_once_delivery: false,
         }
     }
 }
 
+/// default_reconnect_setting is the backoff of the reconnect of the streaming pull,
+/// which starts from 100ms and grows up to 60s with jitter without limit of the attempts.
+pub fn default_reconnect_setting() -> RetrySetting {
+    RetrySetting {
+        from_millis: 2,
+        factor: 50,
+        max_delay: Some(Duration::from_secs(60)),
+        take: usize::MAX,
+        jitter: true,
+        ..Default::default()
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Subscriber {
     pinger: Option<JoinHandle<()>>,
@@ -162,6 +234,8 @@ impl Subscriber {
                 Some(v) => v.codes.clone(),
                 None => default_retry_setting().codes,
             };
+            let reconnect = &config.reconnect_setting;
+            let mut delays = backoff(reconnect);
             loop {
                 let mut request = create_empty_streaming_pull_request();
                 request.subscription = subscription.to_string();
@@ -181,6 +255,9 @@ impl Subscriber {
                             break;
                         } else if retryable_codes.contains(&e.code()) {
                             tracing::warn!("failed to start streaming: will reconnect {:?} : {}", e, subscription);
+                            if !Self::wait_reconnect(&cancel_receiver, &mut delays, &subscription).await {
+                                break;
+                            }
                             continue;
                         } else {
                             tracing::error!("failed to start streaming: will stop {:?} : {}", e, subscription);
@@ -188,26 +265,34 @@ impl Subscriber {
                         }
                     }
                 };
-                match Self::recv(
+                let started_at = Instant::now();
+                let result = Self::recv(
                     client.clone(),
                     stream,
                     subscription.as_str(),
                     cancel_receiver.clone(),
                     queue.clone(),
+                    config.enable_exactly_once_delivery,
                 )
-                .await
-                {
-                    Ok(_) => break,
+                .await;
+                match result {
+                    Ok(_) if cancel_receiver.is_cancelled() => break,
+                    // The server may close the stream at any time, e.g. on the rebalancing of the backends.
+                    Ok(_) => tracing::warn!("streaming closed by the server: will reconnect : {}", subscription),
+                    Err(e) if retryable_codes.contains(&e.code()) => {
+                        tracing::warn!("streaming terminated: will reconnect {:?} : {}", e, subscription)
+                    }
                     Err(e) => {
-                        if retryable_codes.contains(&e.code()) {
-                            tracing::trace!("reconnect - '{:?}' : {} ", e, subscription);
-                            continue;
-                        } else {
-                            tracing::error!("terminated subscriber streaming with error {:?} : {}", e, subscription);
-                            break;
-                        }
+                        tracing::error!("terminated subscriber streaming with error {:?} : {}", e, subscription);
+                        break;
                     }
                 }
+                if reconnect.max_delay.is_some_and(|v| started_at.elapsed() > v) {
+                    delays = backoff(reconnect);
+                }
+                if !Self::wait_reconnect(&cancel_receiver, &mut delays, &subscription).await {
+                    break;
+                }
             }
             // streaming request is closed when the ping_sender closed.
             tracing::trace!("stop subscriber in streaming: {}", subscription);
@@ -218,12 +303,33 @@ impl Subscriber {
         }
     }
 
+    /// Waits for the backoff delay before reconnecting. Returns false if cancelled or the backoff is exhausted.
+    async fn wait_reconnect(
+        cancel: &CancellationToken,
+        delays: &mut impl Iterator<Item = Duration>,
+        subscription: &str,
+    ) -> bool {
+        let wait = match delays.next() {
+            Some(wait) => wait,
+            None => {
+                tracing::error!("reconnect attempts exhausted: will stop : {}", subscription);
+                return false;
+            }
+        };
+        tracing::info!("reconnect streaming after {:?} : {}", wait, subscription);
+        select! {
+            _ = cancel.cancelled() => false,
+            _ = sleep(wait) => true,
+        }
+    }
+
     async fn recv(
         client: SubscriberClient,
         mut stream: Streaming<StreamingPullResponse>,
         subscription: &str,
         cancel: CancellationToken,
         queue: async_channel::Sender<ReceivedMessage>,
+        mut exactly_once_delivery: bool,
     ) -> Result<(), Status> {
         tracing::trace!("start streaming: {}", subscription);
         loop {
@@ -238,7 +344,18 @@ impl Subscriber {
                         Some(m) => m,
                         None => return Ok(())
                     };
-                    let _ = handle_message(&cancel, &queue, &client, subscription, message.received_messages).await;
+                    if let Some(properties) = &message.subscription_properties {
+                        exactly_once_delivery |= properties.exactly_once_delivery_enabled;
+                    }
+                    let _ = handle_message(
+                        &cancel,
+                        &queue,
+                        &client,
+                        subscription,
+                        message.received_messages,
+                        exactly_once_delivery,
+                    )
+                    .await;
                 }
             }
         }
@@ -260,6 +377,7 @@ async fn handle_message(
     client: &SubscriberClient,
     subscription: &str,
     messages: Vec<InternalReceivedMessage>,
+    exactly_once_delivery: bool,
 ) -> usize {
     let mut nack_targets = vec![];
     for received_message in messages {
@@ -272,6 +390,7 @@ async fn handle_message(
                 message,
                 received_message.ack_id.clone(),
                 (received_message.delivery_attempt > 0).then_some(received_message.delivery_attempt as usize),
+                exactly_once_delivery,
             );
             let should_nack = select! {
                 result = queue.send(msg) => result.is_err(),
@@ -315,10 +434,71 @@ async fn modify_ack_deadline(
         .map(|e| e.into_inner())
 }
 
+/// The maximum ack deadline accepted by the server.
+const MAX_ACK_DEADLINE_SECONDS: i32 = 600;
+
+fn redelivery_deadline_seconds(delay: Duration) -> i32 {
+    let seconds = delay.as_secs().saturating_add(u64::from(delay.subsec_nanos() > 0));
+    seconds.min(MAX_ACK_DEADLINE_SECONDS as u64) as i32
+}
+
 async fn nack(subscriber_client: &SubscriberClient, subscription: String, ack_ids: Vec<String>) -> Result<(), Status> {
     modify_ack_deadline(subscriber_client, subscription, ack_ids, 0).await
 }
 
+const ERROR_INFO_TYPE: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+const TRANSIENT_FAILURE_PREFIX: &str = "TRANSIENT_";
+const PERMANENT_FAILURE_INVALID_ACK_ID: &str = "PERMANENT_FAILURE_INVALID_ACK_ID";
+
+/// ack_failures returns the reason of the failure for each ack id in the ErrorInfo of the status.
+fn ack_failures(status: &Status) -> HashMap<String, String> {
+    RpcStatus::decode(status.details())
+        .map(|v| v.details)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|d| d.type_url == ERROR_INFO_TYPE)
+        .filter_map(|d| ErrorInfo::decode(d.value.as_slice()).ok())
+        .flat_map(|v| v.metadata)
+        .collect()
+}
+
+/// invoke_exactly_once retries the ack ids which failed transiently until all of them succeed
+/// or the retry is exhausted. The permanent failure is returned immediately.
+async fn invoke_exactly_once<F, Fut>(mut ack_ids: Vec<String>, action: F) -> Result<(), Status>
+where
+    F: Fn(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<(), Status>>,
+{
+    let setting = default_retry_setting();
+    let mut delays = backoff(&setting);
+    loop {
+        let status = match action(ack_ids.clone()).await {
+            Ok(_) => return Ok(()),
+            Err(status) => status,
+        };
+        let failures = ack_failures(&status);
+        if failures.is_empty() {
+            return Err(status);
+        }
+        if let Some((ack_id, reason)) = failures.iter().find(|(_, v)| !v.starts_with(TRANSIENT_FAILURE_PREFIX)) {
+            return Err(match reason.as_str() {
+                PERMANENT_FAILURE_INVALID_ACK_ID => {
+                    Status::new(Code::InvalidArgument, format!("invalid ack id: {ack_id}"))
+                }
+                _ => status,
+            });
+        }
+        ack_ids.retain(|v| failures.contains_key(v));
+        match delays.next() {
+            Some(delay) => {
+                tracing::debug!("retry the transient failures of exactly-once delivery: {:?}", failures);
+                sleep(delay).await
+            }
+            None => return Err(status),
+        }
+    }
+}
+
 pub(crate) async fn ack(
     subscriber_client: &SubscriberClient,
     subscription: String,
@@ -333,16 +513,27 @@ pub(crate) async fn ack(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use prost::Message;
     use serial_test::serial;
     use tokio_util::sync::CancellationToken;
 
     use google_cloud_gax::conn::{ConnectionOptions, Environment};
+    use google_cloud_gax::grpc::{Code, Status};
+    use google_cloud_gax::retry::backoff;
     use google_cloud_googleapis::pubsub::v1::{PublishRequest, PubsubMessage, PullRequest};
+    use google_cloud_googleapis::rpc::{ErrorInfo, Status as RpcStatus};
 
     use crate::apiv1::conn_pool::ConnectionManager;
     use crate::apiv1::publisher_client::PublisherClient;
     use crate::apiv1::subscriber_client::SubscriberClient;
-    use crate::subscriber::handle_message;
+    use crate::subscriber::{
+        attribute, default_reconnect_setting, handle_message, invoke_exactly_once, redelivery_deadline_seconds,
+        require_attribute, ERROR_INFO_TYPE, PERMANENT_FAILURE_INVALID_ACK_ID,
+    };
 
     #[ctor::ctor]
     fn init() {
@@ -395,7 +586,103 @@ mod tests {
         let messages = response.received_messages;
         let (queue, _) = async_channel::unbounded();
         queue.close();
-        let nack_size = handle_message(&CancellationToken::new(), &queue, &subc, subscription, messages).await;
+        let nack_size = handle_message(&CancellationToken::new(), &queue, &subc, subscription, messages, false).await;
         assert_eq!(1, nack_size);
     }
+
+    #[test]
+    fn test_default_reconnect_setting() {
+        let setting = default_reconnect_setting().with_jitter(false);
+        let delays: Vec<_> = backoff(&setting).take(4).map(|v| v.as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800]);
+        let delay = backoff(&setting).nth(20).unwrap();
+        assert_eq!(delay, Duration::from_secs(60));
+
+        for delay in backoff(&default_reconnect_setting()).take(4) {
+            assert!(
+                delay >= Duration::from_millis(50) && delay <= Duration::from_millis(1200),
+                "{delay:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_redelivery_deadline_seconds() {
+        assert_eq!(redelivery_deadline_seconds(Duration::ZERO), 0);
+        assert_eq!(redelivery_deadline_seconds(Duration::from_millis(1)), 1);
+        assert_eq!(redelivery_deadline_seconds(Duration::from_secs(30)), 30);
+        assert_eq!(redelivery_deadline_seconds(Duration::from_millis(30_500)), 31);
+        assert_eq!(redelivery_deadline_seconds(Duration::from_secs(601)), 600);
+        assert_eq!(redelivery_deadline_seconds(Duration::MAX), 600);
+    }
+
+    #[test]
+    fn test_attribute() {
+        let message = PubsubMessage {
+            attributes: [("event".to_string(), "created".to_string())].into_iter().collect(),
+            ..Default::default()
+        };
+        assert_eq!(attribute(&message, "event"), Some("created"));
+        assert_eq!(attribute(&message, "source"), None);
+        assert_eq!(require_attribute(&message, "event").unwrap(), "created");
+
+        let err = require_attribute(&message, "source").unwrap_err();
+        assert_eq!(err.0, "source");
+        assert_eq!(err.to_string(), "attribute source was not found in the message");
+    }
+
+    fn ack_failure_status(failures: &[(&str, &str)]) -> Status {
+        let info = ErrorInfo {
+            reason: "EXACTLY_ONCE_ACKID_FAILURE".to_string(),
+            domain: "pubsub.googleapis.com".to_string(),
+            metadata: failures.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        };
+        let status = RpcStatus {
+            code: Code::FailedPrecondition as i32,
+            message: "ack failed".to_string(),
+            details: vec![prost_types::Any {
+                type_url: ERROR_INFO_TYPE.to_string(),
+                value: info.encode_to_vec(),
+            }],
+        };
+        Status::with_details(Code::FailedPrecondition, "ack failed", status.encode_to_vec().into())
+    }
+
+    #[tokio::test]
+    async fn test_invoke_exactly_once() {
+        // The transient failure is retried only for the failed ack ids.
+        let calls = Mutex::new(vec![]);
+        let result = invoke_exactly_once(vec!["a".to_string(), "b".to_string()], |ack_ids| {
+            let mut calls = calls.lock().unwrap();
+            calls.push(ack_ids);
+            let result = match calls.len() {
+                1 => Err(ack_failure_status(&[("b", "TRANSIENT_FAILURE_UNORDERED_ACK_ID")])),
+                _ => Ok(()),
+            };
+            async move { result }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![vec!["a".to_string(), "b".to_string()], vec!["b".to_string()]]
+        );
+
+        // The permanent failure is returned without retry.
+        let count = AtomicUsize::new(0);
+        let result = invoke_exactly_once(vec!["a".to_string()], |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+            async { Err(ack_failure_status(&[("a", PERMANENT_FAILURE_INVALID_ACK_ID)])) }
+        })
+        .await;
+        assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // The error without ErrorInfo is returned as is.
+        let result = invoke_exactly_once(vec!["a".to_string()], |_| async {
+            Err(Status::new(Code::PermissionDenied, "denied"))
+        })
+        .await;
+        assert_eq!(result.unwrap_err().code(), Code::PermissionDenied);
+    }
 }
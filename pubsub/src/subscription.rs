@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use std::time::{Duration, SystemTime};
 
 use prost_types::{DurationError, FieldMask};
+use tokio::sync::Semaphore;
 use tokio_util::sync::CancellationToken;
 
 use google_cloud_gax::grpc::codegen::tokio_stream::Stream;
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::RetrySetting;
+use google_cloud_googleapis::iam::v1::{GetIamPolicyRequest, Policy, SetIamPolicyRequest, TestIamPermissionsRequest};
 use google_cloud_googleapis::pubsub::v1::seek_request::Target;
 use google_cloud_googleapis::pubsub::v1::subscription::AnalyticsHubSubscriptionInfo;
 use google_cloud_googleapis::pubsub::v1::{
@@ -19,12 +22,16 @@ use google_cloud_googleapis::pubsub::v1::{
 };
 
 use crate::apiv1::subscriber_client::SubscriberClient;
-
+use crate::client::Lifecycle;
 use crate::subscriber::{ack, ReceivedMessage, Subscriber, SubscriberConfig};
 
 #[derive(Debug, Clone, Default)]
 pub struct SubscriptionConfig {
     pub push_config: Option<PushConfig>,
+    /// The maximum time in seconds between 10 and 600 to acknowledge a message before it is redelivered.
+    /// The default of 10 seconds is used if 0.
+    /// The subscriber created without `SubscriberConfig` uses it as the lease of the streaming pull.
+    /// The subscriber created with `SubscriberConfig` uses `stream_ack_deadline_seconds` of the config instead.
     pub ack_deadline_seconds: i32,
     pub retain_acked_messages: bool,
     pub message_retention_duration: Option<Duration>,
@@ -36,6 +43,8 @@ pub struct SubscriptionConfig {
     pub retry_policy: Option<RetryPolicy>,
     pub detached: bool,
     pub topic_message_retention_duration: Option<Duration>,
+    /// Delivers each message only once while its ack deadline is not expired and it is not acknowledged.
+    /// The subscriber created without `SubscriberConfig` retries the transient failures of the acknowledgement.
     pub enable_exactly_once_delivery: bool,
     pub bigquery_config: Option<BigQueryConfig>,
     pub state: i32,
@@ -81,6 +90,8 @@ pub struct SubscriptionConfigToUpdate {
     pub expiration_policy: Option<ExpirationPolicy>,
     pub dead_letter_policy: Option<DeadLetterPolicy>,
     pub retry_policy: Option<RetryPolicy>,
+    pub enable_exactly_once_delivery: Option<bool>,
+    pub cloud_storage_config: Option<CloudStorageConfig>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -110,6 +121,11 @@ pub struct ReceiveConfig {
     pub worker_count: usize,
     pub channel_capacity: Option<usize>,
     pub subscriber_config: Option<SubscriberConfig>,
+    /// The maximum number of handlers running concurrently across the workers.
+    /// A worker waits for a permit before running the handler, so that the bounded channel
+    /// set by `channel_capacity` stops pulling more messages than can be processed.
+    /// The handlers are bounded only by `worker_count` if `None`.
+    pub max_concurrency: Option<usize>,
 }
 
 impl Default for ReceiveConfig {
@@ -118,6 +134,7 @@ impl Default for ReceiveConfig {
             worker_count: 10,
             subscriber_config: None,
             channel_capacity: None,
+            max_concurrency: None,
         }
     }
 }
@@ -207,11 +224,24 @@ impl Stream for MessageStream {
 pub struct Subscription {
     fqsn: String,
     subc: SubscriberClient,
+    lifecycle: Lifecycle,
+    /// The exactly once delivery setting last seen, which `pull` uses to ack the messages.
+    exactly_once_delivery: Arc<Mutex<Option<bool>>>,
 }
 
 impl Subscription {
     pub(crate) fn new(fqsn: String, subc: SubscriberClient) -> Self {
-        Self { fqsn, subc }
+        Self {
+            fqsn,
+            subc,
+            lifecycle: Lifecycle::default(),
+            exactly_once_delivery: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub(crate) fn with_lifecycle(mut self, lifecycle: Lifecycle) -> Self {
+        self.lifecycle = lifecycle;
+        self
     }
 
     pub(crate) fn streaming_pool_size(&self) -> usize {
@@ -254,8 +284,13 @@ impl Subscription {
         self.subc.clone()
     }
 
+    fn cache_exactly_once_delivery(&self, value: bool) {
+        *self.exactly_once_delivery.lock().unwrap() = Some(value);
+    }
+
     /// create creates the subscription.
     pub async fn create(&self, fqtn: &str, cfg: SubscriptionConfig, retry: Option<RetrySetting>) -> Result<(), Status> {
+        let exactly_once_delivery = cfg.enable_exactly_once_delivery;
         self.subc
             .create_subscription(
                 InternalSubscription {
@@ -290,7 +325,7 @@ impl Subscription {
                 retry,
             )
             .await
-            .map(|_v| ())
+            .map(|_v| self.cache_exactly_once_delivery(exactly_once_delivery))
     }
 
     /// delete deletes the subscription.
@@ -325,6 +360,7 @@ impl Subscription {
         };
         self.subc.get_subscription(req, retry).await.map(|v| {
             let inner = v.into_inner();
+            self.cache_exactly_once_delivery(inner.enable_exactly_once_delivery);
             (inner.topic.to_string(), inner.into())
         })
     }
@@ -374,10 +410,22 @@ impl Subscription {
             config.labels = v;
             paths.push("labels".to_string());
         }
+        if updating.dead_letter_policy.is_some() {
+            config.dead_letter_policy = updating.dead_letter_policy;
+            paths.push("dead_letter_policy".to_string());
+        }
         if updating.retry_policy.is_some() {
             config.retry_policy = updating.retry_policy;
             paths.push("retry_policy".to_string());
         }
+        if let Some(v) = updating.enable_exactly_once_delivery {
+            config.enable_exactly_once_delivery = v;
+            paths.push("enable_exactly_once_delivery".to_string());
+        }
+        if updating.cloud_storage_config.is_some() {
+            config.cloud_storage_config = updating.cloud_storage_config;
+            paths.push("cloud_storage_config".to_string());
+        }
 
         let update_req = UpdateSubscriptionRequest {
             subscription: Some(config),
@@ -385,12 +433,15 @@ impl Subscription {
         };
         self.subc.update_subscription(update_req, retry).await.map(|v| {
             let inner = v.into_inner();
+            self.cache_exactly_once_delivery(inner.enable_exactly_once_delivery);
             (inner.topic.to_string(), inner.into())
         })
     }
 
     /// pull get message synchronously.
     /// It blocks until at least one message is available.
+    /// The messages are acked with the exactly once delivery if it is enabled on the subscription.
+    /// The setting is fetched on the first pull unless it is known from `create`, `config` or `update`.
     pub async fn pull(&self, max_messages: i32, retry: Option<RetrySetting>) -> Result<Vec<ReceivedMessage>, Status> {
         #[allow(deprecated)]
         let req = PullRequest {
@@ -398,6 +449,11 @@ impl Subscription {
             return_immediately: false,
             max_messages,
         };
+        let cached = *self.exactly_once_delivery.lock().unwrap();
+        let exactly_once_delivery = match cached {
+            Some(v) => v,
+            None => self.config(retry.clone()).await?.1.enable_exactly_once_delivery,
+        };
         let messages = self.subc.pull(req, retry).await?.into_inner().received_messages;
         Ok(messages
             .into_iter()
@@ -409,6 +465,7 @@ impl Subscription {
                     m.message.unwrap(),
                     m.ack_id,
                     (m.delivery_attempt > 0).then_some(m.delivery_attempt as usize),
+                    exactly_once_delivery,
                 )
             })
             .collect())
@@ -458,7 +515,7 @@ impl Subscription {
     pub async fn subscribe(&self, opt: Option<SubscribeConfig>) -> Result<MessageStream, Status> {
         let opt = opt.unwrap_or_default();
         let (tx, rx) = create_channel(opt.channel_capacity);
-        let cancel = CancellationToken::new();
+        let cancel = self.lifecycle.cancellable().child_token();
         let sub_opt = self.unwrap_subscribe_config(opt.subscriber_config).await?;
 
         // spawn a separate subscriber task for each connection in the pool
@@ -488,6 +545,14 @@ impl Subscription {
     /// receive calls f with the outstanding messages from the subscription.
     /// It blocks until cancellation token is cancelled, or the service returns a non-retryable error.
     /// The standard way to terminate a receive is to use CancellationToken.
+    /// `Client::shutdown` also cancels the CancellationToken and waits for the receive to finish,
+    /// that is the messages already received are handled.
+    ///
+    /// When the subscription enables message ordering, the messages of each of the `worker_count` streams
+    /// are handled one by one by the worker of the stream, since the server delivers an ordering key in one stream.
+    /// A handler that doesn't return for a key only blocks the keys delivered in the same stream,
+    /// and the other streams keep delivering their keys.
+    /// Nack the message instead of waiting in the handler so that the other keys of the stream are not delayed.
     pub async fn receive<F>(
         &self,
         f: impl Fn(ReceivedMessage, CancellationToken) -> F + Send + 'static + Sync + Clone,
@@ -497,6 +562,7 @@ impl Subscription {
     where
         F: Future<Output = ()> + Send + 'static,
     {
+        let running = self.lifecycle.track().await;
         let op = config.unwrap_or_default();
         let mut receivers = Vec::with_capacity(op.worker_count);
         let mut senders = Vec::with_capacity(receivers.len());
@@ -529,20 +595,30 @@ impl Subscription {
             })
             .collect();
 
+        let semaphore = op.max_concurrency.map(|v| Arc::new(Semaphore::new(v)));
         let mut message_receivers = Vec::with_capacity(receivers.len());
         for receiver in receivers {
             let f_clone = f.clone();
             let cancel_clone = cancel.clone();
             let name = self.fqsn.clone();
+            let semaphore = semaphore.clone();
             message_receivers.push(tokio::spawn(async move {
                 while let Ok(message) = receiver.recv().await {
-                    f_clone(message, cancel_clone.clone()).await;
+                    // the permit is acquired after receiving so that the messages with other ordering keys are not blocked by an idle worker.
+                    let _permit = match &semaphore {
+                        Some(semaphore) => semaphore.acquire().await.ok(),
+                        None => None,
+                    };
+                    Lifecycle::handle(f_clone(message, cancel_clone.clone())).await;
                 }
                 // queue is closed by subscriber when the cancellation token is cancelled
                 tracing::trace!("stop message receiver : {}", name);
             }));
         }
-        cancel.cancelled().await;
+        tokio::select! {
+            _ = cancel.cancelled() => {},
+            _ = self.lifecycle.cancellable().cancelled() => cancel.cancel(),
+        }
 
         // wait for all the threads finish.
         for mut subscriber in subscribers {
@@ -553,6 +629,7 @@ impl Subscription {
         for mr in message_receivers {
             let _ = mr.await;
         }
+        drop(running);
         Ok(())
     }
 
@@ -678,6 +755,42 @@ impl Subscription {
         Ok(())
     }
 
+    /// get_iam_policy gets the access control policy of the subscription.
+    pub async fn get_iam_policy(&self, retry: Option<RetrySetting>) -> Result<Policy, Status> {
+        let req = GetIamPolicyRequest {
+            resource: self.fqsn.to_string(),
+            options: None,
+        };
+        self.subc.iam().get_iam_policy(req, retry).await.map(|v| v.into_inner())
+    }
+
+    /// set_iam_policy replaces the access control policy of the subscription.
+    pub async fn set_iam_policy(&self, policy: Policy, retry: Option<RetrySetting>) -> Result<Policy, Status> {
+        let req = SetIamPolicyRequest {
+            resource: self.fqsn.to_string(),
+            policy: Some(policy),
+            update_mask: None,
+        };
+        self.subc.iam().set_iam_policy(req, retry).await.map(|v| v.into_inner())
+    }
+
+    /// test_iam_permissions returns the subset of the permissions that the caller has on the subscription.
+    pub async fn test_iam_permissions(
+        &self,
+        permissions: Vec<String>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Vec<String>, Status> {
+        let req = TestIamPermissionsRequest {
+            resource: self.fqsn.to_string(),
+            permissions,
+        };
+        self.subc
+            .iam()
+            .test_iam_permissions(req, retry)
+            .await
+            .map(|v| v.into_inner().permissions)
+    }
+
     async fn unwrap_subscribe_config(&self, cfg: Option<SubscriberConfig>) -> Result<SubscriberConfig, Status> {
         if let Some(cfg) = cfg {
             return Ok(cfg);
@@ -689,6 +802,7 @@ impl Subscription {
         };
         if cfg.1.enable_exactly_once_delivery {
             default_cfg.max_outstanding_messages = 5;
+            default_cfg.enable_exactly_once_delivery = true;
         }
         Ok(default_cfg)
     }
@@ -717,6 +831,7 @@ mod tests {
     use uuid::Uuid;
 
     use google_cloud_gax::conn::{ConnectionOptions, Environment};
+    use google_cloud_googleapis::iam::v1::Binding;
     use google_cloud_googleapis::pubsub::v1::{PublishRequest, PubsubMessage};
 
     use crate::apiv1::conn_pool::ConnectionManager;
@@ -799,11 +914,15 @@ mod tests {
 
         let updating = SubscriptionConfigToUpdate {
             ack_deadline_seconds: Some(100),
+            message_retention_duration: Some(Duration::from_secs(60 * 60)),
+            labels: Some(HashMap::from([("env".to_string(), "test".to_string())])),
             ..Default::default()
         };
         let new_config = subscription.update(updating, None).await.unwrap();
         assert_eq!(new_config.0, topic_name);
         assert_eq!(new_config.1.ack_deadline_seconds, 100);
+        assert_eq!(new_config.1.message_retention_duration, Some(Duration::from_secs(60 * 60)));
+        assert_eq!(new_config.1.labels.get("env").map(String::as_str), Some("test"));
 
         let receiver_ctx = CancellationToken::new();
         let cancel_receiver = receiver_ctx.clone();
@@ -829,7 +948,17 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     #[serial]
     async fn test_pull() {
-        let subscription = create_subscription(false).await;
+        test_pull_messages(false).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_pull_exactly_once() {
+        test_pull_messages(true).await;
+    }
+
+    async fn test_pull_messages(enable_exactly_once_delivery: bool) {
+        let subscription = create_subscription(enable_exactly_once_delivery).await;
         let base = PubsubMessage {
             data: "test_message".into(),
             ..Default::default()
@@ -843,6 +972,54 @@ mod tests {
         subscription.delete(None).await.unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_subscriber_config_from_subscription() {
+        let subscription = create_subscription(true).await;
+        let updating = SubscriptionConfigToUpdate {
+            ack_deadline_seconds: Some(120),
+            ..Default::default()
+        };
+        let (_, config) = subscription.update(updating, None).await.unwrap();
+        assert_eq!(config.ack_deadline_seconds, 120);
+        assert!(config.enable_exactly_once_delivery);
+
+        let config = subscription.unwrap_subscribe_config(None).await.unwrap();
+        assert_eq!(config.stream_ack_deadline_seconds, 120);
+        assert!(config.enable_exactly_once_delivery);
+        subscription.delete(None).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_iam_policy() {
+        let subscription = create_subscription(false).await;
+        let mut policy = subscription.get_iam_policy(None).await.unwrap();
+        policy.bindings.push(Binding {
+            role: "roles/pubsub.subscriber".to_string(),
+            members: vec!["allAuthenticatedUsers".to_string()],
+            condition: None,
+        });
+        subscription.set_iam_policy(policy, None).await.unwrap();
+
+        let policy = subscription.get_iam_policy(None).await.unwrap();
+        let binding = policy
+            .bindings
+            .iter()
+            .find(|v| v.role == "roles/pubsub.subscriber")
+            .unwrap();
+        assert_eq!(binding.members, vec!["allAuthenticatedUsers".to_string()]);
+
+        let permissions = vec!["pubsub.subscriptions.consume".to_string()];
+        let granted = subscription
+            .test_iam_permissions(permissions.clone(), None)
+            .await
+            .unwrap();
+        assert!(granted.iter().all(|v| permissions.contains(v)));
+
+        subscription.delete(None).await.unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_subscription_exactly_once() {
@@ -906,6 +1083,56 @@ mod tests {
         assert_eq!(v.load(SeqCst), msg_size);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_receive_max_concurrency() {
+        let msg_size = 10;
+        let msgs: Vec<PubsubMessage> = (0..msg_size)
+            .map(|_v| PubsubMessage {
+                data: "test".into(),
+                ..Default::default()
+            })
+            .collect();
+        let subscription = create_subscription(false).await;
+        let cancellation_token = CancellationToken::new();
+        let cancel_receiver = cancellation_token.clone();
+        let received = Arc::new(AtomicU32::new(0));
+        let running = Arc::new(AtomicU32::new(0));
+        let max_running = Arc::new(AtomicU32::new(0));
+        let (received2, running2, max_running2) = (received.clone(), running.clone(), max_running.clone());
+        let opt = Some(ReceiveConfig {
+            max_concurrency: Some(2),
+            channel_capacity: Some(1),
+            ..Default::default()
+        });
+        let handle = tokio::spawn(async move {
+            let _ = subscription
+                .receive(
+                    move |message, _ctx| {
+                        let (received, running, max_running) =
+                            (received2.clone(), running2.clone(), max_running2.clone());
+                        async move {
+                            let current = running.fetch_add(1, SeqCst) + 1;
+                            max_running.fetch_max(current, SeqCst);
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                            running.fetch_sub(1, SeqCst);
+                            received.fetch_add(1, SeqCst);
+                            let _ = message.ack().await;
+                        }
+                    },
+                    cancel_receiver,
+                    opt,
+                )
+                .await;
+        });
+        publish(Some(msgs)).await;
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        cancellation_token.cancel();
+        let _ = handle.await;
+        assert_eq!(received.load(SeqCst), msg_size);
+        assert!(max_running.load(SeqCst) <= 2);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[serial]
     async fn test_multi_subscriber_multi_subscription() {
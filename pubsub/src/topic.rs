@@ -5,6 +5,7 @@ use prost_types::DurationError;
 
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::RetrySetting;
+use google_cloud_googleapis::iam::v1::{GetIamPolicyRequest, Policy, SetIamPolicyRequest, TestIamPermissionsRequest};
 use google_cloud_googleapis::pubsub::v1::{
     DeleteTopicRequest, GetTopicRequest, IngestionDataSourceSettings, ListTopicSubscriptionsRequest,
     MessageStoragePolicy, SchemaSettings, Topic as InternalTopic,
@@ -12,6 +13,7 @@ use google_cloud_googleapis::pubsub::v1::{
 
 use crate::apiv1::publisher_client::PublisherClient;
 use crate::apiv1::subscriber_client::SubscriberClient;
+use crate::client::Lifecycle;
 use crate::publisher::{Publisher, PublisherConfig};
 use crate::subscription::Subscription;
 
@@ -48,11 +50,22 @@ pub struct Topic {
     fqtn: String,
     pubc: PublisherClient,
     subc: SubscriberClient,
+    lifecycle: Lifecycle,
 }
 
 impl Topic {
     pub(crate) fn new(fqtn: String, pubc: PublisherClient, subc: SubscriberClient) -> Self {
-        Self { fqtn, pubc, subc }
+        Self {
+            fqtn,
+            pubc,
+            subc,
+            lifecycle: Lifecycle::default(),
+        }
+    }
+
+    pub(crate) fn with_lifecycle(mut self, lifecycle: Lifecycle) -> Self {
+        self.lifecycle = lifecycle;
+        self
     }
 
     /// id returns the unique identifier of the topic within its project.
@@ -67,8 +80,12 @@ impl Topic {
         self.fqtn.as_str()
     }
 
+    /// new_publisher creates a publisher of the topic.
+    /// The publisher is shut down by `Client::shutdown` as well as by `Publisher::shutdown`.
     pub fn new_publisher(&self, config: Option<PublisherConfig>) -> Publisher {
-        Publisher::new(self.fqtn.clone(), self.pubc.clone(), config)
+        let publisher = Publisher::new(self.fqtn.clone(), self.pubc.clone(), config);
+        self.lifecycle.register(&publisher);
+        publisher
     }
 
     /// create creates the topic.
@@ -129,29 +146,67 @@ impl Topic {
         };
         self.pubc.list_topic_subscriptions(req, retry).await.map(|v| {
             v.into_iter()
-                .map(|sub_name| Subscription::new(sub_name, self.subc.clone()))
+                .map(|sub_name| Subscription::new(sub_name, self.subc.clone()).with_lifecycle(self.lifecycle.clone()))
                 .collect()
         })
     }
+
+    /// get_iam_policy gets the access control policy of the topic.
+    pub async fn get_iam_policy(&self, retry: Option<RetrySetting>) -> Result<Policy, Status> {
+        let req = GetIamPolicyRequest {
+            resource: self.fqtn.to_string(),
+            options: None,
+        };
+        self.pubc.iam().get_iam_policy(req, retry).await.map(|v| v.into_inner())
+    }
+
+    /// set_iam_policy replaces the access control policy of the topic.
+    pub async fn set_iam_policy(&self, policy: Policy, retry: Option<RetrySetting>) -> Result<Policy, Status> {
+        let req = SetIamPolicyRequest {
+            resource: self.fqtn.to_string(),
+            policy: Some(policy),
+            update_mask: None,
+        };
+        self.pubc.iam().set_iam_policy(req, retry).await.map(|v| v.into_inner())
+    }
+
+    /// test_iam_permissions returns the subset of the permissions that the caller has on the topic.
+    pub async fn test_iam_permissions(
+        &self,
+        permissions: Vec<String>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Vec<String>, Status> {
+        let req = TestIamPermissionsRequest {
+            resource: self.fqtn.to_string(),
+            permissions,
+        };
+        self.pubc
+            .iam()
+            .test_iam_permissions(req, retry)
+            .await
+            .map(|v| v.into_inner().permissions)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
 
+    use prost::Message;
     use serial_test::serial;
     use tokio::task::JoinHandle;
-    use tokio::time::sleep;
+    use tokio::time::{sleep, timeout, Instant};
     use uuid::Uuid;
 
     use google_cloud_gax::conn::{ConnectionOptions, Environment};
     use google_cloud_gax::grpc::{Code, Status};
+    use google_cloud_googleapis::iam::v1::Binding;
     use google_cloud_googleapis::pubsub::v1::PubsubMessage;
 
     use crate::apiv1::conn_pool::ConnectionManager;
     use crate::apiv1::publisher_client::PublisherClient;
     use crate::apiv1::subscriber_client::SubscriberClient;
-    use crate::publisher::{Publisher, PublisherConfig};
+    use crate::publisher::{PublishError, Publisher, PublisherConfig};
     use crate::topic::Topic;
 
     #[ctor::ctor]
@@ -184,7 +239,7 @@ mod tests {
         topic
     }
 
-    async fn publish(publisher: Publisher) -> Vec<JoinHandle<Result<String, Status>>> {
+    async fn publish(publisher: Publisher) -> Vec<JoinHandle<Result<String, PublishError>>> {
         (0..10)
             .map(|_i| {
                 let publisher = publisher.clone();
@@ -216,6 +271,8 @@ mod tests {
         sleep(Duration::from_secs(1)).await;
         let mut publisher = publisher;
         publisher.shutdown().await;
+        // Shutdown is idempotent.
+        publisher.shutdown().await;
 
         // Confirm flush bundle.
         for task in tasks {
@@ -236,7 +293,7 @@ mod tests {
             vec![publisher.publish(PubsubMessage::default()).await]
         };
         for result in results {
-            let err = result.get().await.unwrap_err();
+            let err = Status::from(result.get().await.unwrap_err());
             assert_eq!(Code::Cancelled, err.code());
             assert_eq!("closed", err.message());
         }
@@ -272,6 +329,34 @@ mod tests {
         topic.delete(None).await.unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_iam_policy() {
+        let topic = create_topic().await;
+        let mut policy = topic.get_iam_policy(None).await.unwrap();
+        policy.bindings.push(Binding {
+            role: "roles/pubsub.publisher".to_string(),
+            members: vec!["allUsers".to_string()],
+            condition: None,
+        });
+        let policy = topic.set_iam_policy(policy, None).await.unwrap();
+        assert!(policy.bindings.iter().any(|v| v.role == "roles/pubsub.publisher"));
+
+        let policy = topic.get_iam_policy(None).await.unwrap();
+        let binding = policy
+            .bindings
+            .iter()
+            .find(|v| v.role == "roles/pubsub.publisher")
+            .unwrap();
+        assert_eq!(binding.members, vec!["allUsers".to_string()]);
+
+        let permissions = vec!["pubsub.topics.publish".to_string()];
+        let granted = topic.test_iam_permissions(permissions.clone(), None).await.unwrap();
+        assert!(granted.iter().all(|v| permissions.contains(v)));
+
+        topic.delete(None).await.unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_publish_after_shutdown() {
@@ -284,6 +369,111 @@ mod tests {
         publish_after_shutdown(true).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_shutdown_with_timeout() {
+        let topic = create_topic().await;
+        let config = PublisherConfig {
+            flush_interval: Duration::from_secs(10),
+            bundle_size: 11,
+            ..Default::default()
+        };
+
+        // Flush the buffered messages within the timeout.
+        let mut publisher = topic.new_publisher(Some(config.clone()));
+        let tasks = publish(publisher.clone()).await;
+        sleep(Duration::from_secs(1)).await;
+        publisher.shutdown_with_timeout(Duration::from_secs(10)).await.unwrap();
+        for task in tasks {
+            assert!(task.await.unwrap().is_ok());
+        }
+
+        // The messages not published before the timeout are returned.
+        let mut publisher = topic.new_publisher(Some(config));
+        let messages = (0..10)
+            .map(|i| PubsubMessage {
+                data: format!("msg{i}").into(),
+                ..Default::default()
+            })
+            .collect();
+        let awaiters = publisher.publish_bulk(messages).await;
+        let unpublished = match publisher.shutdown_with_timeout(Duration::ZERO).await {
+            Ok(_) => 0,
+            Err(messages) => messages.len(),
+        };
+        let mut published = 0;
+        for awaiter in awaiters {
+            if awaiter.get().await.is_ok() {
+                published += 1;
+            }
+        }
+        assert_eq!(10, published + unpublished);
+
+        topic.delete(None).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_flush_by_bundle_byte_size() {
+        let topic = create_topic().await;
+        let msg = || PubsubMessage {
+            data: "abc".into(),
+            ..Default::default()
+        };
+        // A single worker holds all the messages in its bundle.
+        let config = PublisherConfig {
+            workers: 1,
+            flush_interval: Duration::from_secs(60),
+            bundle_size: 100,
+            bundle_byte_size: msg().encoded_len() * 2,
+            ..Default::default()
+        };
+        let mut publisher = topic.new_publisher(Some(config));
+
+        // The bundle is not flushed until it reaches bundle_byte_size.
+        let awaiter = publisher.publish(msg()).await;
+        let first = tokio::spawn(awaiter.get());
+        sleep(Duration::from_secs(1)).await;
+        assert!(!first.is_finished());
+
+        // The bundle is flushed long before flush_interval.
+        let second = publisher.publish(msg()).await;
+        let result = timeout(Duration::from_secs(10), async { (first.await.unwrap(), second.get().await) }).await;
+        let (first, second) = result.unwrap();
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+
+        publisher.shutdown().await;
+        topic.delete(None).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_flush_by_interval() {
+        let topic = create_topic().await;
+        let config = PublisherConfig {
+            flush_interval: Duration::from_secs(2),
+            bundle_size: 100,
+            ..Default::default()
+        };
+        let mut publisher = topic.new_publisher(Some(config));
+
+        // The bundle is flushed by the deadline even though it is never filled.
+        let start = Instant::now();
+        let awaiter = publisher
+            .publish(PubsubMessage {
+                data: "abc".into(),
+                ..Default::default()
+            })
+            .await;
+        let result = timeout(Duration::from_secs(10), awaiter.get()).await.unwrap();
+        assert!(result.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(1500));
+
+        publisher.shutdown().await;
+        topic.delete(None).await.unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_publish_immediately() {
@@ -305,4 +495,27 @@ mod tests {
         publisher.shutdown().await;
         topic.delete(None).await.unwrap();
     }
+
+    #[cfg(feature = "bytes")]
+    #[tokio::test]
+    #[serial]
+    async fn test_publish_bytes() {
+        let topic = create_topic().await;
+        let publisher = topic.new_publisher(None);
+
+        let payload = crate::bytes::Bytes::from_static(b"msg");
+        let msg = PubsubMessage {
+            data: payload.clone(),
+            ..Default::default()
+        };
+        // The payload is shared, not copied.
+        assert_eq!(msg.data.as_ptr(), payload.as_ptr());
+        let ack_ids = publisher.publish_immediately(vec![msg], None).await.unwrap();
+
+        assert_eq!(1, ack_ids.len());
+
+        let mut publisher = publisher;
+        publisher.shutdown().await;
+        topic.delete(None).await.unwrap();
+    }
 }
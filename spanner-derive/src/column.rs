@@ -6,12 +6,13 @@ use syn::Meta::{List, NameValue, Path};
 use syn::NestedMeta::Meta;
 use syn::{Error, Field};
 
-use crate::symbol::{COLUMN, COLUMN_NAME, COMMIT_TIMESTAMP};
+use crate::symbol::{COLUMN, COLUMN_NAME, COMMIT_TIMESTAMP, SKIP};
 
 pub(crate) struct Column<'a> {
     field: &'a Field,
     pub column_name: Option<String>,
     pub commit_timestamp: bool,
+    pub skip: bool,
 }
 
 impl Column<'_> {
@@ -31,6 +32,7 @@ impl<'a> From<&'a Field> for Column<'a> {
     fn from(field: &'a Field) -> Self {
         let mut commit_timestamp = false;
         let mut column_name = None;
+        let mut skip = false;
         for meta_item in field.attrs.iter().flat_map(|attr| get_meta_items(attr).unwrap()) {
             match &meta_item {
                 // Parse `#[column(name = "foo")]`
@@ -43,6 +45,10 @@ impl<'a> From<&'a Field> for Column<'a> {
                 Meta(Path(word)) if word == COMMIT_TIMESTAMP => {
                     commit_timestamp = true;
                 }
+                // Parse `#[column(skip)]`
+                Meta(Path(word)) if word == SKIP => {
+                    skip = true;
+                }
                 _ => {}
             }
         }
@@ -51,6 +57,7 @@ impl<'a> From<&'a Field> for Column<'a> {
             field,
             commit_timestamp,
             column_name,
+            skip,
         }
     }
 }
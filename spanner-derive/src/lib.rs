@@ -58,6 +58,8 @@
 //! }
 //!```
 //!
+//! `Option<T>` fields are mapped to the nullable column of `T`, and fields whose type also derives `Table` are encoded as `STRUCT` (or `ARRAY<STRUCT>` for `Vec<T>`).
+//!
 //! Here is the generated implementation.
 //!```
 //! use time::OffsetDateTime;
@@ -145,7 +147,10 @@
 //!    pub user_id: String,
 //!    pub user_characters: Vec<UserCharacter>,
 //!    #[spanner(name="Items")]
-//!    pub user_items: Vec<UserItem>
+//!    pub user_items: Vec<UserItem>,
+//!    // #[spanner(skip)] is used for the field not in the result set. The field is set to Default::default().
+//!    #[spanner(skip)]
+//!    pub item_count: usize,
 //! }
 //!
 //! async fn run(user_id: &str, tx: &mut Transaction) -> Result<Option<UserBundle>, Error> {
@@ -10,6 +10,12 @@ pub(crate) fn generate_query_methods(item: ItemStruct) -> impl ToTokens {
     for field in &item.fields {
         let field_var = field.ident.as_ref().unwrap();
         let column = Column::from(field);
+        if column.skip {
+            try_from_struct_fields.push(quote! {
+                #field_var: Default::default()
+            });
+            continue;
+        }
         let column_name = column.name();
         try_from_struct_fields.push(quote! {
             #field_var: s.column_by_name(#column_name)?
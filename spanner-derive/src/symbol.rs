@@ -8,6 +8,7 @@ pub(crate) struct Symbol(&'static str);
 
 pub(crate) const COMMIT_TIMESTAMP: Symbol = Symbol("commitTimestamp");
 pub(crate) const COLUMN_NAME: Symbol = Symbol("name");
+pub(crate) const SKIP: Symbol = Symbol("skip");
 pub(crate) const COLUMN: Symbol = Symbol("spanner");
 
 impl PartialEq<Symbol> for Ident {
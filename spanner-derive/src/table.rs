@@ -11,6 +11,9 @@ pub(crate) fn generate_table_methods(item: ItemStruct) -> impl ToTokens {
     for field in &item.fields {
         let field_var = field.ident.as_ref().unwrap();
         let column = Column::from(field);
+        if column.skip {
+            continue;
+        }
         let column_name = column.name();
         let ty = &field.ty;
         let mut get_field_type = quote! { <#ty> };
@@ -6,7 +6,7 @@ use time::{Date, OffsetDateTime};
 
 use google_cloud_spanner::client::{Client, ClientConfig, Error};
 use google_cloud_spanner::mutation::insert_struct;
-use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner::statement::{Statement, ToKind, ToStruct};
 use google_cloud_spanner_derive::{Query, Table};
 
 #[derive(Table, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -107,6 +107,45 @@ pub struct UserBundle {
     pub user_items: Vec<UserItem>,
 }
 
+#[derive(Query)]
+pub struct UserItems {
+    pub user_id: String,
+    #[spanner(name = "Items")]
+    pub user_items: Vec<UserItem>,
+    #[spanner(skip)]
+    pub item_count: usize,
+}
+
+#[derive(Table, Default)]
+pub struct UserProfile {
+    pub user_id: String,
+    pub nickname: Option<String>,
+    pub main_character: Option<UserCharacter>,
+    pub items: Vec<UserItem>,
+    #[spanner(skip)]
+    pub cached: bool,
+}
+
+#[test]
+fn test_nested_types() {
+    let types = UserProfile::get_types();
+    let names: Vec<&str> = types.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, vec!["UserId", "Nickname", "MainCharacter", "Items"]);
+    assert_eq!(types[1].1, String::get_type());
+    assert_eq!(types[2].1, UserCharacter::get_type());
+    assert_eq!(types[3].1, Vec::<UserItem>::get_type());
+
+    let profile = UserProfile {
+        user_id: "user".to_string(),
+        main_character: Some(UserCharacter::default()),
+        ..Default::default()
+    };
+    let kinds = profile.to_kinds();
+    assert_eq!(kinds.len(), 4);
+    assert_eq!(kinds[1].1, None::<String>.to_kind());
+    assert_eq!(kinds[2].1, UserCharacter::default().to_kind());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_table_derive() -> Result<(), Error> {
@@ -200,3 +239,50 @@ async fn test_query_derive() -> Result<(), Error> {
     }
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_query_derive_with_name_and_skip() -> Result<(), Error> {
+    std::env::set_var("SPANNER_EMULATOR_HOST", "localhost:9010");
+    let config = ClientConfig::default();
+    let client = Client::new(
+        "projects/local-project/instances/test-instance/databases/local-database",
+        config,
+    )
+    .await?;
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let user_id = format!("user-n-{now}");
+    let user = User {
+        user_id: user_id.clone(),
+        ..Default::default()
+    };
+    let user_item = UserItem {
+        user_id: user_id.clone(),
+        ..Default::default()
+    };
+    client
+        .apply(vec![insert_struct("User", user), insert_struct("UserItem", user_item)])
+        .await?;
+
+    let mut tx = client.read_only_transaction().await?;
+    let mut stmt = Statement::new(
+        "
+    SELECT
+        UserId,
+	    ARRAY(SELECT AS STRUCT * FROM UserItem WHERE UserId = @UserId) AS Items,
+    From User
+    WHERE UserID = @UserID",
+    );
+    stmt.add_param("UserID", &user_id);
+    let mut reader = tx.query(stmt).await?;
+    if let Some(row) = reader.next().await? {
+        let v: UserItems = row.try_into()?;
+        assert_eq!(v.user_id, user_id);
+        assert_eq!(v.user_items.len(), 1);
+        assert_eq!(v.item_count, 0);
+    } else {
+        panic!("no data found");
+    }
+    Ok(())
+}
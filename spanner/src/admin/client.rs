@@ -37,6 +37,8 @@ async fn internal_client(config: &AdminClientConfig) -> Result<(Channel, Operati
     let conn_options = ConnectionOptions {
         timeout: Some(Duration::from_secs(30)),
         connect_timeout: Some(Duration::from_secs(30)),
+        quota_project: config.quota_project.clone(),
+        ..Default::default()
     };
     let conn_pool = ConnectionManager::new(1, SPANNER, AUDIENCE, &config.environment, &conn_options).await?;
     let conn = conn_pool.conn();
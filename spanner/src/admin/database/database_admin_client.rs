@@ -8,10 +8,10 @@ use google_cloud_googleapis::iam::v1::{
 use google_cloud_googleapis::longrunning::Operation as InternalOperation;
 use google_cloud_googleapis::spanner::admin::database::v1::database_admin_client::DatabaseAdminClient as InternalDatabaseAdminClient;
 use google_cloud_googleapis::spanner::admin::database::v1::{
-    Backup, CreateBackupRequest, CreateDatabaseRequest, Database, DeleteBackupRequest, DropDatabaseRequest,
-    GetBackupRequest, GetDatabaseDdlRequest, GetDatabaseDdlResponse, GetDatabaseRequest, ListBackupOperationsRequest,
-    ListBackupsRequest, ListDatabaseOperationsRequest, ListDatabasesRequest, RestoreDatabaseRequest,
-    UpdateBackupRequest, UpdateDatabaseDdlRequest,
+    Backup, CreateBackupRequest, CreateDatabaseRequest, Database, DatabaseDialect, DeleteBackupRequest,
+    DropDatabaseRequest, GetBackupRequest, GetDatabaseDdlRequest, GetDatabaseDdlResponse, GetDatabaseRequest,
+    ListBackupOperationsRequest, ListBackupsRequest, ListDatabaseOperationsRequest, ListDatabasesRequest,
+    RestoreDatabaseRequest, UpdateBackupRequest, UpdateDatabaseDdlRequest,
 };
 use google_cloud_longrunning::autogen::operations_client::OperationsClient;
 use google_cloud_longrunning::longrunning::Operation;
@@ -84,6 +84,49 @@ impl DatabaseAdminClient {
             .map(|d| Operation::new(self.lro_client.clone(), d.into_inner()))
     }
 
+    /// create_database_with_ddl creates a new Cloud Spanner database named `database_id` in `instance`
+    /// (projects/<project>/instances/<instance>), applies `extra_ddl` to it and waits until the
+    /// long-running operation completes.
+    /// ```
+    /// use google_cloud_spanner::admin::client::Client;
+    ///
+    /// async fn run(client: Client) {
+    ///     let database = client
+    ///         .database()
+    ///         .create_database_with_ddl(
+    ///             "projects/local-project/instances/test-instance",
+    ///             "test-database",
+    ///             vec!["CREATE TABLE User (UserId STRING(36) NOT NULL) PRIMARY KEY(UserId)".to_string()],
+    ///             None,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///     println!("created {}", database.name);
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn create_database_with_ddl(
+        &self,
+        instance: &str,
+        database_id: &str,
+        extra_ddl: Vec<String>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Database, Status> {
+        let req = CreateDatabaseRequest {
+            parent: instance.to_string(),
+            create_statement: format!("CREATE DATABASE `{database_id}`"),
+            extra_statements: extra_ddl,
+            encryption_config: None,
+            database_dialect: DatabaseDialect::GoogleStandardSql.into(),
+            proto_descriptors: vec![],
+        };
+        let mut operation = self.create_database(req, retry).await?;
+        operation
+            .wait(None)
+            .await?
+            .ok_or_else(|| Status::internal(format!("no database returned by operation {}", operation.name())))
+    }
+
     /// get_database gets the state of a Cloud Spanner database.
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn get_database(
@@ -60,6 +60,34 @@ mod tests {
         assert_eq!(instance.state, State::Ready as i32);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_create_database_with_ddl() {
+        let client = new_client().await;
+        let database_id = format!("test{}ddl", OffsetDateTime::now_utc().unix_timestamp());
+        let database = client
+            .create_database_with_ddl(
+                "projects/local-project/instances/test-instance",
+                &database_id,
+                vec!["CREATE TABLE Tbl (ID STRING(MAX)) PRIMARY KEY(ID)".to_string()],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(database.state, State::Ready as i32);
+
+        let ddl = client
+            .get_database_ddl(
+                GetDatabaseDdlRequest {
+                    database: database.name.to_string(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ddl.into_inner().statements.len(), 1);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_database() {
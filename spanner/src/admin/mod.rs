@@ -18,6 +18,9 @@ pub const SCOPES: [&str; 2] = [
 pub struct AdminClientConfig {
     /// Runtime project
     pub environment: Environment,
+    /// The project billed for the quota of the requests.
+    /// The `quota_project_id` of the credentials is used by default.
+    pub quota_project: Option<String>,
 }
 
 impl Default for AdminClientConfig {
@@ -27,6 +30,7 @@ impl Default for AdminClientConfig {
                 Some(v) => Environment::Emulator(v),
                 None => Environment::GoogleCloud(Box::new(NopeTokenSourceProvider {})),
             },
+            quota_project: None,
         }
     }
 }
@@ -39,6 +43,7 @@ impl AdminClientConfig {
     pub async fn with_auth(mut self) -> Result<Self, google_cloud_auth::error::Error> {
         if let Environment::GoogleCloud(_) = self.environment {
             let ts = google_cloud_auth::token::DefaultTokenSourceProvider::new(Self::auth_config()).await?;
+            self.quota_project = self.quota_project.or(ts.quota_project_id());
             self.environment = Environment::GoogleCloud(Box::new(ts))
         }
         Ok(self)
@@ -54,6 +59,7 @@ impl AdminClientConfig {
                 Box::new(credentials),
             )
             .await?;
+            self.quota_project = self.quota_project.or(ts.quota_project_id());
             self.environment = Environment::GoogleCloud(Box::new(ts))
         }
         Ok(self)
@@ -73,5 +79,8 @@ pub fn default_retry_setting() -> RetrySetting {
         factor: 1u64,
         take: 20,
         codes: vec![Code::Unavailable, Code::Unknown, Code::DeadlineExceeded],
+        jitter: false,
+        max_elapsed: None,
+        timeout: None,
     }
 }
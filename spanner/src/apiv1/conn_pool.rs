@@ -32,6 +32,11 @@ impl ConnectionManager {
 
     pub fn conn(&self) -> Client {
         let conn = self.inner.conn();
-        Client::new(SpannerClient::new(conn))
+        let client = SpannerClient::new(conn);
+        let client = match self.inner.compression() {
+            Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+            None => client,
+        };
+        Client::new(client)
     }
 }
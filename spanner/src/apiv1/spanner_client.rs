@@ -31,13 +31,16 @@ pub(crate) fn ping_query_request(session_name: impl Into<String>) -> ExecuteSqlR
     }
 }
 
-fn default_setting() -> RetrySetting {
+pub(crate) fn default_setting() -> RetrySetting {
     RetrySetting {
         from_millis: 50,
         max_delay: Some(Duration::from_secs(10)),
         factor: 1u64,
         take: 20,
         codes: vec![Code::Unavailable, Code::Unknown],
+        jitter: false,
+        max_elapsed: None,
+        timeout: None,
     }
 }
 
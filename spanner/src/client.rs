@@ -5,19 +5,20 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
-use google_cloud_gax::conn::{ConnectionOptions, Environment};
+use google_cloud_gax::conn::{CompressionEncoding, ConnectionOptions, Environment, KeepAliveConfig, RpcInterceptor};
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::{invoke_fn, TryAs};
 use google_cloud_googleapis::spanner::v1::{commit_request, transaction_options, Mutation, TransactionOptions};
 use google_cloud_token::NopeTokenSourceProvider;
 
 use crate::apiv1::conn_pool::{ConnectionManager, SPANNER};
+use crate::mutation::{split_mutations, BatchOptions};
 use crate::retry::TransactionRetrySetting;
 use crate::session::{ManagedSession, SessionConfig, SessionError, SessionManager};
 use crate::statement::Statement;
 use crate::transaction::{CallOptions, QueryOptions};
 use crate::transaction_ro::{BatchReadOnlyTransaction, ReadOnlyTransaction};
-use crate::transaction_rw::{commit, CommitOptions, ReadWriteTransaction};
+use crate::transaction_rw::{commit, CommitChunkedError, CommitOptions, ReadWriteTransaction};
 use crate::value::{Timestamp, TimestampBound};
 
 #[derive(Clone, Default)]
@@ -29,6 +30,7 @@ pub struct PartitionedUpdateOption {
 #[derive(Clone)]
 pub struct ReadOnlyTransactionOption {
     pub timestamp_bound: TimestampBound,
+    /// The priority of call_options is also used as the default priority for the reads and queries in the transaction.
     pub call_options: CallOptions,
 }
 
@@ -43,16 +45,38 @@ impl Default for ReadOnlyTransactionOption {
 
 #[derive(Clone, Default)]
 pub struct ReadWriteTransactionOption {
+    /// The priority of begin_options is also used as the default priority for the reads, queries,
+    /// DML statements and commit in the transaction.
+    /// e.g. `Priority::Low` prevents batch jobs from competing with online traffic.
     pub begin_options: CallOptions,
     pub commit_options: CommitOptions,
 }
 
 #[derive(Clone, Debug)]
 pub struct ChannelConfig {
-    /// num_channels is the number of gRPC channels.
+    /// num_channels is the number of gRPC channels. RPCs are distributed across the channels in round-robin.
+    ///
+    /// Sessions are assigned to the channels in the same way, so each channel multiplexes about
+    /// `SessionConfig::max_opened / num_channels` sessions. Since a single HTTP/2 connection allows
+    /// around 100 concurrent streams, keep `max_opened` below `num_channels * 100`, which is what
+    /// `ClientConfig::default` uses, and increase `num_channels` together with `max_opened`.
     pub num_channels: usize,
     pub connect_timeout: Duration,
     pub timeout: Duration,
+    /// compression enables the compression of the requests and the responses, such as gzip.
+    /// Disabled by default.
+    pub compression: Option<CompressionEncoding>,
+    /// interceptors are called on every RPC, e.g. to add custom metadata or to collect metrics.
+    pub interceptors: Vec<Arc<dyn RpcInterceptor>>,
+    /// quota_project is the project billed for the quota of the requests.
+    /// The `quota_project_id` of the credentials is used by default.
+    pub quota_project: Option<String>,
+    /// keep_alive configures the HTTP/2 keepalive pings which detect the connections dropped while idle.
+    /// Pings every 30 seconds by default.
+    pub keep_alive: KeepAliveConfig,
+    /// user_agent is the token of the application like `my-service/1.0` sent in the `user-agent`
+    /// and `x-goog-api-client` headers, so that the requests can be distinguished in the request logs.
+    pub user_agent: Option<String>,
 }
 
 impl Default for ChannelConfig {
@@ -61,6 +85,11 @@ impl Default for ChannelConfig {
             num_channels: 4,
             connect_timeout: Duration::from_secs(30),
             timeout: Duration::from_secs(30),
+            compression: None,
+            interceptors: vec![],
+            quota_project: None,
+            keep_alive: KeepAliveConfig::default(),
+            user_agent: None,
         }
     }
 }
@@ -103,6 +132,7 @@ impl ClientConfig {
     pub async fn with_auth(mut self) -> Result<Self, google_cloud_auth::error::Error> {
         if let Environment::GoogleCloud(_) = self.environment {
             let ts = google_cloud_auth::token::DefaultTokenSourceProvider::new(Self::auth_config()).await?;
+            self.channel_config.quota_project = self.channel_config.quota_project.or(ts.quota_project_id());
             self.environment = Environment::GoogleCloud(Box::new(ts))
         }
         Ok(self)
@@ -118,6 +148,7 @@ impl ClientConfig {
                 Box::new(credentials),
             )
             .await?;
+            self.channel_config.quota_project = self.channel_config.quota_project.or(ts.quota_project_id());
             self.environment = Environment::GoogleCloud(Box::new(ts))
         }
         Ok(self)
@@ -179,6 +210,11 @@ impl Client {
         let options = ConnectionOptions {
             timeout: Some(config.channel_config.timeout),
             connect_timeout: Some(config.channel_config.connect_timeout),
+            compression: config.channel_config.compression,
+            interceptors: config.channel_config.interceptors,
+            quota_project: config.channel_config.quota_project,
+            keep_alive: config.channel_config.keep_alive,
+            user_agent: config.channel_config.user_agent,
         };
         let conn_pool =
             ConnectionManager::new(pool_size, &config.environment, config.endpoint.as_str(), &options).await?;
@@ -432,6 +468,50 @@ impl Client {
         Ok(result?.0)
     }
 
+    /// apply_batched splits the mutations into multiple commits so that each commit satisfies the BatchOptions,
+    /// and applies them in order. It returns the commit timestamps of the commits.
+    ///
+    /// Note that this is NOT atomic across the commits. If a commit fails, the mutations of
+    /// the preceding commits are already applied and the rest are not applied.
+    /// In that case `CommitChunkedError::PartiallyCommitted` is returned with the commit timestamps of
+    /// the applied commits, same as `ReadWriteTransaction::commit_chunked`.
+    /// ```
+    /// use google_cloud_spanner::mutation::{insert, BatchOptions};
+    /// use google_cloud_spanner::client::{Client, Error};
+    /// use google_cloud_spanner::transaction_rw::CommitChunkedError;
+    ///
+    /// async fn run(client: Client) -> Result<(), Error>{
+    ///     let ms = (0..100_000_i64).map(|i| insert("Guild", &["GuildID", "OwnerUserID"], &[&i, &"owner"])).collect();
+    ///     match client.apply_batched(ms, BatchOptions::default()).await {
+    ///         Ok(_commit_timestamps) => Ok(()),
+    ///         Err(CommitChunkedError::NotCommitted(err)) => Err(err),
+    ///         // The mutations of the chunks in commit_timestamps are applied.
+    ///         Err(CommitChunkedError::PartiallyCommitted { error, .. }) => Err(error),
+    ///     }
+    /// }
+    /// ```
+    pub async fn apply_batched(
+        &self,
+        ms: Vec<Mutation>,
+        options: BatchOptions,
+    ) -> Result<Vec<Option<Timestamp>>, CommitChunkedError<Error>> {
+        let chunks = split_mutations(ms, &options);
+        let mut commit_timestamps = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            match self.apply(chunk).await {
+                Ok(commit_timestamp) => commit_timestamps.push(commit_timestamp),
+                Err(error) if commit_timestamps.is_empty() => return Err(CommitChunkedError::NotCommitted(error)),
+                Err(error) => {
+                    return Err(CommitChunkedError::PartiallyCommitted {
+                        commit_timestamps,
+                        error,
+                    })
+                }
+            }
+        }
+        Ok(commit_timestamps)
+    }
+
     /// ReadWriteTransaction executes a read-write transaction, with retries as
     /// necessary.
     ///
@@ -634,3 +714,80 @@ impl Client {
         (options.begin_options, options.commit_options)
     }
 }
+
+#[cfg(feature = "emulator")]
+impl Client {
+    /// setup_emulator creates the instance with `instance_config` and the database with `database_ddl`
+    /// on the emulator of `SPANNER_EMULATOR_HOST`. The instance and the database which already exist are left as is.
+    /// `database` has the form projects/PROJECT_ID/instances/INSTANCE_ID/databases/DATABASE_ID.
+    /// Returns an error when `SPANNER_EMULATOR_HOST` is not set.
+    /// ```
+    /// use google_cloud_spanner::client::{Client, ClientConfig, Error};
+    ///
+    /// async fn run() -> Result<Client, Error> {
+    ///     const DATABASE: &str = "projects/local-project/instances/test-instance/databases/local-database";
+    ///     Client::setup_emulator(
+    ///         DATABASE,
+    ///         "emulator-config",
+    ///         &["CREATE TABLE User (UserId STRING(36) NOT NULL) PRIMARY KEY(UserId)"],
+    ///     )
+    ///     .await?;
+    ///     Client::new(DATABASE, ClientConfig::default()).await
+    /// }
+    /// ```
+    pub async fn setup_emulator(database: &str, instance_config: &str, database_ddl: &[&str]) -> Result<(), Error> {
+        use google_cloud_googleapis::spanner::admin::instance::v1::{CreateInstanceRequest, Instance};
+
+        use crate::admin::client::Client as AdminClient;
+        use crate::admin::AdminClientConfig;
+
+        let host = var("SPANNER_EMULATOR_HOST")
+            .map_err(|_| Error::InvalidConfig("SPANNER_EMULATOR_HOST is not set".to_string()))?;
+        let invalid_database = || Error::InvalidConfig(format!("invalid database name: {database}"));
+        let (instance, database_id) = database.split_once("/databases/").ok_or_else(invalid_database)?;
+        let (project, instance_id) = instance.split_once("/instances/").ok_or_else(invalid_database)?;
+        let admin = AdminClient::new(AdminClientConfig {
+            environment: Environment::Emulator(host),
+            quota_project: None,
+        })
+        .await?;
+
+        let config = if instance_config.contains('/') {
+            instance_config.to_string()
+        } else {
+            format!("{project}/instanceConfigs/{instance_config}")
+        };
+        let request = CreateInstanceRequest {
+            parent: project.to_string(),
+            instance_id: instance_id.to_string(),
+            instance: Some(Instance {
+                name: instance.to_string(),
+                config,
+                display_name: instance_id.to_string(),
+                node_count: 1,
+                ..Default::default()
+            }),
+        };
+        let result = match admin.instance().create_instance(request, None).await {
+            Ok(mut operation) => operation.wait(None).await.map(|_| ()),
+            Err(status) => Err(status),
+        };
+        ignore_already_exists(result)?;
+
+        let ddl = database_ddl.iter().map(|v| v.to_string()).collect();
+        let result = admin
+            .database()
+            .create_database_with_ddl(instance, database_id, ddl, None)
+            .await
+            .map(|_| ());
+        ignore_already_exists(result)
+    }
+}
+
+#[cfg(feature = "emulator")]
+fn ignore_already_exists(result: Result<(), Status>) -> Result<(), Error> {
+    match result {
+        Err(status) if status.code() != Code::AlreadyExists => Err(status.into()),
+        _ => Ok(()),
+    }
+}
@@ -31,6 +31,7 @@ use crate::statement::ToKind;
 /// types:
 ///   - i64 and Option<i64> are mapped to Cloud Spanner's INT64 type.
 ///   - f64 and Option<f64> are mapped to Cloud Spanner's FLOAT64 type.
+///   - f32 and Option<f32> are mapped to Cloud Spanner's FLOAT32 type.
 ///   - bool and Option<bool> are mapped to Cloud Spanner's BOOL type.
 ///   - Vec<u8>, &[u8], Option<Vec<u8>> and Option<&[u8]> is mapped to Cloud Spanner's BYTES type.
 ///   - String, &str, Option<String>, Option<&str> are mapped to Cloud Spanner's STRING type.
@@ -134,8 +135,10 @@ pub enum RangeKind {
 ///  ```
 ///
 ///  The next example retrieves all events for "Bob":
-///
-///     Key::new("Bob").to_prefix()
+///  ```
+///    use google_cloud_spanner::key::{Key, KeyRange};
+///    let range = KeyRange::prefix(Key::new(&"Bob"));
+///  ```
 ///
 ///  To retrieve events before the year 2000:
 ///  ```
@@ -204,6 +207,48 @@ impl KeyRange {
     pub fn new(start: Key, end: Key, kind: RangeKind) -> KeyRange {
         KeyRange { start, end, kind }
     }
+
+    /// after returns a KeyRange that contains all the keys after the given key, excluding the key itself.
+    /// It is used to resume a paginated read from the last key returned by the previous page.
+    /// # Examples
+    /// ```
+    ///    use google_cloud_googleapis::spanner::v1::read_request::OrderBy;
+    ///    use google_cloud_spanner::key::{Key, KeyRange};
+    ///    use google_cloud_spanner::transaction::ReadOptions;
+    ///    use google_cloud_spanner::transaction_ro::ReadOnlyTransaction;
+    ///
+    ///    async fn next_page(tx: &mut ReadOnlyTransaction, last_user_id: &str) {
+    ///        let options = ReadOptions {
+    ///            limit: 100,
+    ///            order_by: OrderBy::PrimaryKey,
+    ///            ..Default::default()
+    ///        };
+    ///        let range = KeyRange::after(Key::new(&last_user_id));
+    ///        let mut iter = tx.read_with_option("User", &["UserId"], range, options).await.unwrap();
+    ///        while let Some(row) = iter.next().await.unwrap() {
+    ///            let user_id = row.column_by_name::<String>("UserId").unwrap();
+    ///        }
+    ///    }
+    /// ```
+    pub fn after(start: Key) -> KeyRange {
+        // An empty end key closed on the right matches every key.
+        KeyRange::new(start, Key::composite(&[]), RangeKind::OpenClosed)
+    }
+
+    /// prefix returns a KeyRange that contains all the keys starting with the given key components.
+    /// It is typically used to read or delete the interleaved child rows of a parent row.
+    /// # Examples
+    /// ```
+    ///    use google_cloud_spanner::key::{Key, KeyRange};
+    ///    use google_cloud_spanner::mutation::delete;
+    ///
+    ///    // Deletes all the albums of the singer 1.
+    ///    let mutation = delete("Albums", KeyRange::prefix(Key::new(&1)));
+    /// ```
+    pub fn prefix(prefix: Key) -> KeyRange {
+        // A closed boundary that omits trailing components matches every key with the same leading components.
+        KeyRange::new(prefix.clone(), prefix, RangeKind::ClosedClosed)
+    }
 }
 
 impl From<KeyRange> for InternalKeyRange {
@@ -361,4 +406,33 @@ mod tests {
             _ => panic!("invalid end key trype"),
         }
     }
+
+    #[test]
+    fn test_key_range_after() {
+        let raw_range: v1::KeyRange = KeyRange::after(Key::new(&1)).into();
+        match raw_range.start_key_type.unwrap() {
+            v1::key_range::StartKeyType::StartOpen(mut v) => match v.values.pop().unwrap().kind.unwrap() {
+                Kind::StringValue(v) => assert_eq!(v, "1"),
+                _ => panic!("invalid start kind"),
+            },
+            _ => panic!("invalid start key type"),
+        }
+        match raw_range.end_key_type.unwrap() {
+            v1::key_range::EndKeyType::EndClosed(v) => assert!(v.values.is_empty()),
+            _ => panic!("invalid end key type"),
+        }
+    }
+
+    #[test]
+    fn test_key_range_prefix() {
+        let raw_range: v1::KeyRange = KeyRange::prefix(Key::composite(&[&1, &"a"])).into();
+        match raw_range.start_key_type.unwrap() {
+            v1::key_range::StartKeyType::StartClosed(v) => assert_eq!(v.values.len(), 2),
+            _ => panic!("invalid start key type"),
+        }
+        match raw_range.end_key_type.unwrap() {
+            v1::key_range::EndKeyType::EndClosed(v) => assert_eq!(v.values.len(), 2),
+            _ => panic!("invalid end key type"),
+        }
+    }
 }
@@ -281,7 +281,7 @@
 //!
 //! * The used session is returned to the drop timing session pool, so unlike Go, there is no need to call Stop.
 //!
-//! * To read rows with an index, use `client.read_with_option`.
+//! * To read rows with an index, a limit or in the key order, use `client.read_with_option` with `ReadOptions`.
 //!
 //! ### <a name="Statements"></a>Statements
 //!
@@ -412,6 +412,19 @@
 //! }
 //! ```
 //!
+//! To read a consistent snapshot as of a known time such as a commit timestamp, use `TimestampBound::read_timestamp`.
+//!
+//! ```
+//! use time::OffsetDateTime;
+//! use google_cloud_spanner::client::{Client, Error};
+//! use google_cloud_spanner::value::TimestampBound;
+//!
+//! pub async fn run(client: Client, commit_timestamp: OffsetDateTime) -> Result<(), Error>{
+//!     let tx = client.single_with_timestamp_bound(TimestampBound::read_timestamp(commit_timestamp)).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
 //! See the documentation of TimestampBound for more details.
 //!
 //! ### <a name="Mutations"></a>Mutations
@@ -646,3 +659,4 @@ pub mod transaction_ro;
 pub mod transaction_rw;
 pub mod value;
 pub use bigdecimal;
+pub use serde_json;
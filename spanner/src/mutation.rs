@@ -1,3 +1,4 @@
+use prost::Message;
 use prost_types::{ListValue, Value};
 
 use google_cloud_googleapis::spanner::v1::mutation::{Delete, Operation, Write};
@@ -177,6 +178,72 @@ pub fn delete(table: &str, key_set: impl Into<KeySet>) -> Mutation {
     }
 }
 
+/// mutation_count returns the estimated number of mutations the given Mutation
+/// contributes to a commit, computed as rows × columns for write operations.
+/// A delete counts one mutation per key or key range (one for all keys).
+///
+/// Cloud Spanner also counts mutations on secondary indexes, which cannot be
+/// derived from the Mutation alone, so the result is a lower bound.
+pub fn mutation_count(mutation: &Mutation) -> usize {
+    match &mutation.operation {
+        Some(Operation::Insert(w))
+        | Some(Operation::Update(w))
+        | Some(Operation::InsertOrUpdate(w))
+        | Some(Operation::Replace(w)) => w.values.len() * w.columns.len(),
+        Some(Operation::Delete(d)) => match &d.key_set {
+            Some(ks) if !ks.all => ks.keys.len() + ks.ranges.len(),
+            _ => 1,
+        },
+        None => 0,
+    }
+}
+
+/// BatchOptions specifies how to split the mutations into multiple commits.
+#[derive(Clone, Debug)]
+pub struct BatchOptions {
+    /// The maximum number of mutations (rows × columns) in a commit.
+    pub max_mutations_per_commit: usize,
+    /// The maximum encoded size of the mutations in a commit.
+    pub max_bytes: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            max_mutations_per_commit: 40_000,
+            max_bytes: 100 * 1024 * 1024,
+        }
+    }
+}
+
+/// split_mutations partitions the mutations into the chunks each of which satisfies the BatchOptions
+/// using the estimation of mutation_count. The order of the mutations is preserved.
+/// A mutation which exceeds the limits by itself is placed in its own chunk.
+pub fn split_mutations(ms: Vec<Mutation>, options: &BatchOptions) -> Vec<Vec<Mutation>> {
+    let mut chunks = vec![];
+    let mut chunk = vec![];
+    let mut count = 0;
+    let mut bytes = 0;
+    for m in ms {
+        let m_count = mutation_count(&m);
+        let m_bytes = m.encoded_len();
+        if !chunk.is_empty()
+            && (count + m_count > options.max_mutations_per_commit || bytes + m_bytes > options.max_bytes)
+        {
+            chunks.push(std::mem::take(&mut chunk));
+            count = 0;
+            bytes = 0;
+        }
+        count += m_count;
+        bytes += m_bytes;
+        chunk.push(m);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
 #[cfg(test)]
 mod tests {
     use prost_types::value::Kind;
@@ -330,6 +397,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_replace_map() {
+        let user_id = 1;
+        let mutation = replace_map(
+            "Guild",
+            &[
+                ("UserId", &"aa"),
+                ("GuildId", &user_id),
+                ("updatedAt", &CommitTimestamp::new()),
+            ],
+        );
+        match mutation.operation.unwrap() {
+            v1::mutation::Operation::Replace(mut w) => {
+                assert_eq!("Guild", w.table);
+                assert_eq!(3, w.values.pop().unwrap().values.len());
+                assert_eq!(vec!["UserId", "GuildId", "updatedAt"], w.columns);
+            }
+            _ => panic!("invalid operation"),
+        }
+    }
+
     #[test]
     fn test_replace_struct() {
         let mutation = replace_struct(
@@ -387,6 +475,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mutation_count() {
+        let ms = [
+            insert(
+                "Guild",
+                &["GuildId", "UserId", "UpdatedAt"],
+                &[&"1", &"2", &CommitTimestamp::new()],
+            ),
+            update_map("Guild", &[("GuildId", &"1"), ("UserId", &"2")]),
+            delete("Guild", all_keys()),
+            delete("Guild", vec![Key::new(&"1"), Key::new(&"2")]),
+        ];
+        assert_eq!(3, mutation_count(&ms[0]));
+        assert_eq!(2, mutation_count(&ms[1]));
+        assert_eq!(1, mutation_count(&ms[2]));
+        assert_eq!(2, mutation_count(&ms[3]));
+    }
+
+    #[test]
+    fn test_split_mutations() {
+        let ms: Vec<Mutation> = (0..10)
+            .map(|i| insert("Guild", &["GuildId", "UserId"], &[&i.to_string(), &"user"]))
+            .collect();
+
+        let chunks = split_mutations(
+            ms.clone(),
+            &BatchOptions {
+                max_mutations_per_commit: 5,
+                ..Default::default()
+            },
+        );
+        assert_eq!(vec![2, 2, 2, 2, 2], chunks.iter().map(|c| c.len()).collect::<Vec<_>>());
+
+        let chunks = split_mutations(
+            ms.clone(),
+            &BatchOptions {
+                max_bytes: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(10, chunks.len());
+
+        let chunks = split_mutations(ms, &BatchOptions::default());
+        assert_eq!(1, chunks.len());
+        assert_eq!(10, chunks[0].len());
+    }
+
     fn assert_struct(mut w: Write) {
         assert_eq!("Guild", w.table);
         assert_eq!("StructField", w.columns.pop().unwrap());
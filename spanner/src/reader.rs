@@ -1,16 +1,23 @@
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
+use prost::Message;
 use prost_types::{value::Kind, Value};
+use time::OffsetDateTime;
 
 use google_cloud_gax::grpc::{Code, Response, Status, Streaming};
+use google_cloud_gax::retry::RetrySetting;
 use google_cloud_googleapis::spanner::v1::struct_type::Field;
 use google_cloud_googleapis::spanner::v1::{ExecuteSqlRequest, PartialResultSet, ReadRequest, ResultSetMetadata};
 
+use crate::retry::TransactionRetry;
 use crate::row::Row;
 use crate::session::SessionHandle;
 use crate::transaction::CallOptions;
 
+/// The maximum size of the result sets buffered while waiting for the next resume token.
+const MAX_BYTES_BETWEEN_RESUME_TOKENS: usize = 128 * 1024 * 1024;
+
 pub trait Reader: Send + Sync {
     fn read(
         &self,
@@ -21,6 +28,11 @@ pub trait Reader: Send + Sync {
     fn update_token(&mut self, resume_token: Vec<u8>);
 
     fn can_resume(&self) -> bool;
+
+    /// Whether the rows are buffered until the resume token to resume the stream without duplicates.
+    fn enable_resume(&self) -> bool {
+        true
+    }
 }
 
 pub struct StatementReader {
@@ -36,7 +48,9 @@ impl Reader for StatementReader {
     ) -> Result<Response<Streaming<PartialResultSet>>, Status> {
         let option = option.unwrap_or_default();
         let client = &mut session.spanner_client;
-        let result = client.execute_streaming_sql(self.request.clone(), option.retry).await;
+        let result = client
+            .execute_streaming_sql(self.request.clone(), option.retry_setting())
+            .await;
         session.invalidate_if_needed(result).await
     }
 
@@ -47,6 +61,10 @@ impl Reader for StatementReader {
     fn can_resume(&self) -> bool {
         self.enable_resume && !self.request.resume_token.is_empty()
     }
+
+    fn enable_resume(&self) -> bool {
+        self.enable_resume
+    }
 }
 
 pub struct TableReader {
@@ -61,7 +79,9 @@ impl Reader for TableReader {
     ) -> Result<Response<Streaming<PartialResultSet>>, Status> {
         let option = option.unwrap_or_default();
         let client = &mut session.spanner_client;
-        let result = client.streaming_read(self.request.clone(), option.retry).await;
+        let result = client
+            .streaming_read(self.request.clone(), option.retry_setting())
+            .await;
         session.invalidate_if_needed(result).await
     }
 
@@ -177,6 +197,53 @@ impl ResultSet {
     }
 }
 
+/// ResumeBuffer holds the result sets received after the last resume token.
+/// The rows are yielded only after the next resume token is received, so that the stream resumed
+/// from the token doesn't yield them twice.
+#[derive(Default)]
+struct ResumeBuffer {
+    result_sets: Vec<PartialResultSet>,
+    bytes: usize,
+    /// Whether the rows after the last resume token have already been yielded because the buffer overflowed.
+    overflowed: bool,
+}
+
+impl ResumeBuffer {
+    /// push returns true if the buffered result sets should be yielded.
+    fn push(&mut self, result_set: PartialResultSet) -> bool {
+        let has_token = !result_set.resume_token.is_empty();
+        self.bytes += result_set.encoded_len();
+        self.result_sets.push(result_set);
+        if has_token {
+            self.overflowed = false;
+            return true;
+        }
+        if self.bytes > MAX_BYTES_BETWEEN_RESUME_TOKENS {
+            self.overflowed = true;
+        }
+        self.overflowed
+    }
+
+    fn take(&mut self) -> Vec<PartialResultSet> {
+        self.bytes = 0;
+        std::mem::take(&mut self.result_sets)
+    }
+
+    /// clear discards the result sets to be received again from the last resume token.
+    fn clear(&mut self) {
+        self.bytes = 0;
+        self.result_sets.clear();
+    }
+}
+
+/// resume_retry retries the streaming error which the stream can be resumed from.
+fn resume_retry() -> TransactionRetry<Status> {
+    TransactionRetry::with_codes(RetrySetting {
+        codes: vec![Code::Unavailable, Code::Internal],
+        ..Default::default()
+    })
+}
+
 pub struct RowIterator<'a, T>
 where
     T: Reader,
@@ -186,6 +253,9 @@ where
     reader: T,
     rs: ResultSet,
     reader_option: Option<CallOptions>,
+    read_timestamp: Option<Arc<OnceLock<OffsetDateTime>>>,
+    buffer: ResumeBuffer,
+    retry: Option<TransactionRetry<Status>>,
 }
 
 impl<'a, T> RowIterator<'a, T>
@@ -210,6 +280,9 @@ where
             reader,
             rs,
             reader_option: None,
+            read_timestamp: None,
+            buffer: ResumeBuffer::default(),
+            retry: None,
         })
     }
 
@@ -217,35 +290,80 @@ where
         self.reader_option = Some(option);
     }
 
+    /// with_read_timestamp stores the read timestamp in the metadata of the result into the given cell.
+    pub(crate) fn with_read_timestamp(mut self, read_timestamp: Arc<OnceLock<OffsetDateTime>>) -> Self {
+        self.read_timestamp = Some(read_timestamp);
+        self
+    }
+
     async fn try_recv(&mut self, option: Option<CallOptions>) -> Result<bool, Status> {
-        // try getting records from server
-        let maybe_result_set = match self.streaming.message().await {
-            Ok(s) => s,
-            Err(e) => {
-                if !self.reader.can_resume() {
-                    return Err(e);
+        loop {
+            // try getting records from server
+            let maybe_result_set = match self.streaming.message().await {
+                Ok(s) => s,
+                Err(e) => {
+                    self.resume(e, option.clone()).await?;
+                    continue;
+                }
+            };
+            self.retry = None;
+
+            match maybe_result_set {
+                Some(result_set) => {
+                    //if resume_token changes set new resume_token
+                    let resume_token = result_set.resume_token.clone();
+                    let flush = self.buffer.push(result_set) || !self.reader.enable_resume();
+                    if !resume_token.is_empty() {
+                        self.reader.update_token(resume_token);
+                    }
+                    if flush && self.flush()? {
+                        return Ok(true);
+                    }
                 }
-                tracing::debug!("streaming error: {}. resume reading by resume_token", e);
-                let result = self.reader.read(self.session, option).await?;
-                self.streaming = result.into_inner();
-                self.streaming.message().await?
+                None => return self.flush(),
             }
-        };
+        }
+    }
 
-        match maybe_result_set {
-            Some(result_set) => {
-                if result_set.values.is_empty() {
-                    return Ok(false);
-                }
-                //if resume_token changes set new resume_token
-                if !result_set.resume_token.is_empty() {
-                    self.reader.update_token(result_set.resume_token);
+    /// resume restarts the stream from the last resume token if the error is retryable.
+    /// The result sets received after the token are discarded since the server sends them again.
+    async fn resume(&mut self, status: Status, option: Option<CallOptions>) -> Result<(), Status> {
+        if self.buffer.overflowed || !self.reader.can_resume() {
+            return Err(status);
+        }
+        tracing::debug!("streaming error: {}. resume reading by resume_token", status);
+        self.retry.get_or_insert_with(resume_retry).next(status).await?;
+        self.buffer.clear();
+        let result = self.reader.read(self.session, option).await?;
+        self.streaming = result.into_inner();
+        Ok(())
+    }
+
+    /// flush adds the buffered result sets to the rows to yield.
+    fn flush(&mut self) -> Result<bool, Status> {
+        let mut added = false;
+        for result_set in self.buffer.take() {
+            if let Some(cell) = &self.read_timestamp {
+                let ts = result_set
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.transaction.as_ref())
+                    .and_then(|t| t.read_timestamp.as_ref());
+                if let Some(ts) = ts {
+                    let nanos = ts.seconds as i128 * 1_000_000_000 + ts.nanos as i128;
+                    if let Ok(v) = OffsetDateTime::from_unix_timestamp_nanos(nanos) {
+                        let _ = cell.set(v);
+                    }
                 }
-                self.rs
-                    .add(result_set.metadata, result_set.values, result_set.chunked_value)
             }
-            None => Ok(false),
+            if result_set.values.is_empty() && result_set.metadata.is_none() {
+                continue;
+            }
+            added |= self
+                .rs
+                .add(result_set.metadata, result_set.values, result_set.chunked_value)?;
         }
+        Ok(added)
     }
 
     /// Return metadata for all columns
@@ -287,9 +405,9 @@ mod tests {
     use prost_types::Value;
 
     use google_cloud_googleapis::spanner::v1::struct_type::Field;
-    use google_cloud_googleapis::spanner::v1::{ResultSetMetadata, StructType};
+    use google_cloud_googleapis::spanner::v1::{PartialResultSet, ResultSetMetadata, StructType};
 
-    use crate::reader::ResultSet;
+    use crate::reader::{ResultSet, ResumeBuffer, MAX_BYTES_BETWEEN_RESUME_TOKENS};
     use crate::row::{Row, TryFromValue};
     use crate::statement::ToKind;
 
@@ -706,4 +824,43 @@ mod tests {
         );
         assert!(rs.next().is_none());
     }
+
+    fn partial_result_set(v: &str, resume_token: &[u8]) -> PartialResultSet {
+        PartialResultSet {
+            values: vec![value(v)],
+            resume_token: resume_token.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resume_buffer() {
+        let mut buffer = ResumeBuffer::default();
+        assert!(!buffer.push(partial_result_set("a", &[])));
+        assert!(!buffer.push(partial_result_set("b", &[])));
+        assert!(buffer.push(partial_result_set("c", &[1])));
+        let values: Vec<Value> = buffer.take().into_iter().flat_map(|v| v.values).collect();
+        assert_eq!(values, vec![value("a"), value("b"), value("c")]);
+
+        // the result sets after the last resume token are received again on resume
+        assert!(!buffer.push(partial_result_set("d", &[])));
+        buffer.clear();
+        assert!(buffer.take().is_empty());
+        assert!(!buffer.overflowed);
+    }
+
+    #[test]
+    fn test_resume_buffer_overflow() {
+        let mut buffer = ResumeBuffer::default();
+        let large = "x".repeat(MAX_BYTES_BETWEEN_RESUME_TOKENS);
+        assert!(buffer.push(partial_result_set(&large, &[])));
+        assert!(buffer.overflowed);
+        assert_eq!(buffer.take().len(), 1);
+
+        // the rows after the last resume token are already yielded until the next resume token
+        assert!(buffer.push(partial_result_set("a", &[])));
+        assert!(buffer.overflowed);
+        assert!(buffer.push(partial_result_set("b", &[1])));
+        assert!(!buffer.overflowed);
+    }
 }
@@ -1,8 +1,11 @@
 use std::iter::Take;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use google_cloud_gax::grpc::{Code, Status};
-use google_cloud_gax::retry::{CodeCondition, Condition, ExponentialBackoff, Retry, RetrySetting, TryAs};
+use google_cloud_gax::retry::{
+    backoff, retry_delay, CodeCondition, Condition, ExponentialBackoff, Retry, RetrySetting, TryAs,
+};
 
 pub struct TransactionCondition<E>
 where
@@ -35,11 +38,28 @@ where
     }
 }
 
+/// TransactionRetry retries the transaction aborted by the server.
+/// ```
+/// use std::time::Duration;
+/// use google_cloud_gax::grpc::Code;
+/// use google_cloud_gax::retry::RetrySetting;
+/// use google_cloud_spanner::client::Error;
+/// use google_cloud_spanner::retry::TransactionRetry;
+///
+/// let setting = RetrySetting::default()
+///     .with_codes(vec![Code::Aborted])
+///     .with_from_millis(2)
+///     .with_factor(10)
+///     .with_max_delay(Duration::from_secs(5))
+///     .with_jitter(true)
+///     .with_take(20);
+/// let retry = TransactionRetry::<Error>::with_setting(setting);
+/// ```
 pub struct TransactionRetry<E>
 where
     E: TryAs<Status>,
 {
-    strategy: Take<ExponentialBackoff>,
+    strategy: Box<dyn Iterator<Item = Duration> + Send + Sync>,
     condition: TransactionCondition<E>,
 }
 
@@ -47,6 +67,8 @@ impl<E> TransactionRetry<E>
 where
     E: TryAs<Status>,
 {
+    /// Waits before the next attempt if the error is retryable, otherwise returns the error.
+    /// It waits at least the delay suggested by the server in the RetryInfo of the status, same as the other retries.
     pub async fn next(&mut self, status: E) -> Result<(), E> {
         let duration = if self.condition.should_retry(&status) {
             self.strategy.next()
@@ -55,6 +77,10 @@ where
         };
         match duration {
             Some(duration) => {
+                let duration = match status.try_as().and_then(retry_delay) {
+                    Some(suggested) => duration.max(suggested),
+                    None => duration,
+                };
                 tokio::time::sleep(duration).await;
                 Ok(())
             }
@@ -63,10 +89,27 @@ where
     }
 
     pub fn new() -> Self {
-        let setting = TransactionRetrySetting::default();
-        let strategy = <TransactionRetrySetting as Retry<E, TransactionCondition<E>>>::strategy(&setting);
+        Self::with_setting(TransactionRetrySetting::default().inner)
+    }
+
+    /// Creates the retry with the backoff, jitter and max attempts of the setting.
+    /// Only `Code::Aborted` is retried and the other codes of the setting are ignored,
+    /// since a read-write transaction failed with them may have been committed and could be applied twice.
+    pub fn with_setting(setting: RetrySetting) -> Self {
+        let codes = setting
+            .codes
+            .iter()
+            .copied()
+            .filter(|code| *code == Code::Aborted)
+            .collect();
+        Self::with_codes(setting.with_codes(codes))
+    }
+
+    /// Creates the retry which retries every code of the setting.
+    pub(crate) fn with_codes(setting: RetrySetting) -> Self {
+        let setting = TransactionRetrySetting { inner: setting };
         Self {
-            strategy,
+            strategy: Box::new(backoff::<Status, CodeCondition, RetrySetting>(&setting.inner)),
             condition: setting.condition(),
         }
     }
@@ -106,6 +149,18 @@ where
             tracing::trace!("transaction retry fn, error: {:?}, duration: {:?}", status, duration);
         };
     }
+
+    fn jitter(&self) -> bool {
+        self.inner.jitter
+    }
+
+    fn max_elapsed(&self) -> Option<std::time::Duration> {
+        self.inner.max_elapsed
+    }
+
+    fn timeout(&self) -> Option<std::time::Duration> {
+        self.inner.timeout
+    }
 }
 
 impl TransactionRetrySetting {
@@ -127,11 +182,32 @@ impl Default for TransactionRetrySetting {
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
+    use google_cloud_gax::grpc::metadata::MetadataValue;
     use google_cloud_gax::grpc::{Code, Status};
-    use google_cloud_gax::retry::{Condition, Retry};
+    use google_cloud_gax::retry::{Condition, Retry, RetrySetting};
+    use prost::Message;
 
     use crate::client::Error;
-    use crate::retry::TransactionRetrySetting;
+    use crate::retry::{TransactionRetry, TransactionRetrySetting};
+
+    #[derive(Clone, PartialEq, Message)]
+    struct RetryInfo {
+        #[prost(message, optional, tag = "1")]
+        retry_delay: Option<prost_types::Duration>,
+    }
+
+    fn aborted(delay: Duration) -> Error {
+        let info = RetryInfo {
+            retry_delay: Some(delay.try_into().unwrap()),
+        };
+        let mut status = Status::aborted("aborted");
+        status
+            .metadata_mut()
+            .insert_bin("google.rpc.retryinfo-bin", MetadataValue::from_bytes(&info.encode_to_vec()));
+        Error::GRPC(status)
+    }
 
     #[test]
     fn test_transaction_condition() {
@@ -142,4 +218,50 @@ mod tests {
         let err = &Error::GRPC(Status::new(Code::Aborted, ""));
         assert!(default.condition().should_retry(err));
     }
+
+    #[tokio::test]
+    async fn test_transaction_retry_with_setting() {
+        let setting = RetrySetting::default().with_from_millis(1).with_take(2);
+        let mut retry = TransactionRetry::<Error>::with_setting(setting);
+        assert!(retry.next(Error::GRPC(Status::new(Code::Aborted, ""))).await.is_ok());
+        assert!(retry.next(Error::GRPC(Status::new(Code::Aborted, ""))).await.is_ok());
+        assert!(retry.next(Error::GRPC(Status::new(Code::Aborted, ""))).await.is_err());
+        assert!(retry.next(Error::GRPC(Status::new(Code::NotFound, ""))).await.is_err());
+
+        // Only Code::Aborted is retried even if the setting has the other codes.
+        let setting = RetrySetting::default().with_from_millis(1);
+        let mut retry = TransactionRetry::<Error>::with_setting(setting);
+        assert!(retry
+            .next(Error::GRPC(Status::new(Code::Unavailable, "")))
+            .await
+            .is_err());
+        assert!(retry.next(Error::GRPC(Status::new(Code::Unknown, ""))).await.is_err());
+        assert!(retry.next(Error::GRPC(Status::new(Code::Aborted, ""))).await.is_ok());
+
+        // Without Code::Aborted nothing is retried.
+        let setting = RetrySetting::default()
+            .with_from_millis(1)
+            .with_codes(vec![Code::Unavailable]);
+        let mut retry = TransactionRetry::<Error>::with_setting(setting);
+        assert!(retry.next(Error::GRPC(Status::new(Code::Aborted, ""))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_retry_delay() {
+        let setting = RetrySetting::default()
+            .with_from_millis(100)
+            .with_max_delay(Duration::from_millis(100));
+
+        // The backoff is kept when the server suggests a shorter delay.
+        let mut retry = TransactionRetry::<Error>::with_setting(setting.clone());
+        let start = Instant::now();
+        assert!(retry.next(aborted(Duration::from_millis(1))).await.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // The delay suggested by the server is kept when it is longer than the backoff.
+        let mut retry = TransactionRetry::<Error>::with_setting(setting);
+        let start = Instant::now();
+        assert!(retry.next(aborted(Duration::from_millis(300))).await.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
 }
@@ -12,10 +12,10 @@ use time::macros::format_description;
 use time::{Date, OffsetDateTime};
 
 use google_cloud_googleapis::spanner::v1::struct_type::Field;
-use google_cloud_googleapis::spanner::v1::StructType;
+use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeCode};
 
 use crate::bigdecimal::{BigDecimal, ParseBigDecimalError};
-use crate::value::CommitTimestamp;
+use crate::value::{CommitTimestamp, Json, PgNumeric, PENDING_COMMIT_TIMESTAMP};
 
 #[derive(Clone)]
 pub struct Row {
@@ -52,6 +52,12 @@ pub enum Error {
     BigDecimalParseError(String, #[source] ParseBigDecimalError),
     #[error("Failed to parse as Prost Timestamp field={0}")]
     ProstTimestampParseError(String, #[source] ::prost_types::TimestampError),
+    #[error("Commit timestamp is not assigned yet: field={0}")]
+    PendingCommitTimestamp(String),
+    #[error("Failed to parse as Json field={0}")]
+    JsonParseError(String, #[source] serde_json::Error),
+    #[error("Column type mismatch: field={0}, expected={1}")]
+    TypeMismatch(String, &'static str),
 }
 
 impl Row {
@@ -72,6 +78,32 @@ impl Row {
     {
         self.column(index(&self.index, column_name)?)
     }
+
+    /// column_names returns the names of the columns in the order of the result set.
+    pub fn column_names(&self) -> Vec<&str> {
+        self.fields.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    /// fields returns the column metadata including the type of each column.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// columns returns an iterator over the (name, value) pairs of the row.
+    /// This is useful when the columns are not known statically.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.fields.iter().map(|f| f.name.as_str()).zip(self.values.iter())
+    }
+
+    /// to_json converts the row into a JSON object keyed by the column names according to the type of each column.
+    /// INT64 is a number, JSON is the parsed document, ARRAY is an array and STRUCT is an object.
+    /// BYTES (base64), TIMESTAMP (RFC3339), DATE and NUMERIC are strings as they are encoded by Spanner.
+    /// NULL is null.
+    /// The column without name is keyed by `_` and its position such as `_0`,
+    /// and the duplicated name is suffixed with `_1`, `_2` and so on to keep every column.
+    pub fn to_json(&self) -> serde_json::Value {
+        to_json_object(&self.fields, &self.values)
+    }
 }
 
 //don't use TryFrom trait to avoid the conflict
@@ -156,11 +188,24 @@ impl TryFromValue for f64 {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
             Kind::NumberValue(s) => Ok(*s),
+            // NaN and infinity are encoded as strings.
+            Kind::StringValue(s) if s == "NaN" => Ok(f64::NAN),
+            Kind::StringValue(s) if s == "Infinity" => Ok(f64::INFINITY),
+            Kind::StringValue(s) if s == "-Infinity" => Ok(f64::NEG_INFINITY),
             v => kind_to_error(v, field),
         }
     }
 }
 
+impl TryFromValue for f32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        if !is_float32(field) {
+            return Err(Error::TypeMismatch(field.name.to_string(), "FLOAT32"));
+        }
+        <f64 as TryFromValue>::try_from(item, field).map(|v| v as f32)
+    }
+}
+
 impl TryFromValue for bool {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -173,6 +218,9 @@ impl TryFromValue for bool {
 impl TryFromValue for OffsetDateTime {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
+            Kind::StringValue(s) if s == PENDING_COMMIT_TIMESTAMP => {
+                Err(Error::PendingCommitTimestamp(field.name.to_string()))
+            }
             Kind::StringValue(s) => {
                 Ok(OffsetDateTime::parse(s, &Rfc3339).map_err(|e| Error::DateParseError(field.name.to_string(), e))?)
             }
@@ -193,9 +241,12 @@ impl TryFromValue for ::prost_types::Timestamp {
 
 impl TryFromValue for CommitTimestamp {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
-        Ok(CommitTimestamp {
-            timestamp: TryFromValue::try_from(item, field)?,
-        })
+        match as_ref(item, field)? {
+            Kind::StringValue(s) if s == PENDING_COMMIT_TIMESTAMP => Ok(CommitTimestamp::new()),
+            _ => Ok(CommitTimestamp {
+                timestamp: TryFromValue::try_from(item, field)?,
+            }),
+        }
     }
 }
 
@@ -231,6 +282,26 @@ impl TryFromValue for BigDecimal {
     }
 }
 
+impl TryFromValue for PgNumeric {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) if s == "NaN" => Ok(PgNumeric::NaN),
+            _ => Ok(PgNumeric::Number(TryFromValue::try_from(item, field)?)),
+        }
+    }
+}
+
+impl TryFromValue for Json {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => Ok(Json(
+                serde_json::from_str(s).map_err(|e| Error::JsonParseError(field.name.to_string(), e))?,
+            )),
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
 impl TryFromValue for String {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -245,13 +316,17 @@ where
     T: TryFromStruct,
 {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
-        let maybe_array = match field.r#type.as_ref() {
+        let tp = match field.r#type.as_ref() {
             None => return Err(Error::StructParseError(field.name.to_string(), "field type must not be none")),
-            Some(tp) => tp.array_element_type.as_ref(),
+            Some(tp) => tp,
         };
-        let maybe_struct_type = match maybe_array {
-            None => return Err(Error::StructParseError(field.name.to_string(), "array must not be none")),
-            Some(tp) => tp.struct_type.as_ref(),
+        // A nested struct field carries its own struct type, while an element of ARRAY<STRUCT> refers to the array element type.
+        let maybe_struct_type = match tp.struct_type.as_ref() {
+            Some(struct_type) => Some(struct_type),
+            None => match tp.array_element_type.as_ref() {
+                None => return Err(Error::StructParseError(field.name.to_string(), "array must not be none")),
+                Some(tp) => tp.struct_type.as_ref(),
+            },
         };
         let struct_type = match maybe_struct_type {
             None => {
@@ -328,6 +403,74 @@ pub fn kind_to_error<'a, T>(v: &'a value::Kind, field: &'a Field) -> Result<T, E
     Err(Error::KindMismatch(field.name.to_string(), actual))
 }
 
+/// is_float32 returns true if the column or the element of the array column is FLOAT32.
+fn is_float32(field: &Field) -> bool {
+    match field.r#type.as_ref() {
+        Some(t) if t.code == TypeCode::Array as i32 => t
+            .array_element_type
+            .as_ref()
+            .is_some_and(|t| t.code == TypeCode::Float32 as i32),
+        Some(t) => t.code == TypeCode::Float32 as i32,
+        None => false,
+    }
+}
+
+fn to_json_object(fields: &[Field], values: &[Value]) -> serde_json::Value {
+    let mut object = serde_json::Map::with_capacity(fields.len());
+    for (i, (f, v)) in fields.iter().zip(values.iter()).enumerate() {
+        let name = if f.name.is_empty() {
+            format!("_{i}")
+        } else {
+            f.name.to_string()
+        };
+        let mut key = name.clone();
+        let mut suffix = 1;
+        while object.contains_key(&key) {
+            key = format!("{name}_{suffix}");
+            suffix += 1;
+        }
+        object.insert(key, to_json(v, f.r#type.as_ref()));
+    }
+    serde_json::Value::Object(object)
+}
+
+fn to_json(value: &Value, tp: Option<&Type>) -> serde_json::Value {
+    let code = tp.map(|t| t.code).unwrap_or_default();
+    match value.kind.as_ref() {
+        None | Some(Kind::NullValue(_)) => serde_json::Value::Null,
+        Some(Kind::BoolValue(v)) => serde_json::Value::Bool(*v),
+        Some(Kind::NumberValue(v)) => serde_json::Number::from_f64(*v)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        // an INT64 out of the range is never returned, but the string is kept as it is just in case.
+        Some(Kind::StringValue(v)) if code == TypeCode::Int64 as i32 => v
+            .parse::<i64>()
+            .map(serde_json::Value::from)
+            .unwrap_or_else(|_| serde_json::Value::String(v.to_string())),
+        Some(Kind::StringValue(v)) if code == TypeCode::Json as i32 => {
+            serde_json::from_str(v).unwrap_or_else(|_| serde_json::Value::String(v.to_string()))
+        }
+        Some(Kind::StringValue(v)) => serde_json::Value::String(v.to_string()),
+        Some(Kind::ListValue(v)) if code == TypeCode::Struct as i32 => {
+            let fields = tp
+                .and_then(|t| t.struct_type.as_ref())
+                .map(|s| s.fields.as_slice())
+                .unwrap_or_default();
+            to_json_object(fields, &v.values)
+        }
+        Some(Kind::ListValue(v)) => {
+            let element_type = tp.and_then(|t| t.array_element_type.as_deref());
+            serde_json::Value::Array(v.values.iter().map(|v| to_json(v, element_type)).collect())
+        }
+        Some(Kind::StructValue(v)) => serde_json::Value::Object(
+            v.fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), to_json(v, None)))
+                .collect(),
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -335,15 +478,18 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
 
+    use prost_types::value::Kind;
     use prost_types::{Timestamp, Value};
+    use time::format_description::well_known::Rfc3339;
     use time::OffsetDateTime;
 
     use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::{Type, TypeCode};
 
     use crate::bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive, Zero};
     use crate::row::{Error, Row, Struct as RowStruct, TryFromStruct};
     use crate::statement::{Kinds, ToKind, ToStruct, Types};
-    use crate::value::CommitTimestamp;
+    use crate::value::{CommitTimestamp, Json, PgNumeric};
 
     struct TestStruct {
         pub struct_field: String,
@@ -388,6 +534,60 @@ mod tests {
         }
     }
 
+    struct NestedStruct {
+        pub name: String,
+        pub inner: TestStruct,
+        pub nullable_inner: Option<TestStruct>,
+    }
+
+    impl TryFromStruct for NestedStruct {
+        fn try_from_struct(s: RowStruct<'_>) -> Result<Self, Error> {
+            Ok(NestedStruct {
+                name: s.column_by_name("name")?,
+                inner: s.column_by_name("inner")?,
+                nullable_inner: s.column_by_name("nullable_inner")?,
+            })
+        }
+    }
+
+    impl ToStruct for NestedStruct {
+        fn to_kinds(&self) -> Kinds {
+            vec![
+                ("name", self.name.to_kind()),
+                ("inner", self.inner.to_kind()),
+                ("nullable_inner", self.nullable_inner.to_kind()),
+            ]
+        }
+
+        fn get_types() -> Types {
+            vec![
+                ("name", String::get_type()),
+                ("inner", TestStruct::get_type()),
+                ("nullable_inner", Option::<TestStruct>::get_type()),
+            ]
+        }
+    }
+
+    fn new_row(columns: Vec<(&str, Type, Kind)>) -> Row {
+        let index = columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _, _))| (name.to_string(), i))
+            .collect();
+        let fields = columns
+            .iter()
+            .map(|(name, tp, _)| Field {
+                name: name.to_string(),
+                r#type: Some(tp.clone()),
+            })
+            .collect();
+        let values = columns
+            .into_iter()
+            .map(|(_, _, kind)| Value { kind: Some(kind) })
+            .collect();
+        Row::new(Arc::new(index), Arc::new(fields), values)
+    }
+
     #[test]
     fn test_try_from() {
         let mut index = HashMap::new();
@@ -490,5 +690,234 @@ mod tests {
             BigDecimal::zero()
         );
         assert_eq!(format!("{}", struct_data[1].prost_timestamp), "2027-02-19T07:23:59Z");
+
+        assert_eq!(row.column_names(), vec!["value", "array", "struct", "decimal", "timestamp"]);
+        assert_eq!(row.fields()[1].r#type, Some(Vec::<i64>::get_type()));
+        let columns: Vec<(&str, &Value)> = row.columns().collect();
+        assert_eq!(columns.len(), 5);
+        assert_eq!(columns[0].0, "value");
+        assert_eq!(columns[0].1.kind, Some("aaa".to_kind()));
+    }
+
+    #[test]
+    fn test_try_from_nested_struct() {
+        let now = OffsetDateTime::now_utc();
+        let inner = |name: &str| TestStruct {
+            struct_field: name.to_string(),
+            struct_field_time: now,
+            commit_timestamp: CommitTimestamp { timestamp: now },
+            big_decimal: BigDecimal::from_str("1.5").unwrap(),
+            prost_timestamp: Timestamp::from_str("2024-01-01T01:13:45Z").unwrap(),
+        };
+        let nested = vec![
+            NestedStruct {
+                name: "first".to_string(),
+                inner: inner("aaa"),
+                nullable_inner: Some(inner("bbb")),
+            },
+            NestedStruct {
+                name: "second".to_string(),
+                inner: inner("ccc"),
+                nullable_inner: None,
+            },
+        ];
+        let row = new_row(vec![("nested", Vec::<NestedStruct>::get_type(), nested.to_kind())]);
+
+        let nested = row.column_by_name::<Vec<NestedStruct>>("nested").unwrap();
+        assert_eq!(nested.len(), 2);
+        assert_eq!(nested[0].name, "first");
+        assert_eq!(nested[0].inner.struct_field, "aaa");
+        assert_eq!(nested[0].inner.struct_field_time, now);
+        assert_eq!(nested[0].nullable_inner.as_ref().unwrap().struct_field, "bbb");
+        assert_eq!(nested[1].name, "second");
+        assert_eq!(nested[1].inner.big_decimal, BigDecimal::from_str("1.5").unwrap());
+        assert!(nested[1].nullable_inner.is_none());
+    }
+
+    #[test]
+    fn test_try_from_numeric_array() {
+        let row = new_row(vec![
+            (
+                "numerics",
+                Vec::<BigDecimal>::get_type(),
+                vec![BigDecimal::from_str("1.5").unwrap(), BigDecimal::zero()].to_kind(),
+            ),
+            (
+                "nullable_numerics",
+                Vec::<Option<BigDecimal>>::get_type(),
+                vec![None, Some(BigDecimal::from_str("-2.25").unwrap())].to_kind(),
+            ),
+            (
+                "pg_numerics",
+                Vec::<PgNumeric>::get_type(),
+                vec![PgNumeric::NaN, PgNumeric::from(BigDecimal::from_str("3.125").unwrap())].to_kind(),
+            ),
+        ]);
+
+        let numerics = row.column_by_name::<Vec<BigDecimal>>("numerics").unwrap();
+        assert_eq!(numerics, vec![BigDecimal::from_str("1.5").unwrap(), BigDecimal::zero()]);
+        let nullable_numerics = row
+            .column_by_name::<Vec<Option<BigDecimal>>>("nullable_numerics")
+            .unwrap();
+        assert_eq!(nullable_numerics, vec![None, Some(BigDecimal::from_str("-2.25").unwrap())]);
+        let pg_numerics = row.column_by_name::<Vec<PgNumeric>>("pg_numerics").unwrap();
+        assert_eq!(
+            pg_numerics,
+            vec![
+                PgNumeric::NaN,
+                PgNumeric::Number(BigDecimal::from_str("3.125").unwrap())
+            ]
+        );
+        // NaN is not a valid GoogleSQL NUMERIC.
+        assert!(row.column_by_name::<Vec<BigDecimal>>("pg_numerics").is_err());
+    }
+
+    #[test]
+    fn test_try_from_json() {
+        let doc = Json(serde_json::json!({"name": "aaa", "tags": [1, 2]}));
+        let row = new_row(vec![
+            ("json", Json::get_type(), doc.to_kind()),
+            (
+                "jsons",
+                Vec::<Option<Json>>::get_type(),
+                vec![Some(doc.clone()), None].to_kind(),
+            ),
+            ("invalid", Json::get_type(), "{".to_kind()),
+        ]);
+
+        let json = row.column_by_name::<Json>("json").unwrap();
+        assert_eq!(json, doc);
+        assert_eq!(json["tags"][1], 2);
+        let jsons = row.column_by_name::<Vec<Option<Json>>>("jsons").unwrap();
+        assert_eq!(jsons, vec![Some(doc), None]);
+        match row.column_by_name::<Json>("invalid") {
+            Err(Error::JsonParseError(name, _)) => assert_eq!(name, "invalid"),
+            _ => panic!("invalid json must not be parsed"),
+        }
+    }
+
+    #[test]
+    fn test_try_from_pending_commit_timestamp() {
+        let now = OffsetDateTime::now_utc();
+        let row = new_row(vec![
+            ("pending", CommitTimestamp::get_type(), CommitTimestamp::new().to_kind()),
+            ("committed", CommitTimestamp::get_type(), now.to_kind()),
+        ]);
+
+        assert!(row.column_by_name::<CommitTimestamp>("pending").unwrap().is_pending());
+        assert!(row
+            .column_by_name::<Option<CommitTimestamp>>("pending")
+            .unwrap()
+            .unwrap()
+            .is_pending());
+        match row.column_by_name::<OffsetDateTime>("pending") {
+            Err(Error::PendingCommitTimestamp(name)) => assert_eq!(name, "pending"),
+            other => panic!("unexpected result {other:?}"),
+        }
+        match row.column_by_name::<Option<OffsetDateTime>>("pending") {
+            Err(Error::PendingCommitTimestamp(name)) => assert_eq!(name, "pending"),
+            other => panic!("unexpected result {other:?}"),
+        }
+
+        let committed = row.column_by_name::<CommitTimestamp>("committed").unwrap();
+        assert!(!committed.is_pending());
+        assert_eq!(OffsetDateTime::from(committed), now);
+        assert_eq!(row.column_by_name::<Option<OffsetDateTime>>("committed").unwrap(), Some(now));
+        let committed = row
+            .column_by_name::<Option<CommitTimestamp>>("committed")
+            .unwrap()
+            .unwrap();
+        assert_eq!(OffsetDateTime::from(committed), now);
+    }
+
+    #[test]
+    fn test_try_from_float() {
+        let row = new_row(vec![
+            ("f32", f32::get_type(), 1.5f32.to_kind()),
+            ("f32s", Vec::<f32>::get_type(), vec![0.5f32, 2.0].to_kind()),
+            ("nan", f32::get_type(), f32::NAN.to_kind()),
+            ("inf", f64::get_type(), f64::INFINITY.to_kind()),
+            ("neg_inf", f64::get_type(), f64::NEG_INFINITY.to_kind()),
+            ("invalid", f64::get_type(), "abc".to_kind()),
+        ]);
+
+        assert_eq!(f32::get_type().code, TypeCode::Float32 as i32);
+        assert_eq!(row.column_by_name::<f32>("f32").unwrap(), 1.5);
+        assert_eq!(row.column_by_name::<Option<f32>>("f32").unwrap(), Some(1.5));
+        assert!(row.column_by_name::<f32>("nan").unwrap().is_nan());
+        assert_eq!(row.column_by_name::<f64>("inf").unwrap(), f64::INFINITY);
+        assert_eq!(row.column_by_name::<f64>("neg_inf").unwrap(), f64::NEG_INFINITY);
+        assert!(row.column_by_name::<f64>("invalid").is_err());
+        assert_eq!(row.column_by_name::<Vec<f32>>("f32s").unwrap(), vec![0.5, 2.0]);
+        // FLOAT64 is not narrowed to f32.
+        match row.column_by_name::<f32>("inf") {
+            Err(Error::TypeMismatch(name, expected)) => assert_eq!((name.as_str(), expected), ("inf", "FLOAT32")),
+            other => panic!("unexpected result {other:?}"),
+        }
+        assert!(row.column_by_name::<Vec<f32>>("f32").is_err());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let now = OffsetDateTime::parse("2024-01-01T01:13:45Z", &Rfc3339).unwrap();
+        let inner = TestStruct {
+            struct_field: "aaa".to_string(),
+            struct_field_time: now,
+            commit_timestamp: CommitTimestamp { timestamp: now },
+            big_decimal: BigDecimal::from_str("1.5").unwrap(),
+            prost_timestamp: Timestamp::from_str("2024-01-01T01:13:45Z").unwrap(),
+        };
+        let row = new_row(vec![
+            ("int", i64::get_type(), i64::MAX.to_kind()),
+            ("float", f64::get_type(), 1.5f64.to_kind()),
+            ("bytes", Vec::<u8>::get_type(), vec![1u8, 2, 3].to_kind()),
+            ("timestamp", OffsetDateTime::get_type(), now.to_kind()),
+            ("null", Option::<String>::get_type(), Option::<String>::None.to_kind()),
+            ("ints", Vec::<Option<i64>>::get_type(), vec![Some(1i64), None].to_kind()),
+            ("json", Json::get_type(), Json(serde_json::json!({"tags": [1, 2]})).to_kind()),
+            ("structs", Vec::<TestStruct>::get_type(), vec![inner].to_kind()),
+        ]);
+
+        assert_eq!(
+            row.to_json(),
+            serde_json::json!({
+                "int": i64::MAX,
+                "float": 1.5,
+                "bytes": "AQID",
+                "timestamp": "2024-01-01T01:13:45Z",
+                "null": null,
+                "ints": [1, null],
+                "json": {"tags": [1, 2]},
+                "structs": [{
+                    "struct_field": "aaa",
+                    "struct_field_time": "2024-01-01T01:13:45Z",
+                    "commit_timestamp": "2024-01-01T01:13:45Z",
+                    "big_decimal": "1.5",
+                    "prost_timestamp": "2024-01-01T01:13:45Z",
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_json_column_names() {
+        // SELECT 1, 2, a.Id, b.Id, 3 AS Id_1 FROM ...
+        let row = new_row(vec![
+            ("", i64::get_type(), 1i64.to_kind()),
+            ("", i64::get_type(), 2i64.to_kind()),
+            ("Id", i64::get_type(), 3i64.to_kind()),
+            ("Id", i64::get_type(), 4i64.to_kind()),
+            ("Id_1", i64::get_type(), 5i64.to_kind()),
+        ]);
+        assert_eq!(
+            row.to_json(),
+            serde_json::json!({
+                "_0": 1,
+                "_1": 2,
+                "Id": 3,
+                "Id_1": 4,
+                "Id_1_1": 5,
+            })
+        );
     }
 }
@@ -60,6 +60,16 @@ impl SessionHandle {
         }
     }
 
+    /// Executes `SELECT 1` on the session to keep it alive on the server.
+    /// Cloud Spanner deletes sessions that have been idle for more than an hour.
+    pub async fn ping(&mut self) -> Result<(), Status> {
+        let request = ping_query_request(self.session.name.clone());
+        let result = self.spanner_client.execute_sql(request, None).await;
+        self.invalidate_if_needed(result).await?;
+        self.last_pong_at = Instant::now();
+        Ok(())
+    }
+
     async fn delete(&mut self) {
         self.valid = false;
         let session_name = &self.session.name;
@@ -400,6 +410,10 @@ pub struct SessionConfig {
     /// However, if the number of sessions is less than or equal to min_opened, it will not be discarded.
     pub idle_timeout: Duration,
 
+    /// session_alive_trust_duration is the keep-alive interval of idle sessions.
+    /// Sessions neither used nor pinged for longer than this value are pinged with `SELECT 1`
+    /// by the health check task, so that the server does not delete them after an hour of inactivity.
+    /// The sum of this value and refresh_interval should be less than one hour.
     pub session_alive_trust_duration: Duration,
 
     /// session_get_timeout is the maximum value of the waiting time that occurs when retrieving from the connection pool when there is no idle session.
@@ -421,7 +435,7 @@ impl Default for SessionConfig {
             max_idle: 300,
             inc_step: 25,
             idle_timeout: Duration::from_secs(30 * 60),
-            session_alive_trust_duration: Duration::from_secs(55 * 60),
+            session_alive_trust_duration: Duration::from_secs(50 * 60),
             session_get_timeout: Duration::from_secs(1),
             refresh_interval: Duration::from_secs(5 * 60),
         }
@@ -594,15 +608,16 @@ async fn health_check(
             }
         };
 
-        let request = ping_query_request(s.session.name.clone());
-        match s.spanner_client.execute_sql(request, None).await {
+        match s.ping().await {
             Ok(_) => {
                 s.last_checked_at = now;
                 s.last_pong_at = now;
                 sessions.recycle(s);
             }
             Err(_) => {
-                s.delete().await;
+                if s.valid {
+                    s.delete().await;
+                }
                 sessions.recycle(s);
             }
         }
@@ -743,6 +758,32 @@ mod tests {
         cancel.cancel();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_ping() {
+        let cm = ConnectionManager::new(
+            4,
+            &Environment::Emulator("localhost:9010".to_string()),
+            "",
+            &ConnectionOptions::default(),
+        )
+        .await
+        .unwrap();
+        let config = SessionConfig {
+            min_opened: 1,
+            max_opened: 1,
+            ..Default::default()
+        };
+        let sm = SessionManager::new(DATABASE, cm, config).await.unwrap();
+        let mut session = sm.get().await.unwrap();
+        let before = session.last_pong_at;
+        session.ping().await.unwrap();
+        assert!(session.last_pong_at > before);
+        assert!(session.valid);
+        drop(session);
+        sm.close().await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[serial]
     async fn test_health_check_not_checked() {
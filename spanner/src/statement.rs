@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, HashMap};
 use base64::prelude::*;
 use prost_types::value::Kind;
 use prost_types::value::Kind::StringValue;
-use prost_types::{value, ListValue, Struct, Value};
+use prost_types::{value, ListValue, Value};
 use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
 use time::{Date, OffsetDateTime};
@@ -12,7 +12,7 @@ use google_cloud_googleapis::spanner::v1::struct_type::Field;
 use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeAnnotationCode, TypeCode};
 
 use crate::bigdecimal::BigDecimal;
-use crate::value::CommitTimestamp;
+use crate::value::{CommitTimestamp, Json, PgNumeric, PENDING_COMMIT_TIMESTAMP};
 
 /// A Statement is a SQL query with named parameters.
 ///
@@ -45,6 +45,7 @@ impl Statement {
 
     /// add_params add the bind parameter.
     /// Implement the ToKind trait to use non-predefined types.
+    /// A type implementing the ToStruct trait is bound as a STRUCT parameter, e.g. `WHERE a = @pair.a AND b = @pair.b`.
     pub fn add_param<T>(&mut self, name: &str, value: &T)
     where
         T: ToKind,
@@ -57,6 +58,21 @@ impl Statement {
             },
         );
     }
+
+    /// add_param_typed add the bind parameter with the explicit type.
+    /// Use this when the type can't be derived from `T`, e.g. binding a raw `Kind` or a NULL of an annotated type.
+    pub fn add_param_typed<T>(&mut self, name: &str, value: &T, param_type: Type)
+    where
+        T: ToKind + ?Sized,
+    {
+        self.param_types.insert(name.to_string(), param_type);
+        self.params.insert(
+            name.to_string(),
+            Value {
+                kind: Some(value.to_kind()),
+            },
+        );
+    }
 }
 
 pub fn single_type<T>(code: T) -> Type
@@ -67,7 +83,6 @@ where
         code: code.into(),
         array_element_type: None,
         struct_type: None,
-        //TODO support PG Numeric
         type_annotation: TypeAnnotationCode::Unspecified.into(),
         proto_type_fqn: "".to_string(),
     }
@@ -83,6 +98,8 @@ pub trait ToKind {
 pub type Kinds = Vec<(&'static str, Kind)>;
 pub type Types = Vec<(&'static str, Type)>;
 
+/// ToStruct converts a value into a STRUCT.
+/// The fields of `to_kinds` must be in the same order as `get_types`.
 pub trait ToStruct {
     fn to_kinds(&self) -> Kinds;
     fn get_types() -> Types
@@ -135,13 +152,31 @@ impl ToKind for i64 {
 
 impl ToKind for f64 {
     fn to_kind(&self) -> Kind {
-        value::Kind::NumberValue(*self)
+        // NaN and infinity are encoded as strings.
+        if self.is_nan() {
+            "NaN".to_kind()
+        } else if *self == f64::INFINITY {
+            "Infinity".to_kind()
+        } else if *self == f64::NEG_INFINITY {
+            "-Infinity".to_kind()
+        } else {
+            value::Kind::NumberValue(*self)
+        }
     }
     fn get_type() -> Type {
         single_type(TypeCode::Float64)
     }
 }
 
+impl ToKind for f32 {
+    fn to_kind(&self) -> Kind {
+        (*self as f64).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Float32)
+    }
+}
+
 impl ToKind for bool {
     fn to_kind(&self) -> Kind {
         value::Kind::BoolValue(*self)
@@ -173,7 +208,7 @@ impl ToKind for OffsetDateTime {
 
 impl ToKind for CommitTimestamp {
     fn to_kind(&self) -> Kind {
-        "spanner.commit_timestamp()".to_kind()
+        PENDING_COMMIT_TIMESTAMP.to_kind()
     }
     fn get_type() -> Type {
         single_type(TypeCode::Timestamp)
@@ -207,6 +242,30 @@ impl ToKind for BigDecimal {
     }
 }
 
+impl ToKind for PgNumeric {
+    fn to_kind(&self) -> Kind {
+        match self {
+            PgNumeric::Number(v) => v.to_kind(),
+            PgNumeric::NaN => "NaN".to_kind(),
+        }
+    }
+    fn get_type() -> Type {
+        Type {
+            type_annotation: TypeAnnotationCode::PgNumeric.into(),
+            ..single_type(TypeCode::Numeric)
+        }
+    }
+}
+
+impl ToKind for Json {
+    fn to_kind(&self) -> Kind {
+        self.0.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Json)
+    }
+}
+
 impl ToKind for ::prost_types::Timestamp {
     fn to_kind(&self) -> Kind {
         // The protobuf timestamp type should be formatted in RFC3339
@@ -227,12 +286,11 @@ impl<T> ToKind for T
 where
     T: ToStruct,
 {
+    /// A STRUCT is encoded as the list of the field values in the order of the fields.
     fn to_kind(&self) -> Kind {
-        let mut fields = BTreeMap::<String, Value>::default();
-        self.to_kinds().into_iter().for_each(|e| {
-            fields.insert(e.0.into(), Value { kind: Some(e.1) });
-        });
-        Kind::StructValue(Struct { fields })
+        Kind::ListValue(ListValue {
+            values: self.to_kinds().into_iter().map(|e| Value { kind: Some(e.1) }).collect(),
+        })
     }
     fn get_type() -> Type {
         Type {
@@ -311,7 +369,9 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::statement::ToKind;
+    use crate::statement::{single_type, Kinds, Statement, ToKind, ToStruct, Types};
+    use crate::value::{Json, PgNumeric};
+    use google_cloud_googleapis::spanner::v1::{TypeAnnotationCode, TypeCode};
     use prost_types::value::Kind;
     use time::OffsetDateTime;
 
@@ -328,4 +388,77 @@ mod test {
         // Prost's Timestamp type and OffsetDateTime should have the same representation in spanner
         assert_eq!(prost_types::Timestamp::get_type(), OffsetDateTime::get_type());
     }
+
+    #[test]
+    fn pg_numeric_to_kind_works() {
+        let tp = PgNumeric::get_type();
+        assert_eq!(tp.code, TypeCode::Numeric as i32);
+        assert_eq!(tp.type_annotation, TypeAnnotationCode::PgNumeric as i32);
+        assert_eq!(PgNumeric::NaN.to_kind(), Kind::StringValue("NaN".to_string()));
+    }
+
+    #[test]
+    fn json_to_kind_works() {
+        assert_eq!(Json::get_type().code, TypeCode::Json as i32);
+        let json = Json(serde_json::json!({"a": [1, "b"]}));
+        assert_eq!(json.to_kind(), Kind::StringValue(r#"{"a":[1,"b"]}"#.to_string()));
+
+        let mut stmt = Statement::new("SELECT * FROM User WHERE Attributes IN UNNEST(@p1)");
+        stmt.add_param("p1", &vec![json]);
+        let array_type = stmt.param_types["p1"].array_element_type.as_ref().unwrap();
+        assert_eq!(array_type.code, TypeCode::Json as i32);
+    }
+
+    #[test]
+    fn null_param_has_type() {
+        let mut stmt = Statement::new("SELECT * FROM User WHERE NullableString = @p1 OR NullableJson = @p2");
+        stmt.add_param("p1", &None::<String>);
+        stmt.add_param_typed("p2", &None::<String>, single_type(TypeCode::Json));
+        assert_eq!(stmt.param_types["p1"].code, TypeCode::String as i32);
+        assert_eq!(stmt.param_types["p2"].code, TypeCode::Json as i32);
+        for name in ["p1", "p2"] {
+            assert!(matches!(stmt.params[name].kind, Some(Kind::NullValue(_))));
+        }
+    }
+
+    struct Pair {
+        a: i64,
+        b: String,
+    }
+
+    impl ToStruct for Pair {
+        fn to_kinds(&self) -> Kinds {
+            vec![("a", self.a.to_kind()), ("b", self.b.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("a", i64::get_type()), ("b", String::get_type())]
+        }
+    }
+
+    #[test]
+    fn struct_param_works() {
+        let mut stmt = Statement::new("SELECT * FROM User WHERE (a, b) = (@pair.a, @pair.b)");
+        stmt.add_param(
+            "pair",
+            &Pair {
+                a: 1,
+                b: "b".to_string(),
+            },
+        );
+        let tp = &stmt.param_types["pair"];
+        assert_eq!(tp.code, TypeCode::Struct as i32);
+        let fields = &tp.struct_type.as_ref().unwrap().fields;
+        assert_eq!(fields[0].name, "a");
+        assert_eq!(fields[0].r#type.as_ref().unwrap().code, TypeCode::Int64 as i32);
+        assert_eq!(fields[1].name, "b");
+        assert_eq!(fields[1].r#type.as_ref().unwrap().code, TypeCode::String as i32);
+        match stmt.params["pair"].kind.as_ref().unwrap() {
+            Kind::ListValue(v) => assert_eq!(
+                v.values.iter().map(|v| v.kind.clone().unwrap()).collect::<Vec<_>>(),
+                vec!["1".to_kind(), "b".to_kind()]
+            ),
+            kind => panic!("struct must be a list value: {kind:?}"),
+        }
+    }
 }
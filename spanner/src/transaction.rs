@@ -1,16 +1,21 @@
 use std::ops::DerefMut;
 use std::sync::atomic::AtomicI64;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use prost_types::Struct;
+use time::OffsetDateTime;
 
 use google_cloud_gax::grpc::Status;
 use google_cloud_gax::retry::RetrySetting;
+use google_cloud_googleapis::spanner::v1::read_request::OrderBy;
 use google_cloud_googleapis::spanner::v1::request_options::Priority;
 use google_cloud_googleapis::spanner::v1::{
     execute_sql_request::QueryMode, execute_sql_request::QueryOptions as ExecuteQueryOptions, ExecuteSqlRequest,
     ReadRequest, RequestOptions, TransactionSelector,
 };
 
+use crate::apiv1::spanner_client::default_setting;
 use crate::key::{Key, KeySet};
 use crate::reader::{Reader, RowIterator, StatementReader, TableReader};
 use crate::row::Row;
@@ -22,6 +27,19 @@ pub struct CallOptions {
     /// Priority is the RPC priority to use for the read operation.
     pub priority: Option<Priority>,
     pub retry: Option<RetrySetting>,
+    /// The gRPC deadline of each RPC, sent to the server with the request metadata.
+    /// For streaming reads and queries, the deadline covers the whole stream.
+    pub timeout: Option<Duration>,
+}
+
+impl CallOptions {
+    /// Returns the retry setting with the timeout applied.
+    pub(crate) fn retry_setting(&self) -> Option<RetrySetting> {
+        match self.timeout {
+            Some(timeout) => Some(self.retry.clone().unwrap_or_else(default_setting).with_timeout(timeout)),
+            None => self.retry.clone(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -32,6 +50,10 @@ pub struct ReadOptions {
     pub index: String,
     /// The maximum number of rows to read. A limit value less than 1 means no limit.
     pub limit: i64,
+    /// The order in which the rows are returned.
+    /// `OrderBy::PrimaryKey` returns the rows in the key order of the table or the index,
+    /// so that the first rows of a key range can be read with `limit`.
+    pub order_by: OrderBy,
     pub call_options: CallOptions,
 }
 
@@ -40,6 +62,7 @@ impl Default for ReadOptions {
         ReadOptions {
             index: "".to_string(),
             limit: 0,
+            order_by: OrderBy::Unspecified,
             call_options: CallOptions::default(),
         }
     }
@@ -103,6 +126,10 @@ pub struct Transaction {
     // for returning ownership of session on before destroy
     pub(crate) sequence_number: AtomicI64,
     pub(crate) transaction_selector: TransactionSelector,
+    /// Priority applied to the requests in this transaction when CallOptions::priority is not specified.
+    pub(crate) default_priority: Option<Priority>,
+    /// The read timestamp returned in the metadata of the first read or query.
+    pub(crate) read_timestamp: Arc<OnceLock<OffsetDateTime>>,
 }
 
 impl Transaction {
@@ -144,16 +171,21 @@ impl Transaction {
             partition_token: vec![],
             seqno: 0,
             query_options: options.optimizer_options,
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: Transaction::create_request_options(
+                options.call_options.priority.or(self.default_priority),
+            ),
             data_boost_enabled: false,
             directed_read_options: None,
         };
+        let read_timestamp = self.read_timestamp.clone();
         let session = self.session.as_mut().unwrap().deref_mut();
         let reader = StatementReader {
             enable_resume: options.enable_resume,
             request,
         };
-        RowIterator::new(session, reader, Some(options.call_options)).await
+        Ok(RowIterator::new(session, reader, Some(options.call_options))
+            .await?
+            .with_read_timestamp(read_timestamp))
     }
 
     /// read returns a RowIterator for reading multiple rows from the database.
@@ -204,16 +236,21 @@ impl Transaction {
             limit: options.limit,
             resume_token: vec![],
             partition_token: vec![],
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: Transaction::create_request_options(
+                options.call_options.priority.or(self.default_priority),
+            ),
             data_boost_enabled: false,
-            order_by: 0,
+            order_by: options.order_by.into(),
             directed_read_options: None,
             lock_hint: 0,
         };
 
+        let read_timestamp = self.read_timestamp.clone();
         let session = self.as_mut_session();
         let reader = TableReader { request };
-        RowIterator::new(session, reader, Some(options.call_options)).await
+        Ok(RowIterator::new(session, reader, Some(options.call_options))
+            .await?
+            .with_read_timestamp(read_timestamp))
     }
 
     /// read returns a RowIterator for reading multiple rows from the database.
@@ -63,11 +63,31 @@ impl ReadOnlyTransaction {
                         mode: Some(transaction_options::Mode::ReadOnly(tb.into())),
                     })),
                 },
+                default_priority: None,
+                read_timestamp: Default::default(),
             },
             rts: None,
         })
     }
 
+    /// read_timestamp returns the timestamp at which the reads of this transaction are performed.
+    /// For the single-use transaction, it is available after the first read or query receives the result,
+    /// which is useful to know the timestamp chosen by the bounded staleness.
+    /// ```
+    /// use google_cloud_spanner::client::{Client, Error};
+    /// use google_cloud_spanner::key::Key;
+    ///
+    /// async fn run(client: Client) -> Result<(), Error> {
+    ///     let mut tx = client.single().await?;
+    ///     let row = tx.read_row("Guild", &["GuildID"], Key::new(&"guild1")).await?;
+    ///     let read_timestamp = tx.read_timestamp();
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_timestamp(&self) -> Option<OffsetDateTime> {
+        self.rts.or_else(|| self.base_tx.read_timestamp.get().copied())
+    }
+
     /// begin starts a snapshot read-only Transaction on Cloud Spanner.
     pub async fn begin(
         mut session: ManagedSession,
@@ -96,6 +116,8 @@ impl ReadOnlyTransaction {
                         transaction_selector: TransactionSelector {
                             selector: Some(transaction_selector::Selector::Id(tx.id)),
                         },
+                        default_priority: options.priority,
+                        read_timestamp: Default::default(),
                     },
                     rts: Some(OffsetDateTime::from(st)),
                 })
@@ -185,7 +207,7 @@ impl BatchReadOnlyTransaction {
         let result = match self
             .as_mut_session()
             .spanner_client
-            .partition_read(request, ro.call_options.retry)
+            .partition_read(request, ro.call_options.retry_setting())
             .await
         {
             Ok(r) => Ok(r
@@ -246,7 +268,7 @@ impl BatchReadOnlyTransaction {
         let result = match self
             .as_mut_session()
             .spanner_client
-            .partition_query(request.clone(), qo.call_options.retry.clone())
+            .partition_query(request.clone(), qo.call_options.retry_setting())
             .await
         {
             Ok(r) => Ok(r
@@ -14,11 +14,30 @@ use google_cloud_googleapis::spanner::v1::{
     ResultSetStats, RollbackRequest, TransactionOptions, TransactionSelector,
 };
 
+use crate::mutation::{mutation_count, split_mutations, BatchOptions};
+use crate::reader::{Reader, RowIterator, StatementReader};
 use crate::session::ManagedSession;
 use crate::statement::Statement;
 use crate::transaction::{CallOptions, QueryOptions, Transaction};
 use crate::value::Timestamp;
 
+/// CommitChunkedError is the error of `ReadWriteTransaction::commit_chunked`.
+#[derive(thiserror::Error, Debug)]
+pub enum CommitChunkedError<E> {
+    /// Nothing is committed, so the transaction can be retried.
+    #[error("{0}")]
+    NotCommitted(#[source] E),
+    /// A follow-up commit failed after the preceding chunks were committed.
+    /// The transaction must not be retried, otherwise the committed chunks are applied twice.
+    #[error("{} chunks were committed before the failure: {error}", commit_timestamps.len())]
+    PartiallyCommitted {
+        /// The commit timestamps of the committed chunks.
+        commit_timestamps: Vec<Option<Timestamp>>,
+        #[source]
+        error: E,
+    },
+}
+
 #[derive(Clone, Default)]
 pub struct CommitOptions {
     pub return_commit_stats: bool,
@@ -153,6 +172,8 @@ impl ReadWriteTransaction {
                 transaction_selector: TransactionSelector {
                     selector: Some(transaction_selector::Selector::Id(tx.id.clone())),
                 },
+                default_priority: options.priority,
+                read_timestamp: Default::default(),
             },
             tx_id: tx.id,
             wb: vec![],
@@ -163,6 +184,13 @@ impl ReadWriteTransaction {
         self.wb.extend_from_slice(&ms)
     }
 
+    /// mutation_count returns the estimated number of mutations buffered by buffer_write,
+    /// computed as rows × columns for each buffered Mutation.
+    /// Use this to flush or split the work before exceeding the per-commit mutation limit.
+    pub fn mutation_count(&self) -> usize {
+        self.wb.iter().map(mutation_count).sum()
+    }
+
     pub async fn update(&mut self, stmt: Statement) -> Result<i64, Status> {
         self.update_with_option(stmt, QueryOptions::default()).await
     }
@@ -180,19 +208,82 @@ impl ReadWriteTransaction {
             partition_token: vec![],
             seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
             query_options: options.optimizer_options,
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: Transaction::create_request_options(
+                options.call_options.priority.or(self.default_priority),
+            ),
             directed_read_options: None,
         };
 
         let session = self.as_mut_session();
         let result = session
             .spanner_client
-            .execute_sql(request, options.call_options.retry)
+            .execute_sql(request, options.call_options.retry_setting())
             .await;
         let response = session.invalidate_if_needed(result).await?;
         Ok(extract_row_count(response.into_inner().stats))
     }
 
+    /// update_returning executes a DML statement with a `THEN RETURN` clause and
+    /// returns a RowIterator over the rows affected by the statement.
+    /// ```
+    /// use google_cloud_spanner::client::{Client, Error};
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// async fn run(client: Client) -> Result<(), Error> {
+    ///     let (_, updated) = client.read_write_transaction(|tx| {
+    ///         Box::pin(async move {
+    ///             let mut stmt = Statement::new("UPDATE User SET Level = Level + 1 WHERE UserId = @UserId THEN RETURN Level");
+    ///             stmt.add_param("UserId", &"user1");
+    ///             let mut iter = tx.update_returning(stmt).await?;
+    ///             let mut levels = vec![];
+    ///             while let Some(row) = iter.next().await? {
+    ///                 levels.push(row.column_by_name::<i64>("Level")?);
+    ///             }
+    ///             Ok::<Vec<i64>, Error>(levels)
+    ///         })
+    ///     }).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_returning(&mut self, stmt: Statement) -> Result<RowIterator<'_, impl Reader>, Status> {
+        self.update_returning_with_option(stmt, QueryOptions::default()).await
+    }
+
+    /// update_returning_with_option executes a DML statement with a `THEN RETURN` clause and
+    /// returns a RowIterator over the rows affected by the statement.
+    ///
+    /// The statement is never resumed on a streaming error regardless of `QueryOptions::enable_resume`,
+    /// since re-executing a DML statement is not idempotent.
+    pub async fn update_returning_with_option(
+        &mut self,
+        stmt: Statement,
+        options: QueryOptions,
+    ) -> Result<RowIterator<'_, impl Reader>, Status> {
+        let request = ExecuteSqlRequest {
+            session: self.get_session_name(),
+            transaction: Some(self.transaction_selector.clone()),
+            sql: stmt.sql.to_string(),
+            data_boost_enabled: false,
+            params: Some(prost_types::Struct { fields: stmt.params }),
+            param_types: stmt.param_types,
+            resume_token: vec![],
+            query_mode: options.mode.into(),
+            partition_token: vec![],
+            seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
+            query_options: options.optimizer_options,
+            request_options: Transaction::create_request_options(
+                options.call_options.priority.or(self.default_priority),
+            ),
+            directed_read_options: None,
+        };
+        let reader = StatementReader {
+            enable_resume: false,
+            request,
+        };
+        let session = self.as_mut_session().deref_mut();
+        RowIterator::new(session, reader, Some(options.call_options)).await
+    }
+
     pub async fn batch_update(&mut self, stmt: Vec<Statement>) -> Result<Vec<i64>, Status> {
         self.batch_update_with_option(stmt, QueryOptions::default()).await
     }
@@ -206,7 +297,9 @@ impl ReadWriteTransaction {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
             seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: Transaction::create_request_options(
+                options.call_options.priority.or(self.default_priority),
+            ),
             statements: stmt
                 .into_iter()
                 .map(|x| execute_batch_dml_request::Statement {
@@ -220,7 +313,7 @@ impl ReadWriteTransaction {
         let session = self.as_mut_session();
         let result = session
             .spanner_client
-            .execute_batch_dml(request, options.call_options.retry)
+            .execute_batch_dml(request, options.call_options.retry_setting())
             .await;
         let response = session.invalidate_if_needed(result).await?;
         Ok(response
@@ -252,12 +345,94 @@ impl ReadWriteTransaction {
                         return Err(err);
                     }
                 }
-                let _ = self.rollback(opt.call_options.retry).await;
+                let _ = self.rollback(opt.call_options.retry_setting()).await;
                 Err(err)
             }
         }
     }
 
+    /// commit_chunked is the same as end except that the buffered mutations are split
+    /// by the BatchOptions. The first chunk is committed with the statements and the reads of
+    /// this transaction, and each of the rest is committed in its own follow-up transaction.
+    /// It returns the commit timestamps of all the commits.
+    ///
+    /// Note that this is NOT atomic across the commits. If a follow-up commit fails, the mutations
+    /// of the preceding commits are already applied and the rest are not applied.
+    /// In that case `CommitChunkedError::PartiallyCommitted` is returned with the commit timestamps of
+    /// the committed chunks, and the transaction must NOT be retried not to apply them twice.
+    /// Only `CommitChunkedError::NotCommitted` can be retried.
+    /// ```
+    /// use google_cloud_spanner::client::{Client, Error};
+    /// use google_cloud_spanner::key::all_keys;
+    /// use google_cloud_spanner::mutation::{update, BatchOptions};
+    /// use google_cloud_spanner::retry::TransactionRetry;
+    /// use google_cloud_spanner::transaction_rw::CommitChunkedError;
+    ///
+    /// async fn run(client: Client) -> Result<(), Error> {
+    ///     let retry = &mut TransactionRetry::new();
+    ///     loop {
+    ///         let tx = &mut client.begin_read_write_transaction().await?;
+    ///         let result: Result<(), Error> = async {
+    ///             let mut reader = tx.read("UserItem", &["UserId", "ItemId", "Quantity"], all_keys()).await?;
+    ///             let mut ms = vec![];
+    ///             while let Some(row) = reader.next().await? {
+    ///                 let user_id = row.column_by_name::<String>("UserId")?;
+    ///                 let item_id = row.column_by_name::<i64>("ItemId")?;
+    ///                 let quantity = row.column_by_name::<i64>("Quantity")? + 1;
+    ///                 ms.push(update("UserItem", &["UserId", "ItemId", "Quantity"], &[&user_id, &item_id, &quantity]));
+    ///             }
+    ///             tx.buffer_write(ms);
+    ///             Ok(())
+    ///         }
+    ///         .await;
+    ///         match tx.commit_chunked(result, None, &BatchOptions::default()).await {
+    ///             Ok((_commit_timestamps, success)) => return Ok(success),
+    ///             Err(CommitChunkedError::NotCommitted(err)) => retry.next(err).await?,
+    ///             // Some chunks are already committed, never retry the transaction.
+    ///             Err(CommitChunkedError::PartiallyCommitted { error, .. }) => return Err(error),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn commit_chunked<S, E>(
+        &mut self,
+        result: Result<S, E>,
+        options: Option<CommitOptions>,
+        batch_options: &BatchOptions,
+    ) -> Result<(Vec<Option<Timestamp>>, S), CommitChunkedError<E>>
+    where
+        E: TryAs<Status> + From<Status>,
+    {
+        let mut chunks = split_mutations(std::mem::take(&mut self.wb), batch_options).into_iter();
+        self.wb = chunks.next().unwrap_or_default();
+        let (commit_timestamp, success) = self
+            .end(result, options.clone())
+            .await
+            .map_err(CommitChunkedError::NotCommitted)?;
+
+        let mut opt = options.unwrap_or_default();
+        if opt.call_options.priority.is_none() {
+            opt.call_options.priority = self.default_priority;
+        }
+        let mut commit_timestamps = vec![commit_timestamp];
+        for chunk in chunks {
+            let tx = commit_request::Transaction::SingleUseTransaction(TransactionOptions {
+                exclude_txn_from_change_streams: false,
+                mode: Some(transaction_options::Mode::ReadWrite(transaction_options::ReadWrite::default())),
+            });
+            match commit(self.as_mut_session(), chunk, tx, opt.clone()).await {
+                Ok(cr) => commit_timestamps.push(cr.commit_timestamp.map(|e| e.into())),
+                Err(status) => {
+                    return Err(CommitChunkedError::PartiallyCommitted {
+                        commit_timestamps,
+                        error: status.into(),
+                    })
+                }
+            }
+        }
+        Ok((commit_timestamps, success))
+    }
+
     pub(crate) async fn finish<T, E>(
         &mut self,
         result: Result<T, E>,
@@ -287,14 +462,14 @@ impl ReadWriteTransaction {
                 let status = match err.try_as() {
                     Some(status) => status,
                     None => {
-                        let _ = self.rollback(opt.call_options.retry).await;
+                        let _ = self.rollback(opt.call_options.retry_setting()).await;
                         return Err((err, self.take_session()));
                     }
                 };
                 match status.code() {
                     Code::Aborted => Err((err, self.take_session())),
                     _ => {
-                        let _ = self.rollback(opt.call_options.retry).await;
+                        let _ = self.rollback(opt.call_options.retry_setting()).await;
                         Err((err, self.take_session()))
                     }
                 }
@@ -302,7 +477,10 @@ impl ReadWriteTransaction {
         }
     }
 
-    pub(crate) async fn commit(&mut self, options: CommitOptions) -> Result<CommitResponse, Status> {
+    pub(crate) async fn commit(&mut self, mut options: CommitOptions) -> Result<CommitResponse, Status> {
+        if options.call_options.priority.is_none() {
+            options.call_options.priority = self.default_priority;
+        }
         let tx_id = self.tx_id.clone();
         let mutations = self.wb.to_vec();
         let session = self.as_mut_session();
@@ -337,7 +515,7 @@ pub(crate) async fn commit(
     };
     let result = session
         .spanner_client
-        .commit(request, commit_options.call_options.retry)
+        .commit(request, commit_options.call_options.retry_setting())
         .await;
     let response = session.invalidate_if_needed(result).await;
     match response {
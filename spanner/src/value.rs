@@ -4,7 +4,9 @@ use std::time::Duration;
 use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as InternalTimestampBound;
 use google_cloud_googleapis::spanner::v1::transaction_options::ReadOnly;
 
-#[derive(Clone, PartialEq, Eq)]
+use crate::bigdecimal::BigDecimal;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Timestamp {
     /// Represents seconds of UTC time since Unix epoch
     /// 1970-01-01T00:00:00Z. Must be from 0001-01-01T00:00:00Z to
@@ -35,6 +37,22 @@ impl From<prost_types::Timestamp> for Timestamp {
     }
 }
 
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(t: time::OffsetDateTime) -> Self {
+        Timestamp {
+            seconds: t.unix_timestamp(),
+            nanos: t.nanosecond() as i32,
+        }
+    }
+}
+
+/// The placeholder value that Spanner replaces with the commit timestamp of the transaction.
+pub(crate) const PENDING_COMMIT_TIMESTAMP: &str = "spanner.commit_timestamp()";
+
+/// CommitTimestamp is written as the placeholder that Spanner replaces with the commit timestamp.
+/// When the placeholder itself is read back (e.g. from the mutation value of a struct),
+/// it is decoded as the pending commit timestamp, with or without `Option`.
+/// Decoding it as `OffsetDateTime` or `Option<OffsetDateTime>` fails with `PendingCommitTimestamp`.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub struct CommitTimestamp {
     pub(crate) timestamp: time::OffsetDateTime,
@@ -44,6 +62,11 @@ impl CommitTimestamp {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns true if the commit timestamp has not been assigned by Spanner yet.
+    pub fn is_pending(&self) -> bool {
+        self.timestamp == time::OffsetDateTime::UNIX_EPOCH
+    }
 }
 
 impl Default for CommitTimestamp {
@@ -68,6 +91,44 @@ impl From<CommitTimestamp> for time::OffsetDateTime {
     }
 }
 
+/// PgNumeric is the NUMERIC type of the PostgreSQL dialect database, which can be `NaN` unlike `BigDecimal`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PgNumeric {
+    Number(BigDecimal),
+    NaN,
+}
+
+impl From<BigDecimal> for PgNumeric {
+    fn from(v: BigDecimal) -> Self {
+        PgNumeric::Number(v)
+    }
+}
+
+/// Json is the JSON type of Cloud Spanner.
+/// The value is sent and received as the string form of the JSON document.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Json(pub serde_json::Value);
+
+impl From<serde_json::Value> for Json {
+    fn from(v: serde_json::Value) -> Self {
+        Json(v)
+    }
+}
+
+impl From<Json> for serde_json::Value {
+    fn from(v: Json) -> Self {
+        v.0
+    }
+}
+
+impl Deref for Json {
+    type Target = serde_json::Value;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[derive(Clone)]
 pub struct TimestampBound {
     inner: InternalTimestampBound,
@@ -89,12 +150,16 @@ impl TimestampBound {
             inner: InternalTimestampBound::MaxStaleness(d.try_into().unwrap()),
         }
     }
-    pub fn min_read_timestamp(t: Timestamp) -> Self {
+    /// min_read_timestamp reads at a timestamp not older than `t`, such as a known commit timestamp.
+    pub fn min_read_timestamp(t: impl Into<Timestamp>) -> Self {
+        let t: Timestamp = t.into();
         TimestampBound {
             inner: InternalTimestampBound::MinReadTimestamp(t.into()),
         }
     }
-    pub fn read_timestamp(t: Timestamp) -> Self {
+    /// read_timestamp reads at exactly `t`, so that multiple reads see the same consistent snapshot.
+    pub fn read_timestamp(t: impl Into<Timestamp>) -> Self {
+        let t: Timestamp = t.into();
         TimestampBound {
             inner: InternalTimestampBound::ReadTimestamp(t.into()),
         }
@@ -109,3 +174,28 @@ impl From<TimestampBound> for ReadOnly {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as InternalTimestampBound;
+    use google_cloud_googleapis::spanner::v1::transaction_options::ReadOnly;
+
+    use crate::value::TimestampBound;
+
+    #[test]
+    fn test_read_timestamp() {
+        let t = datetime!(2024-01-02 03:04:05.123456789 UTC);
+        let expected = prost_types::Timestamp {
+            seconds: t.unix_timestamp(),
+            nanos: 123456789,
+        };
+
+        let ro = ReadOnly::from(TimestampBound::read_timestamp(t));
+        assert_eq!(ro.timestamp_bound, Some(InternalTimestampBound::ReadTimestamp(expected)));
+
+        let ro = ReadOnly::from(TimestampBound::min_read_timestamp(t));
+        assert_eq!(ro.timestamp_bound, Some(InternalTimestampBound::MinReadTimestamp(expected)));
+    }
+}
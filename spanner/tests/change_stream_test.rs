@@ -168,6 +168,7 @@ async fn test_read_change_stream() {
     let db = format!("projects/{}/instances/test-instance/databases/local-database", project);
     let admin_client = admin::client::Client::new(AdminClientConfig {
         environment: create_environment().await,
+        quota_project: None,
     })
     .await
     .unwrap();
@@ -0,0 +1,29 @@
+#![cfg(feature = "emulator")]
+
+use serial_test::serial;
+use time::OffsetDateTime;
+
+use google_cloud_spanner::client::{Client, ClientConfig};
+use google_cloud_spanner::statement::Statement;
+
+#[tokio::test]
+#[serial]
+async fn test_setup_emulator() {
+    std::env::set_var("SPANNER_EMULATOR_HOST", "localhost:9010");
+    let id = OffsetDateTime::now_utc().unix_timestamp_nanos() % 100000;
+    let database = format!("projects/local-project/instances/test-setup{id}/databases/db{id}");
+    let ddl = ["CREATE TABLE Tbl (ID STRING(MAX)) PRIMARY KEY(ID)"];
+
+    Client::setup_emulator(&database, "emulator-config", &ddl)
+        .await
+        .unwrap();
+    // The instance and the database already exist.
+    Client::setup_emulator(&database, "emulator-config", &ddl)
+        .await
+        .unwrap();
+
+    let client = Client::new(database, ClientConfig::default()).await.unwrap();
+    let mut tx = client.single().await.unwrap();
+    let mut rows = tx.query(Statement::new("SELECT ID FROM Tbl")).await.unwrap();
+    assert!(rows.next().await.unwrap().is_none());
+}
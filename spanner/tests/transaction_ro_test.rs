@@ -6,10 +6,13 @@ use serial_test::serial;
 use time::{Duration, OffsetDateTime};
 
 use common::*;
-use google_cloud_spanner::key::Key;
+use google_cloud_googleapis::spanner::v1::read_request::OrderBy;
+use google_cloud_spanner::key::{Key, KeyRange, RangeKind};
 use google_cloud_spanner::row::Row;
 use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner::transaction::{CallOptions, QueryOptions, ReadOptions};
 use google_cloud_spanner::transaction_ro::ReadOnlyTransaction;
+use google_cloud_spanner::value::TimestampBound;
 
 mod common;
 
@@ -346,6 +349,97 @@ async fn test_read_multi_row() {
     assert_eq!(2, all_rows(row).await.unwrap().len());
 }
 
+#[tokio::test]
+#[serial]
+async fn test_read_with_limit() {
+    //set up test data
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_limit_{}", now.unix_timestamp());
+    let mut mutations = vec![create_user_mutation(&user_id, &now)];
+    for item_id in [3, 1, 2] {
+        mutations.push(create_user_item_mutation(&user_id, item_id));
+    }
+    let data_client = create_data_client().await;
+    let _ = data_client.apply(mutations).await.unwrap();
+
+    // test
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let range = KeyRange::new(
+        Key::composite(&[&user_id]),
+        Key::composite(&[&user_id]),
+        RangeKind::ClosedClosed,
+    );
+    let options = ReadOptions {
+        limit: 2,
+        order_by: OrderBy::PrimaryKey,
+        ..Default::default()
+    };
+    let iter = tx
+        .read_with_option("UserItem", &["ItemId"], range, options)
+        .await
+        .unwrap();
+    let item_ids: Vec<i64> = all_rows(iter)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.column_by_name("ItemId").unwrap())
+        .collect();
+    assert_eq!(item_ids, vec![1, 2]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_single_read_timestamp() {
+    //set up test data
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_rts_{}", now.unix_timestamp());
+    let data_client = create_data_client().await;
+    let _ = data_client
+        .apply(vec![create_user_mutation(&user_id, &now)])
+        .await
+        .unwrap();
+
+    // test
+    let tb = TimestampBound::max_staleness(std::time::Duration::from_secs(10));
+    let mut tx = data_client.single_with_timestamp_bound(tb).await.unwrap();
+    assert!(tx.read_timestamp().is_none());
+    // The row may not be visible at the timestamp chosen by the bounded staleness.
+    let _ = tx.read_row("User", &["UserId"], Key::new(&user_id)).await.unwrap();
+    let read_timestamp = tx.read_timestamp().unwrap();
+    assert!(read_timestamp <= OffsetDateTime::now_utc());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_query_with_null_param() {
+    //set up test data
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_null_param_{}", now.unix_timestamp());
+    let data_client = create_data_client().await;
+    let _ = data_client
+        .apply(vec![create_user_mutation(&user_id, &now)])
+        .await
+        .unwrap();
+
+    // test
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let sql = "SELECT UserId FROM User WHERE UserId = @UserId AND (@Name IS NULL OR NullableString = @Name)";
+    let mut stmt = Statement::new(sql);
+    stmt.add_param("UserId", &user_id);
+    stmt.add_param("Name", &None::<String>);
+    assert_eq!(execute_query(&mut tx, stmt).await.len(), 1);
+
+    let mut stmt = Statement::new(sql);
+    stmt.add_param("UserId", &user_id);
+    stmt.add_param("Name", &Some("unknown".to_string()));
+    assert!(execute_query(&mut tx, stmt).await.is_empty());
+
+    let mut stmt = Statement::new("SELECT UserId FROM User WHERE UserId = @UserId AND NullableString = @Name");
+    stmt.add_param("UserId", &user_id);
+    stmt.add_param("Name", &None::<String>);
+    assert!(execute_query(&mut tx, stmt).await.is_empty());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_big_decimal() {
@@ -383,3 +477,30 @@ async fn test_big_decimal() {
         row.column::<BigDecimal>(6).unwrap().to_string()
     );
 }
+
+#[tokio::test]
+#[serial]
+async fn test_query_timeout() {
+    let data_client = create_data_client().await;
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+
+    let options = |timeout| QueryOptions {
+        call_options: CallOptions {
+            timeout: Some(timeout),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let stmt = Statement::new("SELECT 1");
+    let timeout = std::time::Duration::from_nanos(1);
+    let status = tx.query_with_option(stmt, options(timeout)).await.err().unwrap();
+    assert_eq!(status.code(), google_cloud_gax::grpc::Code::DeadlineExceeded);
+
+    let stmt = Statement::new("SELECT 1");
+    let mut iter = tx
+        .query_with_option(stmt, options(std::time::Duration::from_secs(30)))
+        .await
+        .unwrap();
+    let row = iter.next().await.unwrap().unwrap();
+    assert_eq!(row.column::<i64>(0).unwrap(), 1);
+}
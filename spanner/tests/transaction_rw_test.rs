@@ -2,7 +2,9 @@ use serial_test::serial;
 use time::OffsetDateTime;
 
 use common::*;
+use google_cloud_spanner::client::Error;
 use google_cloud_spanner::key::Key;
+use google_cloud_spanner::mutation::BatchOptions;
 use google_cloud_spanner::row::Row;
 use google_cloud_spanner::statement::Statement;
 
@@ -106,6 +108,65 @@ async fn test_rollback() {
     assert_user_row(&row, &past_user, &now, &ts);
 }
 
+#[tokio::test]
+#[serial]
+async fn test_update_returning() {
+    //set up test data
+    let now = OffsetDateTime::now_utc();
+    let data_client = create_data_client().await;
+    let past_user = format!("user_r_{}", now.unix_timestamp());
+    data_client
+        .apply(vec![create_user_mutation(&past_user, &now)])
+        .await
+        .unwrap();
+
+    //test
+    let mut tx = data_client.begin_read_write_transaction().await.unwrap();
+    let result = async {
+        let mut stmt = Statement::new("INSERT INTO UserItem (UserId,ItemId,Quantity,UpdatedAt) VALUES(@UserId,20,500,PENDING_COMMIT_TIMESTAMP()) THEN RETURN ItemId, Quantity");
+        stmt.add_param("UserId", &past_user);
+        let iter = tx.update_returning(stmt).await?;
+        all_rows(iter).await
+    }
+    .await;
+    let rows: Vec<Row> = match tx.end(result, None).await {
+        Ok(s) => s.1,
+        Err(e) => panic!("tx error {e:?}"),
+    };
+    assert_eq!(1, rows.len());
+    let row = rows.first().unwrap();
+    assert_eq!(20, row.column_by_name::<i64>("ItemId").unwrap());
+    assert_eq!(500, row.column_by_name::<i64>("Quantity").unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_commit_chunked() {
+    let now = OffsetDateTime::now_utc();
+    let data_client = create_data_client().await;
+    let user_ids: Vec<String> = (0..3)
+        .map(|i| format!("user_chunk_{}_{i}", now.unix_timestamp()))
+        .collect();
+
+    let mut tx = data_client.begin_read_write_transaction().await.unwrap();
+    for user_id in &user_ids {
+        tx.buffer_write(vec![create_user_mutation(user_id, &now)]);
+    }
+    let per_mutation = tx.mutation_count() / user_ids.len();
+    let options = BatchOptions {
+        max_mutations_per_commit: per_mutation * 2,
+        ..Default::default()
+    };
+    let (commit_timestamps, _) = tx.commit_chunked(Ok::<(), Error>(()), None, &options).await.unwrap();
+    assert_eq!(commit_timestamps.len(), 2);
+    assert!(commit_timestamps.iter().all(|v| v.is_some()));
+
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let keys: Vec<Key> = user_ids.iter().map(|v| Key::new(v)).collect();
+    let reader = tx.read("User", &user_columns(), keys).await.unwrap();
+    assert_eq!(all_rows(reader).await.unwrap().len(), user_ids.len());
+}
+
 async fn assert_data(
     user_id: &str,
     now: &OffsetDateTime,
@@ -1,13 +1,19 @@
+use std::collections::HashMap;
 use std::ops::Deref;
+use std::time::SystemTime;
 
 use ring::{rand, signature};
 
 use google_cloud_token::{NopeTokenSourceProvider, TokenSourceProvider};
 
+use crate::http::retry::RetryConfig;
 use crate::http::service_account_client::ServiceAccountClient;
 use crate::http::storage_client::StorageClient;
 use crate::sign::SignBy::PrivateKey;
-use crate::sign::{create_signed_buffer, RsaKeyPair, SignBy, SignedURLError, SignedURLOptions};
+use crate::sign::{
+    create_post_policy, create_signed_buffer, sign_hmac, signing_date, PostPolicy, PostPolicyOptions, RsaKeyPair,
+    SignBy, SignedURLError, SignedURLOptions,
+};
 
 ///
 /// #### Example building a client configuration with a custom retry strategy as middleware:
@@ -47,6 +53,14 @@ pub struct ClientConfig {
     pub default_google_access_id: Option<String>,
     pub default_sign_by: Option<SignBy>,
     pub project_id: Option<String>,
+    /// The project billed for the quota of the requests, sent as the `x-goog-user-project` header.
+    /// The `quota_project_id` of the credentials is used by default.
+    pub quota_project: Option<String>,
+    /// Retry of the idempotent requests failed with the transient errors. No retry by default.
+    pub retry: Option<RetryConfig>,
+    /// The token of the application like `my-service/1.0` appended to the `user-agent` and
+    /// `x-goog-api-client` headers, so that the requests can be distinguished in the request logs.
+    pub user_agent: Option<String>,
 }
 
 impl Default for ClientConfig {
@@ -59,6 +73,9 @@ impl Default for ClientConfig {
             default_google_access_id: None,
             default_sign_by: None,
             project_id: None,
+            quota_project: None,
+            retry: None,
+            user_agent: None,
         }
     }
 }
@@ -68,6 +85,24 @@ impl ClientConfig {
         self.token_source_provider = None;
         self
     }
+
+    /// Uses the given HTTP client for all the requests instead of the default one.
+    /// A `reqwest::Client` configured with a proxy, timeouts or custom root certificates can be passed as is.
+    /// ```
+    /// use google_cloud_storage::client::ClientConfig;
+    ///
+    /// fn config_with_proxy() -> ClientConfig {
+    ///     let http = reqwest::Client::builder()
+    ///         .proxy(reqwest::Proxy::all("http://proxy.example.com:8080").unwrap())
+    ///         .build()
+    ///         .unwrap();
+    ///     ClientConfig::default().with_http_client(http)
+    /// }
+    /// ```
+    pub fn with_http_client(mut self, value: impl Into<reqwest_middleware::ClientWithMiddleware>) -> Self {
+        self.http = Some(value.into());
+        self
+    }
 }
 
 #[cfg(feature = "auth")]
@@ -93,6 +128,7 @@ impl ClientConfig {
     }
 
     async fn with_token_source(mut self, ts: google_cloud_auth::token::DefaultTokenSourceProvider) -> Self {
+        self.quota_project = self.quota_project.or(ts.quota_project_id());
         match &ts.source_credentials {
             // Credential file is used.
             Some(cred) => {
@@ -149,8 +185,12 @@ impl Client {
             .unwrap_or_else(|| reqwest_middleware::ClientBuilder::new(reqwest::Client::default()).build());
 
         let service_account_client =
-            ServiceAccountClient::new(ts.clone(), config.service_account_endpoint.as_str(), http.clone());
-        let storage_client = StorageClient::new(ts, config.storage_endpoint.as_str(), http);
+            ServiceAccountClient::new(ts.clone(), config.service_account_endpoint.as_str(), http.clone())
+                .with_user_agent(config.user_agent.as_deref());
+        let storage_client = StorageClient::new(ts, config.storage_endpoint.as_str(), http)
+            .with_quota_project(config.quota_project)
+            .with_retry(config.retry)
+            .with_user_agent(config.user_agent.as_deref());
 
         Self {
             default_google_access_id: config.default_google_access_id,
@@ -202,40 +242,114 @@ impl Client {
         object: &str,
         google_access_id: Option<String>,
         sign_by: Option<SignBy>,
-        opts: SignedURLOptions,
+        mut opts: SignedURLOptions,
     ) -> Result<String, SignedURLError> {
-        // use the one from the options or the default one or error out
+        let (google_access_id, sign_by) = self.credentials(google_access_id, sign_by)?;
+        let start_time = *opts.start_time.get_or_insert_with(SystemTime::now);
 
-        let google_access_id = match &google_access_id {
-            Some(overwritten_gai) => overwritten_gai.to_owned(),
-            None => {
-                let default_gai = &self
-                    .default_google_access_id
-                    .clone()
-                    .ok_or(SignedURLError::InvalidOption("No default google_access_id is found"))?;
+        let (signed_buffer, mut builder) = create_signed_buffer(bucket, object, &google_access_id, &sign_by, &opts)?;
+        tracing::trace!("signed_buffer={:?}", String::from_utf8_lossy(&signed_buffer));
 
-                default_gai.to_owned()
-            }
-        };
+        let signature = self
+            .sign(&google_access_id, &sign_by, &signed_buffer, start_time)
+            .await?;
+        builder
+            .query_pairs_mut()
+            .append_pair("X-Goog-Signature", &hex::encode(signature));
+        Ok(builder.to_string())
+    }
 
-        // use the one from the options or the default one or error out
-        let sign_by = match &sign_by {
-            Some(overwritten_sign_by) => overwritten_sign_by.to_owned(),
-            None => {
-                let default_sign_by = &self
-                    .default_sign_by
-                    .clone()
-                    .ok_or(SignedURLError::InvalidOption("No default sign_by is found"))?;
+    /// Returns the URL and the form fields that allow a browser to upload
+    /// the object directly to Cloud Storage with a multipart form POST.
+    /// For more information about the POST policy, see
+    /// https://cloud.google.com/storage/docs/xml-api/post-object-forms
+    ///
+    /// ```
+    /// use google_cloud_storage::client::Client;
+    /// use google_cloud_storage::sign::{PostPolicyCondition, PostPolicyFields, PostPolicyOptions};
+    ///
+    /// async fn run(client: Client) {
+    ///     let policy = client.signed_post_policy("bucket", "file.png", None, None, PostPolicyOptions {
+    ///         fields: PostPolicyFields {
+    ///             content_type: Some("image/png".to_string()),
+    ///             ..Default::default()
+    ///         },
+    ///         conditions: vec![PostPolicyCondition::ContentLengthRange(0, 10 * 1024 * 1024)],
+    ///         ..Default::default()
+    ///     }).await.unwrap();
+    ///     // the browser posts the multipart form with policy.fields to policy.url
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn signed_post_policy(
+        &self,
+        bucket: &str,
+        object: &str,
+        google_access_id: Option<String>,
+        sign_by: Option<SignBy>,
+        mut opts: PostPolicyOptions,
+    ) -> Result<PostPolicy, SignedURLError> {
+        let (google_access_id, sign_by) = self.credentials(google_access_id, sign_by)?;
+        let start_time = *opts.start_time.get_or_insert_with(SystemTime::now);
+
+        let (policy, url, fields) = create_post_policy(bucket, object, &google_access_id, &sign_by, &opts)?;
+        let signature = self
+            .sign(&google_access_id, &sign_by, policy.as_bytes(), start_time)
+            .await?;
+
+        let mut fields: HashMap<String, String> = fields.into_iter().collect();
+        fields.insert("policy".to_string(), policy);
+        fields.insert("x-goog-signature".to_string(), hex::encode(signature));
+        Ok(PostPolicy {
+            url: url.to_string(),
+            fields,
+        })
+    }
 
-                default_sign_by.to_owned()
-            }
+    // the access id of the HMAC key is always used as the google_access_id
+    fn credentials(
+        &self,
+        google_access_id: Option<String>,
+        sign_by: Option<SignBy>,
+    ) -> Result<(String, SignBy), SignedURLError> {
+        let sign_by = self.sign_by(sign_by)?;
+        let google_access_id = match &sign_by {
+            SignBy::Hmac { access_id, .. } => access_id.to_string(),
+            _ => self.google_access_id(google_access_id)?,
         };
+        Ok((google_access_id, sign_by))
+    }
 
-        let (signed_buffer, mut builder) = create_signed_buffer(bucket, object, &google_access_id, &opts)?;
-        tracing::trace!("signed_buffer={:?}", String::from_utf8_lossy(&signed_buffer));
+    // use the one from the options or the default one or error out
+    fn google_access_id(&self, google_access_id: Option<String>) -> Result<String, SignedURLError> {
+        match google_access_id {
+            Some(overwritten_gai) => Ok(overwritten_gai),
+            None => self
+                .default_google_access_id
+                .clone()
+                .ok_or(SignedURLError::InvalidOption("No default google_access_id is found")),
+        }
+    }
+
+    // use the one from the options or the default one or error out
+    fn sign_by(&self, sign_by: Option<SignBy>) -> Result<SignBy, SignedURLError> {
+        match sign_by {
+            Some(overwritten_sign_by) => Ok(overwritten_sign_by),
+            None => self
+                .default_sign_by
+                .clone()
+                .ok_or(SignedURLError::InvalidOption("No default sign_by is found")),
+        }
+    }
 
-        // create signature
-        let signature = match &sign_by {
+    async fn sign(
+        &self,
+        google_access_id: &str,
+        sign_by: &SignBy,
+        buffer: &[u8],
+        start_time: SystemTime,
+    ) -> Result<Vec<u8>, SignedURLError> {
+        match sign_by {
             PrivateKey(private_key) => {
                 // if sign_by is a collection of private keys we check that at least one is present
                 if private_key.is_empty() {
@@ -244,27 +358,19 @@ impl Client {
                 let key_pair = &RsaKeyPair::try_from(private_key)?;
                 let mut signed = vec![0; key_pair.public().modulus_len()];
                 key_pair
-                    .sign(
-                        &signature::RSA_PKCS1_SHA256,
-                        &rand::SystemRandom::new(),
-                        signed_buffer.as_slice(),
-                        &mut signed,
-                    )
+                    .sign(&signature::RSA_PKCS1_SHA256, &rand::SystemRandom::new(), buffer, &mut signed)
                     .map_err(|e| SignedURLError::CertError(e.to_string()))?;
-                signed
+                Ok(signed)
             }
             SignBy::SignBytes => {
                 let path = format!("projects/-/serviceAccounts/{}", google_access_id);
                 self.service_account_client
-                    .sign_blob(&path, signed_buffer.as_slice())
+                    .sign_blob(&path, buffer)
                     .await
-                    .map_err(SignedURLError::SignBlob)?
+                    .map_err(SignedURLError::SignBlob)
             }
-        };
-        builder
-            .query_pairs_mut()
-            .append_pair("X-Goog-Signature", &hex::encode(signature));
-        Ok(builder.to_string())
+            SignBy::Hmac { secret, .. } => Ok(sign_hmac(secret, &signing_date(start_time.into()), buffer)),
+        }
     }
 }
 
@@ -27,7 +27,8 @@ pub struct ListBucketsRequest {
 #[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ListBucketsResponse {
-    /// The list of items.
+    /// The list of items. Empty when no bucket matches the request.
+    #[serde(default)]
     pub items: Vec<Bucket>,
     /// The continuation token, used to page through large result sets. Provide
     /// this value in a subsequent request to return the next page of results.
@@ -38,3 +39,15 @@ pub(crate) fn build(base_url: &str, client: &Client, req: &ListBucketsRequest) -
     let url = format!("{base_url}/b");
     client.get(url).query(&req)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::http::buckets::list::ListBucketsResponse;
+
+    #[test]
+    fn test_deserialize_empty_response() {
+        let response: ListBucketsResponse = serde_json::from_str(r#"{"kind":"storage#buckets"}"#).unwrap();
+        assert!(response.items.is_empty());
+        assert!(response.next_page_token.is_none());
+    }
+}
@@ -267,6 +267,10 @@ pub mod lifecycle {
             pub days_since_noncurrent_time: Option<i32>,
             pub is_live: Option<bool>,
             pub matches_storage_class: Option<Vec<String>>,
+            /// Objects having any of the prefixes are matched.
+            pub matches_prefix: Option<Vec<String>>,
+            /// Objects having any of the suffixes are matched.
+            pub matches_suffix: Option<Vec<String>>,
             #[serde(default, with = "date_format::option")]
             pub noncurrent_time_before: Option<Date>,
             pub num_newer_versions: Option<i32>,
@@ -398,6 +402,54 @@ pub struct Policy {
     pub bindings: Vec<Binding>,
     pub etag: String,
 }
+
+impl Policy {
+    /// Returns the members granted the role by the bindings without a condition.
+    pub fn members(&self, role: &str) -> Vec<&str> {
+        self.bindings
+            .iter()
+            .filter(|b| b.role == role && b.condition.is_none())
+            .flat_map(|b| b.members.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Grants the role to the member with the binding without a condition.
+    pub fn add(&mut self, role: &str, member: &str) {
+        match self
+            .bindings
+            .iter_mut()
+            .find(|b| b.role == role && b.condition.is_none())
+        {
+            Some(binding) => {
+                if !binding.members.iter().any(|m| m == member) {
+                    binding.members.push(member.to_string());
+                }
+            }
+            None => self.bindings.push(Binding {
+                role: role.to_string(),
+                members: vec![member.to_string()],
+                condition: None,
+            }),
+        }
+    }
+
+    /// Revokes the role granted to the member by the binding without a condition.
+    /// The binding is removed when the member was the last one, and the other bindings are left untouched.
+    pub fn remove(&mut self, role: &str, member: &str) {
+        let index = self
+            .bindings
+            .iter()
+            .position(|b| b.role == role && b.condition.is_none() && b.members.iter().any(|m| m == member));
+        if let Some(index) = index {
+            let binding = &mut self.bindings[index];
+            binding.members.retain(|m| m != member);
+            if binding.members.is_empty() {
+                self.bindings.remove(index);
+            }
+        }
+    }
+}
+
 /// Associates `members`, or principals, with a `role`.
 #[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -480,3 +532,53 @@ pub struct Condition {
     #[serde(default)]
     pub description: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::http::buckets::{Binding, Policy};
+
+    #[test]
+    fn test_policy_members() {
+        let mut policy = Policy::default();
+        policy.add("roles/storage.objectViewer", "user:alice@example.com");
+        policy.add("roles/storage.objectViewer", "user:bob@example.com");
+        policy.add("roles/storage.objectViewer", "user:alice@example.com");
+        policy.add("roles/storage.admin", "user:alice@example.com");
+        assert_eq!(policy.bindings.len(), 2);
+        assert_eq!(
+            policy.members("roles/storage.objectViewer"),
+            vec!["user:alice@example.com", "user:bob@example.com"]
+        );
+
+        policy.remove("roles/storage.objectViewer", "user:alice@example.com");
+        assert_eq!(policy.members("roles/storage.objectViewer"), vec!["user:bob@example.com"]);
+        policy.remove("roles/storage.admin", "user:alice@example.com");
+        assert_eq!(policy.bindings.len(), 1);
+        assert!(policy.members("roles/storage.admin").is_empty());
+    }
+
+    #[test]
+    fn test_policy_remove_keeps_other_bindings() {
+        let mut policy = Policy {
+            bindings: vec![
+                Binding {
+                    role: "roles/storage.objectViewer".to_string(),
+                    members: vec![],
+                    condition: None,
+                },
+                Binding {
+                    role: "roles/storage.admin".to_string(),
+                    members: vec!["user:alice@example.com".to_string()],
+                    condition: None,
+                },
+            ],
+            ..Default::default()
+        };
+        policy.remove("roles/storage.admin", "user:alice@example.com");
+        assert_eq!(policy.bindings.len(), 1);
+        assert_eq!(policy.bindings[0].role, "roles/storage.objectViewer");
+
+        policy.remove("roles/storage.objectViewer", "user:bob@example.com");
+        assert_eq!(policy.bindings.len(), 1);
+    }
+}
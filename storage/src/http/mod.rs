@@ -2,7 +2,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
-use reqwest::Response;
+use reqwest::{Request, Response};
 use serde::{de, Deserialize, Deserializer};
 use serde_json::Value;
 
@@ -17,9 +17,16 @@ pub mod notifications;
 pub mod object_access_controls;
 pub mod objects;
 pub mod resumable_upload_client;
+pub mod retry;
 pub mod service_account_client;
 pub mod storage_client;
 
+/// The token of the library in the `x-goog-api-client` header.
+pub(crate) const API_CLIENT: &str = "rust";
+
+/// The token of the library in the `user-agent` header.
+pub(crate) const USER_AGENT: &str = "google-cloud-storage";
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     /// An error returned from the Google Cloud Storage service.
@@ -44,6 +51,18 @@ pub enum Error {
 
     #[error("Request failed: {0} detail={1}")]
     RawResponse(reqwest::Error, String),
+
+    /// An error from writing the downloaded content.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The checksum of the downloaded content does not match the one of the object.
+    #[error("checksum mismatch: expected={0}, actual={1}")]
+    ChecksumMismatch(String, String),
+
+    /// The server ignored the range, which can't be applied to the whole content of unknown length.
+    #[error("range not applicable to the content of unknown length: {0:?}")]
+    InapplicableRange(objects::download::Range),
 }
 
 impl From<reqwest_middleware::Error> for Error {
@@ -71,6 +90,31 @@ pub(crate) async fn check_response_status(response: Response) -> Result<Response
         .unwrap_or(Error::HttpClient(error)))
 }
 
+/// The span of a request, which records the status code of the response.
+#[cfg(all(feature = "trace", feature = "google-cloud-gax"))]
+pub(crate) fn request_span(request: &Request) -> tracing::Span {
+    google_cloud_gax::http_request_span(request.method().as_str(), request.url().as_str())
+}
+
+/// Same as `google_cloud_gax::http_request_span`, for the builds without the optional `google-cloud-gax`.
+#[cfg(all(feature = "trace", not(feature = "google-cloud-gax")))]
+pub(crate) fn request_span(request: &Request) -> tracing::Span {
+    let method = request.method().as_str();
+    tracing::info_span!(
+        "http",
+        otel.name = method,
+        otel.kind = "client",
+        http.request.method = method,
+        url.full = request.url().as_str(),
+        http.response.status_code = tracing::field::Empty,
+    )
+}
+
+#[cfg(not(feature = "trace"))]
+pub(crate) fn request_span(_request: &Request) -> tracing::Span {
+    tracing::Span::none()
+}
+
 pub(crate) trait Escape {
     fn escape(&self) -> String;
 }
@@ -1,12 +1,78 @@
+use bytes::Bytes;
+use futures_util::{future, Stream, TryStreamExt};
 use reqwest_middleware::{ClientWithMiddleware as Client, RequestBuilder};
 
 use crate::http::objects::get::GetObjectRequest;
 use crate::http::Escape;
 
-#[derive(Default)]
+/// The byte range of the object to download.
+/// `Range(None, None)` (the default) downloads the whole object.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Range(pub Option<u64>, pub Option<u64>);
 
 impl Range {
+    /// The bytes from `start` to `end` inclusive.
+    pub fn new(start: u64, end: u64) -> Self {
+        Self(Some(start), Some(end))
+    }
+
+    /// The bytes from `offset` to the end of the object.
+    pub fn from(offset: u64) -> Self {
+        Self(Some(offset), None)
+    }
+
+    /// The last `length` bytes of the object.
+    pub fn suffix(length: u64) -> Self {
+        Self(None, Some(length))
+    }
+
+    /// Whether the range covers the whole object.
+    pub fn is_full(&self) -> bool {
+        self.0.is_none() && self.1.is_none()
+    }
+
+    /// Returns the start and the exclusive end of the range in the content of the given length.
+    /// The suffix range can't be located without the length of the content.
+    fn bounds(&self, len: Option<u64>) -> Option<(u64, u64)> {
+        match (self.0, self.1) {
+            (Some(from), Some(to)) => Some((from, to.saturating_add(1))),
+            (Some(from), None) => Some((from, u64::MAX)),
+            (None, Some(reverse_from)) => len.map(|len| (len.saturating_sub(reverse_from), u64::MAX)),
+            (None, None) => Some((0, u64::MAX)),
+        }
+    }
+
+    /// Applies the range to the whole content.
+    /// Used when the server ignores the Range header and responds with 200 instead of 206 Partial Content.
+    pub(crate) fn slice(&self, data: Vec<u8>) -> Vec<u8> {
+        let len = data.len() as u64;
+        let (start, end) = match self.bounds(Some(len)) {
+            Some(bounds) if !self.is_full() => bounds,
+            _ => return data,
+        };
+        let start = start.min(len) as usize;
+        let end = end.clamp(start as u64, len) as usize;
+        data[start..end].to_vec()
+    }
+
+    /// Applies the range to the whole content streamed in chunks, same as `slice`.
+    /// Returns `None` for the suffix range if the length of the content is unknown.
+    pub(crate) fn slice_stream<E>(
+        &self,
+        stream: impl Stream<Item = Result<Bytes, E>>,
+        len: Option<u64>,
+    ) -> Option<impl Stream<Item = Result<Bytes, E>>> {
+        let (start, end) = self.bounds(len)?;
+        let mut offset = 0_u64;
+        Some(stream.try_filter_map(move |chunk| {
+            let chunk_start = offset;
+            offset += chunk.len() as u64;
+            let from = (start.clamp(chunk_start, offset) - chunk_start) as usize;
+            let to = (end.clamp(chunk_start, offset) - chunk_start) as usize;
+            future::ready(Ok((from < to).then(|| chunk.slice(from..to))))
+        }))
+    }
+
     /// Range: bytes=0-1999 (first 2000 bytes)
     /// Range: bytes=-2000 (last 2000 bytes)
     /// Range: bytes=2000- (from byte 2000 to end of file)
@@ -34,3 +100,44 @@ pub(crate) fn build(base_url: &str, client: &Client, req: &GetObjectRequest, ran
         builder
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::TryStreamExt;
+
+    use crate::http::objects::download::Range;
+
+    #[test]
+    fn test_slice() {
+        let data = || b"0123456789".to_vec();
+        assert_eq!(Range::default().slice(data()), data());
+        assert_eq!(Range::new(1, 3).slice(data()), b"123");
+        assert_eq!(Range::new(8, 20).slice(data()), b"89");
+        assert_eq!(Range::from(7).slice(data()), b"789");
+        assert_eq!(Range::from(20).slice(data()), b"");
+        assert_eq!(Range::suffix(2).slice(data()), b"89");
+        assert_eq!(Range::suffix(20).slice(data()), data());
+        assert!(Range::default().is_full());
+        assert!(!Range::suffix(1).is_full());
+    }
+
+    #[tokio::test]
+    async fn test_slice_stream() {
+        let data = || {
+            let chunks = ["012", "3456", "789"].map(|v| Ok::<_, ()>(Bytes::from(v)));
+            futures_util::stream::iter(chunks)
+        };
+        let collect = |range: Range, len: Option<u64>| async move {
+            let stream = range.slice_stream(data(), len).unwrap();
+            stream.try_collect::<Vec<_>>().await.unwrap().concat()
+        };
+        assert_eq!(collect(Range::default(), None).await, b"0123456789");
+        assert_eq!(collect(Range::new(2, 7), None).await, b"234567");
+        assert_eq!(collect(Range::new(8, 20), None).await, b"89");
+        assert_eq!(collect(Range::from(4), None).await, b"456789");
+        assert_eq!(collect(Range::from(20), None).await, b"");
+        assert_eq!(collect(Range::suffix(5), Some(10)).await, b"56789");
+        assert!(Range::suffix(5).slice_stream(data(), None).is_none());
+    }
+}
@@ -35,6 +35,8 @@ pub struct GetObjectRequest {
     /// Set of properties to return. Defaults to `NO_ACL`.
     pub projection: Option<Projection>,
     /// A set of parameters common to Storage API requests concerning an object.
+    /// Required to read an object encrypted with a customer-supplied encryption key.
+    /// The same headers are sent by `download_object` and `download_streamed_object`.
     #[serde(skip_serializing)]
     pub encryption: Option<Encryption>,
 }
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
+use base64::prelude::*;
 use reqwest_middleware::RequestBuilder;
-
+use sha2::{Digest, Sha256};
 use time::OffsetDateTime;
 
 use crate::http::object_access_controls::ObjectAccessControl;
@@ -260,6 +261,16 @@ pub struct Encryption {
 }
 
 impl Encryption {
+    /// Creates the parameters for a raw 256-bit AES customer-supplied encryption key.
+    /// The key and its SHA256 hash are encoded using base64.
+    pub fn aes256(key: &[u8; 32]) -> Self {
+        Self {
+            encryption_algorithm: "AES256".to_string(),
+            encryption_key: BASE64_STANDARD.encode(key),
+            encryption_key_sha256: BASE64_STANDARD.encode(Sha256::digest(key)),
+        }
+    }
+
     pub(crate) fn with_headers(&self, builder: RequestBuilder) -> RequestBuilder {
         builder
             .header("X-Goog-Encryption-Algorithm", &self.encryption_algorithm)
@@ -267,3 +278,23 @@ impl Encryption {
             .header("X-Goog-Encryption-Key-Sha256", &self.encryption_key_sha256)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::*;
+    use sha2::{Digest, Sha256};
+
+    use crate::http::objects::Encryption;
+
+    #[test]
+    fn test_encryption_aes256() {
+        let key = [7u8; 32];
+        let encryption = Encryption::aes256(&key);
+        assert_eq!(encryption.encryption_algorithm, "AES256");
+        assert_eq!(BASE64_STANDARD.decode(&encryption.encryption_key).unwrap(), key.to_vec());
+        assert_eq!(
+            BASE64_STANDARD.decode(&encryption.encryption_key_sha256).unwrap(),
+            Sha256::digest(key).to_vec()
+        );
+    }
+}
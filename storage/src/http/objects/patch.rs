@@ -1,10 +1,52 @@
+use std::collections::HashMap;
+
 use reqwest_middleware::{ClientWithMiddleware as Client, RequestBuilder};
+use time::OffsetDateTime;
 
 use crate::http::bucket_access_controls::PredefinedBucketAcl;
-use crate::http::object_access_controls::Projection;
-use crate::http::objects::{Encryption, Object};
+use crate::http::object_access_controls::{ObjectAccessControl, Projection};
+use crate::http::objects::Encryption;
 use crate::http::Escape;
 
+/// The object metadata to update without re-uploading the data.
+/// The fields not set are left unchanged.
+#[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectPatchConfig {
+    /// Access controls on the object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acl: Option<Vec<ObjectAccessControl>>,
+    /// Cache-Control directive for the object data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<String>,
+    /// Content-Disposition of the object data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_disposition: Option<String>,
+    /// Content-Encoding of the object data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<String>,
+    /// Content-Language of the object data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_language: Option<String>,
+    /// Content-Type of the object data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// A user-specified timestamp set on an object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub custom_time: Option<OffsetDateTime>,
+    /// Whether an object is under event-based hold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_based_hold: Option<bool>,
+    /// Whether an object is under temporary hold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temporary_hold: Option<bool>,
+    /// User-provided metadata, in key/value pairs.
+    /// The given keys are added or overwritten and the other keys are left unchanged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
 /// Request message for PatchObject.
 #[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
@@ -39,7 +81,7 @@ pub struct PatchObjectRequest {
     pub projection: Option<Projection>,
     /// The Object metadata for updating.
     #[serde(skip_serializing)]
-    pub metadata: Option<Object>,
+    pub metadata: Option<ObjectPatchConfig>,
     /// A set of parameters common to Storage API requests concerning an object.
     #[serde(skip_serializing)]
     pub encryption: Option<Encryption>,
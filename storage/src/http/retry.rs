@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, Request, StatusCode};
+
+/// The query parameters which make a request idempotent by the precondition on the generation.
+const PRECONDITIONS: [&str; 2] = ["ifGenerationMatch", "ifMetagenerationMatch"];
+
+/// The query parameter which makes a DELETE request idempotent by targeting a single generation.
+const GENERATION: &str = "generation";
+
+/// RetryConfig configures the retry of the requests failed with a transient error,
+/// which are the 408, 429 and 5xx responses, the timeouts and the connection failures.
+///
+/// Only the idempotent requests are retried: GET and HEAD requests always, and the other requests,
+/// such as uploads, only when they are conditioned by `ifGenerationMatch` or `ifMetagenerationMatch`.
+/// DELETE requests are also retried when they target a `generation` of the object.
+/// The request is retried as well when the body of the response fails to be read.
+/// The request whose body is a stream is not retried since it can't be sent again.
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    /// The maximum number of attempts including the first one.
+    pub max_attempts: usize,
+    pub initial_delay: Duration,
+    /// The upper bound of each delay, including the one suggested by the `Retry-After` header.
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    /// Randomizes each delay between 50% and 150% of its value.
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(32),
+            multiplier: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub(crate) fn next_delay(&self, current: Duration) -> Duration {
+        current.mul_f64(self.multiplier.max(1.0)).min(self.max_delay)
+    }
+
+    /// wait returns the delay before the next attempt, which is the `Retry-After` of the response if any,
+    /// otherwise the current delay with jitter.
+    pub(crate) fn wait(&self, current: Duration, retry_after: Option<Duration>) -> Duration {
+        let wait = match retry_after {
+            Some(retry_after) => retry_after,
+            None if self.jitter => current.mul_f64(rand::random::<f64>() + 0.5),
+            None => current,
+        };
+        wait.min(self.max_delay)
+    }
+}
+
+pub(crate) fn is_idempotent(request: &Request) -> bool {
+    if request.method() == Method::GET || request.method() == Method::HEAD {
+        return true;
+    }
+    let delete = request.method() == Method::DELETE;
+    request
+        .url()
+        .query_pairs()
+        .any(|(k, _)| PRECONDITIONS.contains(&k.as_ref()) || (delete && k == GENERATION))
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+pub(crate) fn is_retryable_error(error: &reqwest_middleware::Error) -> bool {
+    match error {
+        reqwest_middleware::Error::Reqwest(e) => e.is_connect() || e.is_timeout(),
+        reqwest_middleware::Error::Middleware(_) => false,
+    }
+}
+
+pub(crate) fn is_retryable_body_error(error: &reqwest::Error) -> bool {
+    error.is_body() || error.is_timeout()
+}
+
+/// retry_after returns the delay in seconds of the `Retry-After` header.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use reqwest::{Method, Request, StatusCode};
+
+    use crate::http::retry::{is_idempotent, is_retryable_status, retry_after, RetryConfig};
+
+    fn request(method: Method, url: &str) -> Request {
+        Request::new(method, url.parse().unwrap())
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        let base = "https://storage.googleapis.com/upload/storage/v1/b/bucket/o";
+        assert!(is_idempotent(&request(Method::GET, base)));
+        assert!(!is_idempotent(&request(Method::POST, &format!("{base}?uploadType=media"))));
+        assert!(is_idempotent(&request(
+            Method::POST,
+            &format!("{base}?uploadType=media&ifGenerationMatch=0")
+        )));
+        assert!(is_idempotent(&request(
+            Method::PATCH,
+            &format!("{base}?ifMetagenerationMatch=3")
+        )));
+        assert!(is_idempotent(&request(Method::DELETE, &format!("{base}?generation=3"))));
+        assert!(!is_idempotent(&request(Method::DELETE, base)));
+        assert!(!is_idempotent(&request(Method::POST, &format!("{base}?generation=3"))));
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        for status in [408, 429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(StatusCode::from_u16(status).unwrap()), "{status}");
+        }
+        for status in [400, 401, 403, 404, 412] {
+            assert!(!is_retryable_status(StatusCode::from_u16(status).unwrap()), "{status}");
+        }
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let headers = |value: &'static str| HeaderMap::from_iter([(RETRY_AFTER, HeaderValue::from_static(value))]);
+        assert_eq!(retry_after(&headers("5")), Some(Duration::from_secs(5)));
+        assert_eq!(retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_wait() {
+        let config = RetryConfig {
+            max_delay: Duration::from_secs(3),
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            let wait = config.wait(Duration::from_secs(1), None);
+            assert!(
+                wait >= Duration::from_millis(500) && wait <= Duration::from_millis(1500),
+                "{wait:?}"
+            );
+        }
+        assert_eq!(
+            config.wait(Duration::from_secs(1), Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.wait(Duration::from_secs(1), Some(Duration::from_secs(3600))),
+            Duration::from_secs(3)
+        );
+
+        let config = RetryConfig {
+            jitter: false,
+            ..config
+        };
+        assert_eq!(config.wait(Duration::from_secs(1), None), Duration::from_secs(1));
+        assert_eq!(config.wait(Duration::from_secs(5), None), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let config = RetryConfig {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+            ..Default::default()
+        };
+        let mut delay = config.initial_delay;
+        let mut delays = vec![];
+        for _ in 0..4 {
+            delay = config.next_delay(delay);
+            delays.push(delay.as_millis());
+        }
+        assert_eq!(delays, vec![1000, 2000, 3000, 3000]);
+    }
+}
@@ -2,13 +2,15 @@ use google_cloud_token::TokenSource;
 use reqwest::Response;
 use std::sync::Arc;
 
-use crate::http::Error;
+use crate::http::{Error, API_CLIENT, USER_AGENT};
 
 #[derive(Clone)]
 pub struct ServiceAccountClient {
     ts: Option<Arc<dyn TokenSource>>,
     v1_endpoint: String,
     http: reqwest_middleware::ClientWithMiddleware,
+    api_client: String,
+    user_agent: String,
 }
 
 impl ServiceAccountClient {
@@ -21,9 +23,20 @@ impl ServiceAccountClient {
             ts,
             v1_endpoint: format!("{endpoint}/v1"),
             http,
+            api_client: API_CLIENT.to_string(),
+            user_agent: USER_AGENT.to_string(),
         }
     }
 
+    /// Appends the token of the application to the `user-agent` and `x-goog-api-client` headers.
+    pub(crate) fn with_user_agent(mut self, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            self.api_client = format!("{API_CLIENT} {value}");
+            self.user_agent = format!("{USER_AGENT} {value}");
+        }
+        self
+    }
+
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn sign_blob(&self, name: &str, payload: &[u8]) -> Result<Vec<u8>, Error> {
         let url = format!("{}/{}:signBlob", self.v1_endpoint, name);
@@ -32,8 +45,8 @@ impl ServiceAccountClient {
             .http
             .post(url)
             .json(&request)
-            .header("X-Goog-Api-Client", "rust")
-            .header(reqwest::header::USER_AGENT, "google-cloud-storage");
+            .header("X-Goog-Api-Client", &self.api_client)
+            .header(reqwest::header::USER_AGENT, &self.user_agent);
         let request = match &self.ts {
             Some(ts) => {
                 let token = ts.token().await.map_err(Error::TokenSource)?;
@@ -1,9 +1,15 @@
+use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
 
+use base64::prelude::*;
 use futures_util::{Stream, TryStream, TryStreamExt};
 use reqwest::header::{HeaderValue, CONTENT_LENGTH, LOCATION};
-use reqwest::{Body, Request};
+use reqwest::{Body, Request, Response, StatusCode};
 use reqwest_middleware::RequestBuilder;
+use tokio::fs::File;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::Instrument;
 
 use google_cloud_token::TokenSource;
 
@@ -58,9 +64,10 @@ use crate::http::objects::rewrite::{RewriteObjectRequest, RewriteObjectResponse}
 use crate::http::objects::upload::{UploadObjectRequest, UploadType};
 use crate::http::objects::Object;
 use crate::http::resumable_upload_client::ResumableUploadClient;
+use crate::http::retry::RetryConfig;
 use crate::http::{
     bucket_access_controls, buckets, check_response_status, default_object_access_controls, hmac_keys, notifications,
-    object_access_controls, objects, Error,
+    object_access_controls, objects, request_span, retry, Error, API_CLIENT, USER_AGENT,
 };
 
 pub const SCOPES: [&str; 2] = [
@@ -68,12 +75,20 @@ pub const SCOPES: [&str; 2] = [
     "https://www.googleapis.com/auth/devstorage.full_control",
 ];
 
+/// The header of the project billed for the quota of the requests, same as `google_cloud_gax::conn::QUOTA_PROJECT_HEADER`.
+/// It is defined here since `google-cloud-gax` is an optional dependency.
+const QUOTA_PROJECT_HEADER: &str = "x-goog-user-project";
+
 #[derive(Clone)]
 pub struct StorageClient {
     ts: Option<Arc<dyn TokenSource>>,
     v1_endpoint: String,
     v1_upload_endpoint: String,
     http: reqwest_middleware::ClientWithMiddleware,
+    quota_project: Option<String>,
+    retry: Option<RetryConfig>,
+    api_client: String,
+    user_agent: String,
 }
 
 impl StorageClient {
@@ -87,6 +102,53 @@ impl StorageClient {
             v1_endpoint: format!("{endpoint}/storage/v1"),
             v1_upload_endpoint: format!("{endpoint}/upload/storage/v1"),
             http,
+            quota_project: None,
+            retry: None,
+            api_client: API_CLIENT.to_string(),
+            user_agent: USER_AGENT.to_string(),
+        }
+    }
+
+    /// Sets the project billed for the quota of the requests.
+    pub(crate) fn with_quota_project(mut self, value: Option<String>) -> Self {
+        self.quota_project = value;
+        self
+    }
+
+    /// Appends the token of the application to the `user-agent` and `x-goog-api-client` headers.
+    pub(crate) fn with_user_agent(mut self, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            self.api_client = format!("{API_CLIENT} {value}");
+            self.user_agent = format!("{USER_AGENT} {value}");
+        }
+        self
+    }
+
+    /// Returns the client which retries the requests with the given config instead of the one of the `ClientConfig`.
+    /// The retry is disabled with `None`.
+    ///
+    /// ```
+    /// use google_cloud_storage::client::Client;
+    /// use google_cloud_storage::http::objects::download::Range;
+    /// use google_cloud_storage::http::objects::get::GetObjectRequest;
+    /// use google_cloud_storage::http::retry::RetryConfig;
+    ///
+    /// async fn run(client:Client) {
+    ///     let retry = RetryConfig {
+    ///         max_attempts: 10,
+    ///         ..Default::default()
+    ///     };
+    ///     let result = client.with_retry(Some(retry)).download_object(&GetObjectRequest{
+    ///         bucket: "bucket".to_string(),
+    ///         object: "object".to_string(),
+    ///         ..Default::default()
+    ///     }, &Range::default()).await;
+    /// }
+    /// ```
+    pub fn with_retry(&self, value: Option<RetryConfig>) -> Self {
+        Self {
+            retry: value,
+            ..self.clone()
         }
     }
 
@@ -982,10 +1044,18 @@ impl StorageClient {
     #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub async fn download_object(&self, req: &GetObjectRequest, range: &Range) -> Result<Vec<u8>, Error> {
         let builder = objects::download::build(self.v1_endpoint.as_str(), &self.http, req, range);
-        let request = self.with_headers(builder).await?;
-        let response = request.send().await?;
-        let response = check_response_status(response).await?;
-        Ok(response.bytes().await?.to_vec())
+        let request = self.with_headers(builder).await?.build()?;
+        let (partial, data) = self
+            .execute_with(request, |response| async move {
+                let partial = response.status() == StatusCode::PARTIAL_CONTENT;
+                Ok((partial, response.bytes().await?.to_vec()))
+            })
+            .await?;
+        if partial || range.is_full() {
+            Ok(data)
+        } else {
+            Ok(range.slice(data))
+        }
     }
 
     /// Download the object.
@@ -1016,10 +1086,91 @@ impl StorageClient {
         range: &Range,
     ) -> Result<impl Stream<Item = Result<bytes::Bytes, Error>>, Error> {
         let builder = objects::download::build(self.v1_endpoint.as_str(), &self.http, req, range);
-        let request = self.with_headers(builder).await?;
-        let response = request.send().await?;
-        let response = check_response_status(response).await?;
-        Ok(response.bytes_stream().map_err(Error::from))
+        let request = self.with_headers(builder).await?.build()?;
+        let response = self.execute(request).await?;
+        // The server responds with the whole object instead of 206 Partial Content when it ignores the range.
+        let range = if response.status() == StatusCode::PARTIAL_CONTENT {
+            Range::default()
+        } else {
+            *range
+        };
+        let len = response.content_length();
+        range
+            .slice_stream(response.bytes_stream().map_err(Error::from), len)
+            .ok_or(Error::InapplicableRange(range))
+    }
+
+    /// Download the object into the writer and returns the metadata of the downloaded object.
+    /// The generation is fixed by the metadata fetched before downloading, so that the content always matches it.
+    /// When the whole object is downloaded, its CRC32C checksum is verified against the metadata.
+    ///
+    /// ```
+    /// use google_cloud_storage::client::Client;
+    /// use google_cloud_storage::http::objects::get::GetObjectRequest;
+    /// use google_cloud_storage::http::objects::download::Range;
+    ///
+    /// async fn run(client:Client) {
+    ///     let mut writer = Vec::new();
+    ///     let result = client.download_to_writer(&GetObjectRequest{
+    ///         bucket: "bucket".to_string(),
+    ///         object: "object".to_string(),
+    ///         ..Default::default()
+    ///     }, &Range::default(), &mut writer).await;
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn download_to_writer<W: AsyncWrite + Unpin>(
+        &self,
+        req: &GetObjectRequest,
+        range: &Range,
+        writer: &mut W,
+    ) -> Result<Object, Error> {
+        let object = self.get_object(req).await?;
+        let req = GetObjectRequest {
+            generation: Some(object.generation),
+            ..req.clone()
+        };
+        // The served content differs from the stored one when decompressive transcoding occurs.
+        let verify = range.is_full() && object.content_encoding.as_deref() != Some("gzip");
+        let mut crc32c = 0;
+        let mut stream = self.download_streamed_object(&req, range).await?;
+        while let Some(chunk) = stream.try_next().await? {
+            if verify {
+                crc32c = crc32c::crc32c_append(crc32c, &chunk);
+            }
+            writer.write_all(&chunk).await?;
+        }
+        writer.flush().await?;
+        if verify {
+            if let Some(expected) = &object.crc32c {
+                let actual = BASE64_STANDARD.encode(crc32c.to_be_bytes());
+                if actual != *expected {
+                    return Err(Error::ChecksumMismatch(expected.to_string(), actual));
+                }
+            }
+        }
+        Ok(object)
+    }
+
+    /// Download the whole object into the file at the path and returns the metadata of the downloaded object.
+    /// The file is created if it does not exist, and truncated if it does.
+    ///
+    /// ```
+    /// use google_cloud_storage::client::Client;
+    /// use google_cloud_storage::http::objects::get::GetObjectRequest;
+    ///
+    /// async fn run(client:Client) {
+    ///     let result = client.download_to_file(&GetObjectRequest{
+    ///         bucket: "bucket".to_string(),
+    ///         object: "object".to_string(),
+    ///         ..Default::default()
+    ///     }, "/tmp/object").await;
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn download_to_file(&self, req: &GetObjectRequest, path: impl AsRef<Path>) -> Result<Object, Error> {
+        let mut file = File::create(path).await?;
+        self.download_to_writer(req, &Range::default(), &mut file).await
     }
 
     /// Uploads the object.
@@ -1213,19 +1364,24 @@ impl StorageClient {
         self.upload_object(req, Body::wrap_stream(data), upload_type).await
     }
 
-    /// Patches the object.
+    /// Patches the object metadata. Only the fields set in `metadata` are updated.
     /// https://cloud.google.com/storage/docs/json_api/v1/objects/patch
     ///
     /// ```
     /// use google_cloud_storage::client::Client;
-    /// use google_cloud_storage::http::objects::patch::PatchObjectRequest;
+    /// use google_cloud_storage::http::objects::patch::{ObjectPatchConfig, PatchObjectRequest};
     ///
     ///
-    /// async fn run(client:Client) {
+    /// async fn run(client:Client, metageneration: i64) {
     ///
     ///     let result = client.patch_object(&PatchObjectRequest{
     ///         bucket: "bucket".to_string(),
     ///         object: "object".to_string(),
+    ///         if_metageneration_match: Some(metageneration),
+    ///         metadata: Some(ObjectPatchConfig {
+    ///             cache_control: Some("public, max-age=60".to_string()),
+    ///             ..Default::default()
+    ///         }),
     ///         ..Default::default()
     ///     }).await;
     /// }
@@ -1292,6 +1448,46 @@ impl StorageClient {
         self.send(builder).await
     }
 
+    /// Rewrites the object repeatedly with the returned rewrite token until the rewrite is done.
+    /// The response of each call is passed to `progress`, which can report the `total_bytes_rewritten`.
+    /// Returns the last response, whose `resource` is the rewritten object.
+    ///
+    /// ```
+    /// use google_cloud_storage::client::Client;
+    /// use google_cloud_storage::http::objects::rewrite::RewriteObjectRequest;
+    ///
+    /// async fn run(client:Client) {
+    ///     let result = client.rewrite_object_until_done(&RewriteObjectRequest{
+    ///         source_bucket: "bucket1".to_string(),
+    ///         source_object: "object".to_string(),
+    ///         destination_bucket: "bucket2".to_string(),
+    ///         destination_object: "object1".to_string(),
+    ///         ..Default::default()
+    ///     }, |res| {
+    ///         println!("{}/{}", res.total_bytes_rewritten, res.object_size);
+    ///     }).await;
+    /// }
+    /// ```
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub async fn rewrite_object_until_done<F>(
+        &self,
+        req: &RewriteObjectRequest,
+        mut progress: F,
+    ) -> Result<RewriteObjectResponse, Error>
+    where
+        F: FnMut(&RewriteObjectResponse),
+    {
+        let mut req = req.clone();
+        loop {
+            let response = self.rewrite_object(&req).await?;
+            progress(&response);
+            if response.done {
+                return Ok(response);
+            }
+            req.rewrite_token.clone_from(&response.rewrite_token);
+        }
+    }
+
     /// Composes the object.
     /// https://cloud.google.com/storage/docs/json_api/v1/objects/compose
     ///
@@ -1324,8 +1520,12 @@ impl StorageClient {
 
     async fn with_headers(&self, builder: RequestBuilder) -> Result<RequestBuilder, Error> {
         let builder = builder
-            .header("X-Goog-Api-Client", "rust")
-            .header(reqwest::header::USER_AGENT, "google-cloud-storage");
+            .header("X-Goog-Api-Client", &self.api_client)
+            .header(reqwest::header::USER_AGENT, &self.user_agent);
+        let builder = match &self.quota_project {
+            Some(project) => builder.header(QUOTA_PROJECT_HEADER, project),
+            None => builder,
+        };
         let builder = match &self.ts {
             Some(ts) => {
                 let token = ts.token().await.map_err(Error::TokenSource)?;
@@ -1336,36 +1536,91 @@ impl StorageClient {
         Ok(builder)
     }
 
+    /// Executes the request within the span of `request_span`.
+    /// The idempotent request is retried on the transient errors when the retry is configured.
+    async fn execute(&self, request: Request) -> Result<Response, Error> {
+        self.execute_with(request, |response| async move { Ok(response) }).await
+    }
+
+    /// Executes the request like `execute` and reads the response with `read`.
+    /// The idempotent request is also retried when the body fails to be read by a transient error.
+    async fn execute_with<T, F, Fut>(&self, mut request: Request, read: F) -> Result<T, Error>
+    where
+        F: Fn(Response) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let span = request_span(&request);
+        async {
+            let config = self.retry.as_ref().filter(|_| retry::is_idempotent(&request));
+            let mut attempts = 1;
+            let mut delay = config.map(|v| v.initial_delay).unwrap_or_default();
+            loop {
+                let next = config
+                    .filter(|v| attempts < v.max_attempts)
+                    .and_then(|_| request.try_clone());
+                let backoff = |retry_after| config.map_or(delay, |v| v.wait(delay, retry_after));
+                let (wait, result) = match self.http.execute(request).await {
+                    Ok(response) => {
+                        tracing::Span::current().record("http.response.status_code", response.status().as_u16());
+                        let wait = if retry::is_retryable_status(response.status()) {
+                            Some(backoff(retry::retry_after(response.headers())))
+                        } else {
+                            None
+                        };
+                        match check_response_status(response).await {
+                            Ok(response) => match read(response).await {
+                                Err(Error::HttpClient(e)) if retry::is_retryable_body_error(&e) => {
+                                    (Some(backoff(None)), Err(Error::HttpClient(e)))
+                                }
+                                result => (None, result),
+                            },
+                            Err(e) => (wait, Err(e)),
+                        }
+                    }
+                    Err(e) => (retry::is_retryable_error(&e).then(|| backoff(None)), Err(e.into())),
+                };
+                request = match (wait, next) {
+                    (Some(wait), Some(next)) => {
+                        tracing::debug!("retry the request after {:?}: attempts={}", wait, attempts);
+                        tokio::time::sleep(wait).await;
+                        attempts += 1;
+                        delay = config.map_or(delay, |v| v.next_delay(delay));
+                        next
+                    }
+                    _ => return result,
+                };
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
     async fn send_request<T>(&self, request: Request) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let response = self.http.execute(request).await?;
-        let response = check_response_status(response).await?;
-        Ok(response.json().await?)
+        self.execute_with(request, |response| async move { Ok(response.json().await?) })
+            .await
     }
 
     async fn send<T>(&self, builder: RequestBuilder) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let builder = self.with_headers(builder).await?;
-        let response = builder.send().await?;
-        let response = check_response_status(response).await?;
-        Ok(response.json().await?)
+        let request = self.with_headers(builder).await?.build()?;
+        self.execute_with(request, |response| async move { Ok(response.json().await?) })
+            .await
     }
 
     async fn send_get_empty(&self, builder: RequestBuilder) -> Result<(), Error> {
-        let builder = self.with_headers(builder).await?;
-        let response = builder.send().await?;
-        check_response_status(response).await?;
+        let request = self.with_headers(builder).await?.build()?;
+        self.execute(request).await?;
         Ok(())
     }
 
     async fn send_get_url(&self, builder: RequestBuilder) -> Result<String, Error> {
-        let builder = self.with_headers(builder).await?;
-        let response = builder.send().await?;
-        let response = check_response_status(response).await?;
+        let request = self.with_headers(builder).await?.build()?;
+        let response = self.execute(request).await?;
         Ok(String::from_utf8_lossy(response.headers()[LOCATION].as_bytes()).into_owned())
     }
 }
@@ -1429,11 +1684,13 @@ pub(crate) mod test {
     use crate::http::objects::download::Range;
     use crate::http::objects::get::GetObjectRequest;
     use crate::http::objects::list::ListObjectsRequest;
+    use crate::http::objects::patch::{ObjectPatchConfig, PatchObjectRequest};
     use crate::http::objects::rewrite::RewriteObjectRequest;
     use crate::http::objects::upload::{Media, UploadObjectRequest, UploadType};
-    use crate::http::objects::{Object, SourceObjects};
+    use crate::http::objects::{Encryption, Object, SourceObjects};
     use crate::http::resumable_upload_client::{ChunkSize, UploadStatus, UploadedRange};
     use crate::http::storage_client::{StorageClient, SCOPES};
+    use crate::http::Error;
 
     #[ctor::ctor]
     fn init() {
@@ -1532,6 +1789,8 @@ pub(crate) mod test {
                             condition: Some(lifecycle::rule::Condition {
                                 age: Some(365),
                                 is_live: Some(true),
+                                matches_prefix: Some(vec!["tmp/".to_string()]),
+                                matches_suffix: Some(vec![".log".to_string()]),
                                 ..Default::default()
                             }),
                         }],
@@ -1552,6 +1811,41 @@ pub(crate) mod test {
             .unwrap();
 
         assert_eq!(found.location.as_str(), "ASIA-NORTHEAST1");
+        let condition = found.lifecycle.unwrap().rule[0].condition.clone().unwrap();
+        assert_eq!(condition.matches_prefix, Some(vec!["tmp/".to_string()]));
+        assert_eq!(condition.matches_suffix, Some(vec![".log".to_string()]));
+
+        let lifecycle = Lifecycle {
+            rule: vec![lifecycle::Rule {
+                action: Some(lifecycle::rule::Action {
+                    r#type: lifecycle::rule::ActionType::SetStorageClass,
+                    storage_class: Some("COLDLINE".to_string()),
+                }),
+                condition: Some(lifecycle::rule::Condition {
+                    num_newer_versions: Some(3),
+                    ..Default::default()
+                }),
+            }],
+        };
+        let patched = client
+            .patch_bucket(&PatchBucketRequest {
+                bucket: bucket.name.to_string(),
+                metadata: Some(BucketPatchConfig {
+                    lifecycle: Some(lifecycle.clone()),
+                    cors: Some(vec![Cors {
+                        origin: vec!["https://example.com".to_string()],
+                        method: vec!["PUT".to_string()],
+                        response_header: vec!["Content-Type".to_string()],
+                        max_age_seconds: 3600,
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(patched.lifecycle, Some(lifecycle));
+        assert_eq!(patched.cors.unwrap()[0].origin, vec!["https://example.com".to_string()]);
 
         let entity = format!("user-{}", email);
         let patched = client
@@ -1934,6 +2228,81 @@ pub(crate) mod test {
 
         let downloaded = download(Range::default()).await;
         assert_eq!(downloaded, vec![1, 2, 3, 4, 5, 6, 7]);
+
+        // patch
+        let mut metadata = HashMap::<String, String>::new();
+        metadata.insert("key2".to_string(), "value2".to_string());
+        let patch = |metageneration: i64| PatchObjectRequest {
+            bucket: uploaded.bucket.clone(),
+            object: uploaded.name.clone(),
+            if_metageneration_match: Some(metageneration),
+            metadata: Some(ObjectPatchConfig {
+                cache_control: Some("no-cache".to_string()),
+                metadata: Some(metadata.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let patched = client.patch_object(&patch(object.metageneration)).await.unwrap();
+        assert_eq!(patched.metageneration, object.metageneration + 1);
+        assert_eq!(patched.cache_control.unwrap(), "no-cache".to_string());
+        assert_eq!(patched.content_type.unwrap(), "text/plain".to_string());
+        let patched_metadata = patched.metadata.unwrap();
+        assert_eq!(patched_metadata.get("key1").unwrap().clone(), "value1".to_string());
+        assert_eq!(patched_metadata.get("key2").unwrap().clone(), "value2".to_string());
+
+        // stale metageneration
+        match client.patch_object(&patch(object.metageneration)).await {
+            Err(Error::Response(e)) => assert_eq!(e.code, 412),
+            result => panic!("precondition must fail: {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    pub async fn crud_object_with_encryption() {
+        let (client, project, _) = client().await;
+        let bucket_name = bucket_name(&project, "object");
+        let encryption = Encryption::aes256(&[1u8; 32]);
+        let uploaded = client
+            .upload_object(
+                &UploadObjectRequest {
+                    bucket: bucket_name.to_string(),
+                    encryption: Some(encryption.clone()),
+                    ..Default::default()
+                },
+                vec![1, 2, 3, 4, 5],
+                &UploadType::Simple(Media::new("test1_csek")),
+            )
+            .await
+            .unwrap();
+
+        let mut req = GetObjectRequest {
+            bucket: uploaded.bucket.clone(),
+            object: uploaded.name.clone(),
+            ..Default::default()
+        };
+        assert!(client.download_object(&req, &Range::default()).await.is_err());
+
+        req.encryption = Some(encryption);
+        let downloaded = client.download_object(&req, &Range(Some(1), None)).await.unwrap();
+        assert_eq!(downloaded, vec![2, 3, 4, 5]);
+
+        let mut streamed = client.download_streamed_object(&req, &Range::default()).await.unwrap();
+        let mut data = Vec::new();
+        while let Some(v) = streamed.next().await {
+            data.extend_from_slice(v.unwrap().chunk());
+        }
+        assert_eq!(data, vec![1, 2, 3, 4, 5]);
+
+        client
+            .delete_object(&DeleteObjectRequest {
+                bucket: uploaded.bucket,
+                object: uploaded.name,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
@@ -2044,6 +2413,26 @@ pub(crate) mod test {
             .await
             .unwrap();
 
+        let mut calls = 0;
+        let rewritten = client
+            .rewrite_object_until_done(
+                &RewriteObjectRequest {
+                    destination_bucket: bucket_name.to_string(),
+                    destination_object: format!("{}_rewrite_until_done", uploaded.name),
+                    source_bucket: bucket_name.to_string(),
+                    source_object: uploaded.name.to_string(),
+                    max_bytes_rewritten_per_call: Some(1024 * 1024),
+                    ..Default::default()
+                },
+                |_| calls += 1,
+            )
+            .await
+            .unwrap();
+        assert!(calls > 0);
+        assert!(rewritten.done);
+        assert_eq!(rewritten.total_bytes_rewritten, rewritten.object_size);
+        assert_eq!(rewritten.resource.unwrap().size, uploaded.size);
+
         let _composed = client
             .compose_object(&ComposeObjectRequest {
                 bucket: bucket_name.to_string(),
@@ -2065,6 +2454,53 @@ pub(crate) mod test {
             .unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    pub async fn download_to_writer() {
+        let (client, project, _) = client().await;
+        let bucket_name = bucket_name(&project, "object");
+        let file_name = format!("writer_{}", time::OffsetDateTime::now_utc().unix_timestamp());
+        let uploaded = client
+            .upload_object(
+                &UploadObjectRequest {
+                    bucket: bucket_name.to_string(),
+                    ..Default::default()
+                },
+                "hello world",
+                &UploadType::Simple(Media::new(file_name)),
+            )
+            .await
+            .unwrap();
+        let req = GetObjectRequest {
+            bucket: uploaded.bucket.to_string(),
+            object: uploaded.name.to_string(),
+            ..Default::default()
+        };
+
+        let mut data = Vec::new();
+        let object = client
+            .download_to_writer(&req, &Range::default(), &mut data)
+            .await
+            .unwrap();
+        assert_eq!("hello world", String::from_utf8_lossy(data.as_slice()));
+        assert_eq!(object.generation, uploaded.generation);
+        assert_eq!(object.crc32c, uploaded.crc32c);
+
+        // resume from the middle of the object
+        let mut data = b"hello".to_vec();
+        client
+            .download_to_writer(&req, &Range(Some(5), None), &mut data)
+            .await
+            .unwrap();
+        assert_eq!("hello world", String::from_utf8_lossy(data.as_slice()));
+
+        let path = std::env::temp_dir().join(&uploaded.name);
+        let object = client.download_to_file(&req, &path).await.unwrap();
+        assert_eq!(object.size, 11);
+        assert_eq!("hello world", std::fs::read_to_string(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     pub async fn streamed_object() {
@@ -61,6 +61,7 @@
 //! use google_cloud_storage::client::Client;
 //! use google_cloud_storage::client::ClientConfig;
 //! use google_cloud_storage::sign::SignedURLOptions;
+//! use google_cloud_storage::sign::SignBy;
 //! use google_cloud_storage::sign::SignedURLMethod;
 //! use google_cloud_storage::http::Error;
 //! use google_cloud_storage::http::objects::download::Range;
@@ -90,6 +91,13 @@
 //!         ..Default::default()
 //!    }, &Range::default()).await;
 //!
+//!     // Download the last 1KB of the file
+//!     let tail = client.download_object(&GetObjectRequest {
+//!         bucket: "bucket".to_string(),
+//!         object: "file.png".to_string(),
+//!         ..Default::default()
+//!    }, &Range::suffix(1024)).await;
+//!
 //!     // Create signed url with the default key and google-access-id of the client
 //!     let url_for_download = client.signed_url("bucket", "foo.txt", None, None, SignedURLOptions::default());
 //!     let url_for_upload = client.signed_url("bucket", "foo.txt", None, None, SignedURLOptions {
@@ -97,6 +105,13 @@
 //!         ..Default::default()
 //!     });
 //!
+//!     // Create signed url with the HMAC key
+//!     let sign_by = SignBy::Hmac {
+//!         access_id: "access_id".to_string(),
+//!         secret: "secret".to_string(),
+//!     };
+//!     let url_by_hmac = client.signed_url("bucket", "foo.txt", None, Some(sign_by), SignedURLOptions::default());
+//!
 //!     Ok(())
 //! }
 //! ```
@@ -8,6 +8,7 @@ use once_cell::sync::Lazy;
 use pkcs8::der::pem::PemLabel;
 use pkcs8::SecretDocument;
 use regex::Regex;
+use ring::hmac;
 use sha2::{Digest, Sha256};
 use time::format_description::well_known::iso8601::{EncodedConfig, TimePrecision};
 use time::format_description::well_known::{self, Iso8601};
@@ -70,6 +71,20 @@ impl URLStyle for PathStyle {
 pub enum SignBy {
     PrivateKey(Vec<u8>),
     SignBytes,
+    /// Signs with the HMAC key. The access_id is used as the google_access_id.
+    Hmac {
+        access_id: String,
+        secret: String,
+    },
+}
+
+impl SignBy {
+    fn algorithm(&self) -> &'static str {
+        match self {
+            SignBy::Hmac { .. } => "GOOG4-HMAC-SHA256",
+            _ => "GOOG4-RSA-SHA256",
+        }
+    }
 }
 
 impl Debug for SignBy {
@@ -77,6 +92,7 @@ impl Debug for SignBy {
         match self {
             SignBy::PrivateKey(_) => f.write_str("private_key"),
             SignBy::SignBytes => f.write_str("sign_bytes"),
+            SignBy::Hmac { access_id, .. } => write!(f, "hmac({access_id})"),
         }
     }
 }
@@ -107,6 +123,8 @@ pub struct SignedURLOptions {
     /// Headers is a list of extension headers the client must provide
     /// in order to use the generated signed URL. Each must be a string of the
     /// form "key:values", with multiple values separated by a semicolon.
+    /// The headers are included in the canonical request and X-Goog-SignedHeaders.
+    /// e.g. "x-goog-content-length-range:0,1048576" restricts the size of the upload.
     /// Optional.
     pub headers: Vec<String>,
 
@@ -169,6 +187,7 @@ pub(crate) fn create_signed_buffer(
     bucket: &str,
     name: &str,
     google_access_id: &str,
+    sign_by: &SignBy,
     opts: &SignedURLOptions,
 ) -> Result<(Vec<u8>, Url), SignedURLError> {
     validate_options(opts)?;
@@ -206,15 +225,12 @@ pub(crate) fn create_signed_buffer(
         .encode();
 
     let timestamp = start_time.format(&Iso8601::<CONFIG>).unwrap();
-    let credential_scope = format!(
-        "{}/auto/storage/goog4_request",
-        start_time.format(format_description!("[year][month][day]")).unwrap()
-    );
+    let credential_scope = format!("{}/auto/storage/goog4_request", signing_date(start_time));
 
     // append query parameters
     {
         let mut query_parameters = [
-            ("X-Goog-Algorithm", "GOOG4-RSA-SHA256"),
+            ("X-Goog-Algorithm", sign_by.algorithm()),
             ("X-Goog-Credential", &format!("{}/{}", google_access_id, credential_scope)),
             ("X-Goog-Date", &timestamp),
             ("X-Goog-Expires", opts.expires.as_secs().to_string().as_str()),
@@ -283,18 +299,39 @@ pub(crate) fn create_signed_buffer(
     // create signed buffer
     let hex_digest = hex::encode(Sha256::digest(buffer));
     let mut signed_buffer: Vec<u8> = vec![];
-    signed_buffer.extend_from_slice("GOOG4-RSA-SHA256\n".as_bytes());
+    signed_buffer.extend_from_slice(format!("{}\n", sign_by.algorithm()).as_bytes());
     signed_buffer.extend_from_slice(format!("{timestamp}\n").as_bytes());
     signed_buffer.extend_from_slice(format!("{credential_scope}\n").as_bytes());
     signed_buffer.extend_from_slice(hex_digest.as_bytes());
     Ok((signed_buffer, builder))
 }
 
+/// The date part of the credential scope, in format YYYYMMDD.
+pub(crate) fn signing_date(start_time: OffsetDateTime) -> String {
+    start_time.format(format_description!("[year][month][day]")).unwrap()
+}
+
+/// Signs the buffer with the V4 signing key derived from the HMAC secret.
+/// https://cloud.google.com/storage/docs/authentication/signatures#signing-process
+pub(crate) fn sign_hmac(secret: &str, date: &str, buffer: &[u8]) -> Vec<u8> {
+    let sign = |key: &[u8], data: &[u8]| {
+        hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data)
+            .as_ref()
+            .to_vec()
+    };
+    let key = sign(format!("GOOG4{secret}").as_bytes(), date.as_bytes());
+    let key = sign(&key, b"auto");
+    let key = sign(&key, b"storage");
+    let key = sign(&key, b"goog4_request");
+    sign(&key, buffer)
+}
+
 fn v4_sanitize_headers(hdrs: &[String]) -> Vec<String> {
     let mut sanitized = HashMap::<String, Vec<String>>::new();
     for hdr in hdrs {
         let trimmed = hdr.trim().to_string();
-        let split: Vec<&str> = trimmed.split(':').collect();
+        // the value may contain ':' such as an URL.
+        let split: Vec<&str> = trimmed.splitn(2, ':').collect();
         if split.len() < 2 {
             continue;
         }
@@ -341,6 +378,173 @@ fn validate_options(opts: &SignedURLOptions) -> Result<(), SignedURLError> {
     Ok(())
 }
 
+/// PostPolicyCondition describes a constraint that the browser form upload must satisfy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PostPolicyCondition {
+    /// StartsWith checks that the form field (e.g. "$key", "$Content-Type") starts with the value.
+    StartsWith(String, String),
+    /// ContentLengthRange checks that the uploaded object size is within [min, max] bytes.
+    ContentLengthRange(u64, u64),
+}
+
+impl PostPolicyCondition {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            PostPolicyCondition::StartsWith(key, value) => serde_json::json!(["starts-with", key, value]),
+            PostPolicyCondition::ContentLengthRange(min, max) => {
+                serde_json::json!(["content-length-range", min, max])
+            }
+        }
+    }
+}
+
+/// PostPolicyFields describes the form fields the browser must send as is.
+/// Each field is also added to the policy document as an exact match condition.
+#[derive(Clone, Debug, Default)]
+pub struct PostPolicyFields {
+    pub acl: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_type: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    /// Metadata is the custom metadata of the object. The key must not contain "x-goog-meta-" prefix.
+    pub metadata: HashMap<String, String>,
+    pub status_code_on_success: Option<u16>,
+    pub redirect_to_url_on_success: Option<String>,
+}
+
+impl PostPolicyFields {
+    fn to_map(&self) -> BTreeMap<String, String> {
+        let mut fields = BTreeMap::new();
+        let mut insert = |key: &str, value: &Option<String>| {
+            if let Some(value) = value {
+                fields.insert(key.to_string(), value.to_string());
+            }
+        };
+        insert("acl", &self.acl);
+        insert("cache-control", &self.cache_control);
+        insert("content-type", &self.content_type);
+        insert("content-disposition", &self.content_disposition);
+        insert("content-encoding", &self.content_encoding);
+        insert("success_action_redirect", &self.redirect_to_url_on_success);
+        insert("success_action_status", &self.status_code_on_success.map(|v| v.to_string()));
+        for (k, v) in &self.metadata {
+            fields.insert(format!("x-goog-meta-{k}"), v.to_string());
+        }
+        fields
+    }
+}
+
+/// PostPolicyOptions allows you to restrict the browser upload with the signed POST policy.
+pub struct PostPolicyOptions {
+    /// StartTime is the time at which the policy starts being valid.
+    /// Defaults to the current time.
+    /// Optional.
+    pub start_time: Option<std::time::SystemTime>,
+
+    /// Expires is the duration of time, beginning at StartTime, within which
+    /// the policy is valid. The duration may be no more than 604800 seconds (7 days).
+    /// Required.
+    pub expires: std::time::Duration,
+
+    /// Fields specifies the form fields to be included in the form.
+    /// Optional.
+    pub fields: PostPolicyFields,
+
+    /// Conditions are the additional constraints of the upload such as content-length-range.
+    /// Optional.
+    pub conditions: Vec<PostPolicyCondition>,
+
+    /// Style provides options for the type of URL to use.
+    /// Optional.
+    pub style: Box<dyn URLStyle + Send + Sync>,
+
+    /// Insecure determines whether the URL should use HTTPS (default) or HTTP.
+    /// Optional.
+    pub insecure: bool,
+}
+
+impl Default for PostPolicyOptions {
+    fn default() -> Self {
+        Self {
+            start_time: None,
+            expires: std::time::Duration::from_secs(600),
+            fields: PostPolicyFields::default(),
+            conditions: vec![],
+            style: Box::new(PathStyle {}),
+            insecure: false,
+        }
+    }
+}
+
+/// PostPolicy is the URL and the form fields the browser must use to upload the object.
+#[derive(Clone, Debug)]
+pub struct PostPolicy {
+    /// URL is the destination of the multipart form POST.
+    pub url: String,
+    /// Fields are the form fields which must be included in the multipart form.
+    /// The file must be the last field of the form.
+    pub fields: HashMap<String, String>,
+}
+
+pub(crate) fn create_post_policy(
+    bucket: &str,
+    name: &str,
+    google_access_id: &str,
+    sign_by: &SignBy,
+    opts: &PostPolicyOptions,
+) -> Result<(String, Url, BTreeMap<String, String>), SignedURLError> {
+    if opts.expires.is_zero() {
+        return Err(InvalidOption("storage: expires cannot be zero"));
+    }
+    if opts.expires > Duration::from_secs(ONE_WEEK_IN_SECONDS) {
+        return Err(InvalidOption("storage: expires must be within seven days from now"));
+    }
+    let start_time: OffsetDateTime = opts.start_time.unwrap_or_else(SystemTime::now).into();
+    let expiration = start_time + opts.expires;
+
+    const CONFIG: EncodedConfig = well_known::iso8601::Config::DEFAULT
+        .set_use_separators(false)
+        .set_time_precision(TimePrecision::Second { decimal_digits: None })
+        .encode();
+    let timestamp = start_time.format(&Iso8601::<CONFIG>).unwrap();
+    let credential_scope = format!("{}/auto/storage/goog4_request", signing_date(start_time));
+
+    let mut fields = opts.fields.to_map();
+    fields.insert("key".to_string(), name.to_string());
+    fields.insert("x-goog-date".to_string(), timestamp);
+    fields.insert(
+        "x-goog-credential".to_string(),
+        format!("{}/{}", google_access_id, credential_scope),
+    );
+    fields.insert("x-goog-algorithm".to_string(), sign_by.algorithm().to_string());
+
+    let mut conditions: Vec<serde_json::Value> = fields
+        .iter()
+        .map(|(k, v)| serde_json::json!({ k.as_str(): v }))
+        .collect();
+    conditions.push(serde_json::json!({ "bucket": bucket }));
+    conditions.extend(opts.conditions.iter().map(|c| c.to_json()));
+
+    let policy = serde_json::json!({
+        "conditions": conditions,
+        "expiration": expiration
+            .format(format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z"))
+            .unwrap(),
+    });
+    tracing::trace!("policy={}", policy);
+    let encoded_policy = BASE64_STANDARD.encode(policy.to_string());
+
+    let host = opts.style.host(bucket);
+    let mut url = if opts.insecure {
+        Url::parse(&format!("http://{host}"))
+    } else {
+        Url::parse(&format!("https://{host}"))
+    }?;
+    url.set_path(&format!("{}/", opts.style.path(bucket, "")));
+    Ok((encoded_policy, url, fields))
+}
+
 pub struct RsaKeyPair {
     inner: ring::signature::RsaKeyPair,
 }
@@ -375,12 +579,16 @@ mod test {
     use std::collections::HashMap;
     use std::time::Duration;
 
+    use base64::prelude::*;
     use serial_test::serial;
 
     use crate::http::storage_client::test::bucket_name;
     use google_cloud_auth::credentials::CredentialsFile;
 
-    use crate::sign::{create_signed_buffer, SignedURLOptions};
+    use crate::sign::{
+        create_post_policy, create_signed_buffer, sign_hmac, PostPolicyCondition, PostPolicyFields, PostPolicyOptions,
+        SignBy, SignedURLMethod, SignedURLOptions,
+    };
 
     #[tokio::test]
     #[serial]
@@ -401,9 +609,97 @@ mod test {
             &bucket_name(&file.project_id.unwrap(), "object"),
             "test1",
             &google_access_id,
+            &SignBy::SignBytes,
             &opts,
         )
         .unwrap();
         assert_eq!(signed_buffer.len(), 134)
     }
+
+    #[test]
+    fn create_post_policy_test() {
+        let opts = PostPolicyOptions {
+            start_time: Some(std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            expires: Duration::from_secs(3600),
+            fields: PostPolicyFields {
+                content_type: Some("image/png".to_string()),
+                metadata: HashMap::from([("owner".to_string(), "user1".to_string())]),
+                ..Default::default()
+            },
+            conditions: vec![PostPolicyCondition::ContentLengthRange(0, 1024)],
+            ..Default::default()
+        };
+        let (policy, url, fields) =
+            create_post_policy("bucket", "object.png", "sa@example.com", &SignBy::SignBytes, &opts).unwrap();
+        assert_eq!(url.as_str(), "https://storage.googleapis.com/bucket/");
+        assert_eq!(fields["key"], "object.png");
+        assert_eq!(fields["content-type"], "image/png");
+        assert_eq!(fields["x-goog-meta-owner"], "user1");
+        assert_eq!(fields["x-goog-date"], "20231114T221320Z");
+        assert_eq!(
+            fields["x-goog-credential"],
+            "sa@example.com/20231114/auto/storage/goog4_request"
+        );
+
+        let decoded = BASE64_STANDARD.decode(policy).unwrap();
+        let policy: serde_json::Value = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(policy["expiration"], "2023-11-14T23:13:20Z");
+        let conditions = policy["conditions"].as_array().unwrap();
+        assert!(conditions.contains(&serde_json::json!({"bucket": "bucket"})));
+        assert!(conditions.contains(&serde_json::json!(["content-length-range", 0, 1024])));
+    }
+
+    #[test]
+    fn create_signed_buffer_with_headers_test() {
+        let opts = SignedURLOptions {
+            method: SignedURLMethod::PUT,
+            start_time: Some(std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            content_type: Some("image/png".to_string()),
+            headers: vec![
+                "X-Goog-Content-Length-Range: 0,1024".to_string(),
+                "x-goog-meta-source:https://example.com".to_string(),
+            ],
+            query_parameters: HashMap::from([("upload-id".to_string(), vec!["a b".to_string()])]),
+            ..Default::default()
+        };
+        let (_, url) = create_signed_buffer("bucket", "object", "sa@example.com", &SignBy::SignBytes, &opts).unwrap();
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(
+            query["X-Goog-SignedHeaders"],
+            "content-type;host;x-goog-content-length-range;x-goog-meta-source"
+        );
+        assert_eq!(query["upload-id"], "a b");
+        assert_eq!(query["X-Goog-Date"], "20231114T221320Z");
+        assert_eq!(url.path(), "/bucket/object");
+    }
+
+    #[test]
+    fn create_signed_buffer_with_hmac_test() {
+        let sign_by = SignBy::Hmac {
+            access_id: "GOOG1EXAMPLE".to_string(),
+            secret: "secret".to_string(),
+        };
+        let opts = SignedURLOptions {
+            start_time: Some(std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            ..Default::default()
+        };
+        let (signed_buffer, url) = create_signed_buffer("bucket", "object", "GOOG1EXAMPLE", &sign_by, &opts).unwrap();
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["X-Goog-Algorithm"], "GOOG4-HMAC-SHA256");
+        assert_eq!(query["X-Goog-Credential"], "GOOG1EXAMPLE/20231114/auto/storage/goog4_request");
+        // the canonical request is
+        // GET\n/bucket/object\n{query}\nhost:storage.googleapis.com\n\nhost\nUNSIGNED-PAYLOAD
+        assert_eq!(
+            String::from_utf8(signed_buffer.clone()).unwrap(),
+            "GOOG4-HMAC-SHA256\n20231114T221320Z\n20231114/auto/storage/goog4_request\n\
+             01eb59900e665f8178c93e32fe2cdf2ccc739b25407a245be3d847539cd2a590"
+        );
+
+        let signature = sign_hmac("secret", "20231114", &signed_buffer);
+        assert_eq!(
+            hex::encode(&signature),
+            "274fe63b268470445c18b3ed19c1cc7543390a1d0c4da2b158240b8b1b4981d6"
+        );
+        assert_ne!(signature, sign_hmac("secret", "20231115", &signed_buffer));
+    }
 }